@@ -0,0 +1,164 @@
+//! Token budgets for `context --max-tokens`. Decisions are ranked must before
+//! should before may, then strategic before tactical before operational, then
+//! by relevance, and kept in that order until the next one would not fit.
+//! Token counts are estimated from the rendered output at four characters per
+//! token, which is close enough for common tokenizers on English prose and
+//! needs no model files.
+
+use std::cmp::Ordering;
+use std::collections::HashSet;
+
+use crate::model::{Decision, Level, Weight};
+
+/// Rough token count of a piece of text.
+pub fn estimate(text: &str) -> usize {
+    text.chars().count().div_ceil(4)
+}
+
+/// How closely a decision's scope matches the requested one: the number of
+/// leading path segments they share, so for `billing/invoices` a decision
+/// scoped there beats one scoped to `billing`, which beats an unscoped one.
+pub fn scope_relevance(d: &Decision, scope: Option<&str>) -> f64 {
+    match (scope, d.scope.as_deref()) {
+        (Some(wanted), Some(own)) => own
+            .split('/')
+            .zip(wanted.split('/'))
+            .take_while(|(a, b)| a == b)
+            .count() as f64,
+        _ => 0.0,
+    }
+}
+
+#[derive(Debug)]
+pub struct Selection {
+    pub kept: Vec<Decision>,
+    /// Dropped decisions, highest priority first
+    pub omitted: Vec<Decision>,
+    pub max_tokens: usize,
+    pub used_tokens: usize,
+}
+
+pub fn weight_rank(w: &Weight) -> u8 {
+    match w {
+        Weight::Must => 0,
+        Weight::Should => 1,
+        Weight::May => 2,
+    }
+}
+
+fn level_rank(l: &Level) -> u8 {
+    match l {
+        Level::Strategic => 0,
+        Level::Tactical => 1,
+        Level::Operational => 2,
+    }
+}
+
+/// Keep the highest-priority decisions that fit in `max_tokens`, given what
+/// the output costs without them (`overhead`) and what each one's entry costs
+/// (`cost`). Decisions in `binding` (entailed by a must) rank as must. Selection stops at the first
+/// decision that does not fit, so a may is never kept while a must is dropped.
+/// Kept decisions stay in their original order.
+pub fn select(
+    decisions: Vec<Decision>,
+    max_tokens: usize,
+    overhead: usize,
+    binding: &HashSet<String>,
+    relevance: impl Fn(&Decision) -> f64,
+    cost: impl Fn(&Decision) -> usize,
+) -> Selection {
+    let weight = |d: &Decision| {
+        if binding.contains(&d.id) {
+            0
+        } else {
+            weight_rank(&d.weight)
+        }
+    };
+    let mut ranked: Vec<(usize, &Decision)> = decisions.iter().enumerate().collect();
+    ranked.sort_by(|(ia, a), (ib, b)| {
+        weight(a)
+            .cmp(&weight(b))
+            .then_with(|| level_rank(&a.level).cmp(&level_rank(&b.level)))
+            .then_with(|| relevance(b).partial_cmp(&relevance(a)).unwrap_or(Ordering::Equal))
+            .then_with(|| ia.cmp(ib))
+    });
+
+    let mut used_tokens = overhead;
+    let mut keep = vec![false; decisions.len()];
+    let mut omitted_order = Vec::new();
+    for (i, d) in ranked {
+        let cost = cost(d);
+        if omitted_order.is_empty() && used_tokens + cost <= max_tokens {
+            used_tokens += cost;
+            keep[i] = true;
+        } else {
+            omitted_order.push(i);
+        }
+    }
+
+    let omitted = omitted_order.iter().map(|&i| decisions[i].clone()).collect();
+    let kept = decisions
+        .into_iter()
+        .zip(keep)
+        .filter_map(|(d, k)| k.then_some(d))
+        .collect();
+    Selection {
+        kept,
+        omitted,
+        max_tokens,
+        used_tokens,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decision(id: &str, level: Level, weight: Weight) -> Decision {
+        Decision::test(id).with_level(level).with_weight(weight)
+    }
+
+    fn ids(decisions: &[Decision]) -> Vec<&str> {
+        decisions.iter().map(|d| d.id.as_str()).collect()
+    }
+
+    fn decisions() -> Vec<Decision> {
+        vec![
+            decision("d-1", Level::Strategic, Weight::May),
+            decision("d-2", Level::Operational, Weight::Should),
+            decision("d-3", Level::Operational, Weight::Must),
+            decision("d-4", Level::Strategic, Weight::Must),
+            decision("d-5", Level::Operational, Weight::Should).with_scope("api/orders"),
+        ]
+    }
+
+    #[test]
+    fn keeps_highest_priority_within_budget() {
+        let sel = select(decisions(), 40, 10, &HashSet::new(), |d| scope_relevance(d, Some("api/orders")), |_| 10);
+        assert_eq!(ids(&sel.kept), ["d-3", "d-4", "d-5"]);
+        assert_eq!(ids(&sel.omitted), ["d-2", "d-1"]);
+        assert_eq!(sel.used_tokens, 40);
+    }
+
+    #[test]
+    fn an_entailed_may_outranks_a_lower_level_must() {
+        let binding = HashSet::from(["d-1".to_string()]);
+        let sel = select(decisions(), 20, 0, &binding, |_| 0.0, |_| 10);
+        assert_eq!(ids(&sel.kept), ["d-1", "d-4"]);
+    }
+
+    #[test]
+    fn scope_relevance_counts_shared_segments() {
+        let scoped = |scope| Decision::test("d-1").with_scope(scope);
+        assert_eq!(scope_relevance(&scoped("billing/invoices"), Some("billing/invoices")), 2.0);
+        assert_eq!(scope_relevance(&scoped("billing"), Some("billing/invoices")), 1.0);
+        assert_eq!(scope_relevance(&scoped("billing/refunds"), Some("billing/invoices")), 1.0);
+        assert_eq!(scope_relevance(&scoped("search/index"), Some("billing")), 0.0);
+        assert_eq!(scope_relevance(&Decision::test("d-1"), Some("billing")), 0.0);
+    }
+
+    #[test]
+    fn estimates_a_token_per_four_characters() {
+        assert_eq!(estimate("abcdefghi"), 3);
+    }
+}
@@ -1,11 +1,12 @@
 use std::path::Path;
 
 use crate::config::Config;
-use crate::db;
-use crate::error::Result;
+use crate::db::{self, ListFilter, Store};
+use crate::error::{DictumError, Result};
 use crate::format::OutputFormat;
 use crate::id::generate_id;
-use crate::model::{Decision, Kind, Level, Link, LinkKind, Status, Weight};
+use crate::model::{Decision, Kind, Level, Link, LinkKind, Origin, Status, Weight};
+use crate::similarity::{Similar, SimilarityIndex};
 
 pub struct AddArgs {
     pub title: String,
@@ -19,6 +20,9 @@ pub struct AddArgs {
     pub weight: Weight,
     pub rebuttal: Option<String>,
     pub scope: Option<String>,
+    pub strict: bool,
+    /// Who or what proposed it; set for drafts made by `dictum propose`
+    pub origin: Option<Origin>,
 }
 
 pub fn run(path: &Path, args: AddArgs, is_tty: bool) -> Result<()> {
@@ -28,6 +32,42 @@ pub fn run(path: &Path, args: AddArgs, is_tty: bool) -> Result<()> {
     let config = Config::load(&dictum_dir)?;
     let mut store = db::open(&dictum_dir)?;
 
+    let format = OutputFormat::from_str_or_auto(args.format.as_deref(), is_tty);
+    let added = insert(&mut *store, &dictum_dir, &config, args, Status::Active)?;
+    if let Some((similar, title)) = &added.similar {
+        eprintln!(
+            "Warning: similar to existing decision {} ({:.2}): {}",
+            similar.id, similar.score, title
+        );
+    }
+
+    match format {
+        OutputFormat::Text => println!("Added: {}", added.decision.id),
+        _ => {
+            let output = serde_json::to_string(&added.decision)?;
+            println!("{}", output);
+        }
+    }
+
+    Ok(())
+}
+
+pub struct Added {
+    pub decision: Decision,
+    /// The closest active decision at or above `similarity_threshold`, with its title
+    pub similar: Option<(Similar, String)>,
+}
+
+/// Store a new decision with its labels and parent link. A near-duplicate of
+/// an active decision is returned alongside it, or refused with `strict`. A
+/// draft that repeats a pending or rejected proposal is always refused.
+pub fn insert(
+    store: &mut dyn Store,
+    dictum_dir: &Path,
+    config: &Config,
+    args: AddArgs,
+    status: Status,
+) -> Result<Added> {
     let now = chrono::Utc::now().to_rfc3339();
     let author = args
         .author
@@ -41,7 +81,7 @@ pub fn run(path: &Path, args: AddArgs, is_tty: bool) -> Result<()> {
         title: args.title,
         body: args.body,
         level: args.level,
-        status: Status::Active,
+        status,
         superseded_by: None,
         author,
         created_at: now.clone(),
@@ -50,9 +90,63 @@ pub fn run(path: &Path, args: AddArgs, is_tty: bool) -> Result<()> {
         kind: args.kind,
         weight: args.weight,
         rebuttal: args.rebuttal,
-        scope: args.scope,
+        scope: args.scope.as_deref().and_then(crate::model::scope::normalize),
+        origin: args.origin.filter(|o| !o.is_empty()),
     };
 
+    // Near-duplicate check against active decisions
+    let active = store.decision_list(&ListFilter {
+        status: Some(Status::Active),
+        ..Default::default()
+    })?;
+    let all = store.decision_get_all()?;
+    let index = SimilarityIndex::load_or_build(dictum_dir, &all)?;
+    if decision.status == Status::Draft {
+        let earlier: Vec<&str> = all
+            .iter()
+            .filter(|d| matches!(d.status, Status::Draft | Status::Rejected))
+            .map(|d| d.id.as_str())
+            .collect();
+        let repeat = index
+            .similar_to_decision(&decision, &earlier, 1)
+            .into_iter()
+            .find(|s| s.score >= config.similarity_threshold);
+        if let Some(d) = repeat.and_then(|s| all.iter().find(|d| d.id == s.id)) {
+            return Err(match d.status {
+                Status::Rejected => DictumError::PreviouslyRejected {
+                    id: d.id.clone(),
+                    reason: d
+                        .origin
+                        .as_ref()
+                        .and_then(|o| o.rejection.clone())
+                        .unwrap_or_else(|| "no reason given".to_string()),
+                },
+                _ => DictumError::AlreadyProposed(d.id.clone()),
+            });
+        }
+    }
+    let active_ids: Vec<&str> = active.iter().map(|d| d.id.as_str()).collect();
+    let similar = index
+        .similar_to_decision(&decision, &active_ids, 1)
+        .into_iter()
+        .find(|s| s.score >= config.similarity_threshold);
+    if let Some(ref similar) = similar {
+        if args.strict {
+            return Err(DictumError::TooSimilar {
+                id: similar.id.clone(),
+                score: format!("{:.2}", similar.score),
+            });
+        }
+    }
+    let similar = similar.map(|s| {
+        let title = active
+            .iter()
+            .find(|d| d.id == s.id)
+            .map(|d| d.title.clone())
+            .unwrap_or_default();
+        (s, title)
+    });
+
     store.decision_insert(&decision)?;
 
     for label in &args.label {
@@ -72,14 +166,5 @@ pub fn run(path: &Path, args: AddArgs, is_tty: bool) -> Result<()> {
         store.link_insert(&link)?;
     }
 
-    let format = OutputFormat::from_str_or_auto(args.format.as_deref(), is_tty);
-    match format {
-        OutputFormat::Text => println!("Added: {}", id),
-        _ => {
-            let output = serde_json::to_string(&decision)?;
-            println!("{}", output);
-        }
-    }
-
-    Ok(())
+    Ok(Added { decision, similar })
 }
@@ -1,7 +1,7 @@
 use std::path::Path;
 
 use crate::config::Config;
-use crate::db;
+use crate::db::{self, Store};
 use crate::error::Result;
 use crate::format::OutputFormat;
 use crate::id::generate_id;
@@ -24,17 +24,34 @@ pub fn run(path: &Path, args: AmendArgs, is_tty: bool) -> Result<()> {
 
     let config = Config::load(&dictum_dir)?;
     let mut store = db::open(&dictum_dir)?;
+    let new_decision = supersede(&mut *store, &config, &args)?;
 
+    let format = OutputFormat::from_str_or_auto(args.format.as_deref(), is_tty);
+    match format {
+        OutputFormat::Text => {
+            println!("Amended: {} -> {}", args.id, new_decision.id);
+        }
+        _ => {
+            let output = serde_json::to_string(&new_decision)?;
+            println!("{}", output);
+        }
+    }
+
+    Ok(())
+}
+
+/// Store an edited copy of `args.id` as a new active decision that supersedes it.
+pub fn supersede(store: &mut dyn Store, config: &Config, args: &AmendArgs) -> Result<Decision> {
     let old = store.decision_get(&args.id)?;
 
     let now = chrono::Utc::now().to_rfc3339();
-    let new_title = args.title.unwrap_or_else(|| old.title.clone());
+    let new_title = args.title.clone().unwrap_or_else(|| old.title.clone());
     let new_id = generate_id(&config.prefix, &new_title, &now);
 
     let new_decision = Decision {
         id: new_id.clone(),
         title: new_title,
-        body: args.body.or(old.body.clone()),
+        body: args.body.clone().or(old.body.clone()),
         level: old.level.clone(),
         status: Status::Active,
         superseded_by: None,
@@ -42,10 +59,15 @@ pub fn run(path: &Path, args: AmendArgs, is_tty: bool) -> Result<()> {
         created_at: now.clone(),
         updated_at: now.clone(),
         labels: old.labels.clone(),
-        kind: args.kind.unwrap_or(old.kind.clone()),
-        weight: args.weight.unwrap_or(old.weight.clone()),
-        rebuttal: args.rebuttal.or(old.rebuttal.clone()),
-        scope: args.scope.or(old.scope.clone()),
+        kind: args.kind.clone().unwrap_or(old.kind.clone()),
+        weight: args.weight.clone().unwrap_or(old.weight.clone()),
+        rebuttal: args.rebuttal.clone().or(old.rebuttal.clone()),
+        // An explicit empty scope clears it
+        scope: match args.scope {
+            Some(ref s) => crate::model::scope::normalize(s),
+            None => old.scope.clone(),
+        },
+        origin: old.origin.clone(),
     };
 
     store.decision_insert(&new_decision)?;
@@ -64,19 +86,7 @@ pub fn run(path: &Path, args: AmendArgs, is_tty: bool) -> Result<()> {
     store.link_insert(&link)?;
 
     store.decision_update_status(&args.id, &Status::Superseded, Some(&new_id))?;
-
-    let format = OutputFormat::from_str_or_auto(args.format.as_deref(), is_tty);
-    match format {
-        OutputFormat::Text => {
-            println!("Amended: {} -> {}", args.id, new_id);
-        }
-        _ => {
-            let output = serde_json::to_string(&new_decision)?;
-            println!("{}", output);
-        }
-    }
-
-    Ok(())
+    Ok(new_decision)
 }
 
 pub fn run_deprecate(
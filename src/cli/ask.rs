@@ -0,0 +1,311 @@
+//! `dictum ask`: answer "is there a decision about this?" from the store
+//! alone. The question is matched lexically against active decisions the way
+//! `context --task` ranks them, and each match comes with the refines chain
+//! that justifies it. A question nothing matches well enough gets an explicit
+//! "no governing decision" answer rather than the nearest weak match.
+
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::db::{self, ListFilter};
+use crate::error::{DictumError, Result};
+use crate::format::OutputFormat;
+use crate::model::{Decision, Kind, LinkKind, Status, Weight};
+use crate::{budget, relevance};
+
+/// Words that shape a question without saying what it is about. Left in,
+/// "should we use X?" would match every "Use ..." decision.
+const QUESTION_WORDS: &[&str] = &[
+    "about", "allowed", "am", "any", "can", "could", "did", "do", "does", "have", "how", "i",
+    "if", "is", "may", "me", "must", "need", "ok", "okay", "our", "should", "there", "us", "use",
+    "used", "using", "what", "when", "where", "which", "who", "why", "would", "you",
+];
+
+/// Default relevance a match needs to count as governing
+pub const MIN_SCORE: f64 = 0.3;
+
+pub struct AskArgs {
+    pub question: String,
+    pub scope: Option<String>,
+    pub top: usize,
+    /// Below this relevance a match does not count as governing
+    pub min_score: f64,
+    pub as_of: Option<String>,
+    pub format: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Answer {
+    pub question: String,
+    pub governed: bool,
+    /// How sure the answer is. When governed, the best match's score scaled
+    /// by how many question terms it matched (one term: half, two: three
+    /// quarters, ...), so a single shared word is never certain; otherwise
+    /// one minus the best score
+    pub confidence: f64,
+    pub decisions: Vec<Governing>,
+    /// The best match that fell short of the threshold, when not governed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nearest: Option<Governing>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Governing {
+    #[serde(flatten)]
+    pub decision: Decision,
+    pub score: f64,
+    pub reason: String,
+    /// What it refines, then what that refines, and so on
+    pub chain: Vec<Parent>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Parent {
+    pub id: String,
+    pub title: String,
+    pub kind: Kind,
+    pub weight: Weight,
+    pub status: Status,
+    /// 1 for what the decision refines directly
+    pub depth: usize,
+}
+
+pub fn run(path: &Path, args: AskArgs, is_tty: bool) -> Result<()> {
+    let dictum_dir = path.join(".dictum");
+    crate::cli::ensure_init(&dictum_dir)?;
+
+    let store = db::open_as_of(&dictum_dir, path, args.as_of.as_deref())?;
+    let active = store.decision_list(&ListFilter {
+        status: Some(Status::Active),
+        scope: args.scope.clone(),
+        ..Default::default()
+    })?;
+    let all = store.decision_get_all()?;
+    let refines = store.links_of_kind(&LinkKind::Refines)?;
+    let answer = answer(&args.question, &active, &all, &refines, args.top, args.min_score)?;
+
+    let format = OutputFormat::from_str_or_auto(args.format.as_deref(), is_tty);
+    match format {
+        OutputFormat::Text => print!("{}", format_answer_text(&answer)),
+        _ => println!("{}", serde_json::to_string_pretty(&answer)?),
+    }
+    Ok(())
+}
+
+/// Rank `active` against `question`, keeping at most `top` matches scoring at
+/// least `min_score`. `all` and `refines` supply the parent chains. A
+/// question with nothing but question words and stopwords is an error.
+pub fn answer(
+    question: &str,
+    active: &[Decision],
+    all: &[Decision],
+    refines: &[(String, String)],
+    top: usize,
+    min_score: f64,
+) -> Result<Answer> {
+    let topic: Vec<&str> = question
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !QUESTION_WORDS.contains(&w.to_lowercase().as_str()))
+        .collect();
+    let topic = topic.join(" ");
+    if relevance::terms(&topic).is_empty() {
+        return Err(DictumError::EmptyQuestion(question.to_string()));
+    }
+    let scores = relevance::score_against("question", &topic, active);
+
+    let mut ranked: Vec<&Decision> = active.iter().filter(|d| scores.contains_key(&d.id)).collect();
+    ranked.sort_by(|a, b| {
+        scores[&b.id]
+            .score
+            .total_cmp(&scores[&a.id].score)
+            .then_with(|| budget::weight_rank(&a.weight).cmp(&budget::weight_rank(&b.weight)))
+            .then_with(|| a.id.cmp(&b.id))
+    });
+
+    let by_id: HashMap<&str, &Decision> = all.iter().map(|d| (d.id.as_str(), d)).collect();
+    let governing = |d: &Decision| Governing {
+        decision: d.clone(),
+        score: scores[&d.id].score,
+        reason: scores[&d.id].reason.clone(),
+        chain: chain(&d.id, &by_id, refines),
+    };
+
+    let best = ranked.first().map_or(0.0, |d| scores[&d.id].score);
+    let strength = ranked
+        .first()
+        .map_or(0.0, |d| 1.0 - 0.5f64.powi(scores[&d.id].matched as i32));
+    let decisions: Vec<Governing> = ranked
+        .iter()
+        .take_while(|d| scores[&d.id].score >= min_score)
+        .take(top)
+        .map(|d| governing(d))
+        .collect();
+    let governed = !decisions.is_empty();
+    let confidence = if governed { best * strength } else { 1.0 - best };
+    Ok(Answer {
+        question: question.to_string(),
+        governed,
+        confidence: (confidence * 100.0).round() / 100.0,
+        nearest: if governed { None } else { ranked.first().map(|d| governing(d)) },
+        decisions,
+    })
+}
+
+/// Everything `id` refines, transitively, nearest first.
+fn chain(id: &str, by_id: &HashMap<&str, &Decision>, refines: &[(String, String)]) -> Vec<Parent> {
+    let mut parents = Vec::new();
+    let mut seen = HashSet::from([id.to_string()]);
+    let mut frontier = vec![id.to_string()];
+    let mut depth = 0;
+    while !frontier.is_empty() {
+        depth += 1;
+        let mut next = Vec::new();
+        for child in &frontier {
+            for (_, parent) in refines.iter().filter(|(source, _)| source == child) {
+                if !seen.insert(parent.clone()) {
+                    continue;
+                }
+                if let Some(d) = by_id.get(parent.as_str()) {
+                    parents.push(Parent {
+                        id: d.id.clone(),
+                        title: d.title.clone(),
+                        kind: d.kind.clone(),
+                        weight: d.weight.clone(),
+                        status: d.status.clone(),
+                        depth,
+                    });
+                }
+                next.push(parent.clone());
+            }
+        }
+        frontier = next;
+    }
+    parents
+}
+
+fn format_answer_text(answer: &Answer) -> String {
+    if !answer.governed {
+        let mut out = format!(
+            "No governing decision (confidence {:.2}).\n",
+            answer.confidence
+        );
+        if let Some(ref g) = answer.nearest {
+            out.push_str(&format!(
+                "Nearest, below the threshold: [{}] {} ({:.2})\n",
+                g.decision.id, g.decision.title, g.score
+            ));
+        }
+        return out;
+    }
+
+    let mut out = format!(
+        "Governed by {} decision(s) (confidence {:.2}):\n",
+        answer.decisions.len(),
+        answer.confidence
+    );
+    for g in &answer.decisions {
+        let d = &g.decision;
+        out.push_str(&format!("\n[{}] ({}/{}) {}\n", d.id, d.kind, d.weight, d.title));
+        out.push_str(&format!("  Score {:.2}: {}\n", g.score, g.reason));
+        if let Some(ref scope) = d.scope {
+            out.push_str(&format!("  Scope: {}\n", scope));
+        }
+        if let Some(ref rebuttal) = d.rebuttal {
+            out.push_str(&format!("  Unless: {}\n", rebuttal));
+        }
+        if !g.chain.is_empty() {
+            out.push_str("  Because:\n");
+            for p in &g.chain {
+                let status = if p.status == Status::Active {
+                    String::new()
+                } else {
+                    format!(" [{}]", p.status)
+                };
+                out.push_str(&format!(
+                    "  {}<- [{}] ({}/{}) {}{}\n",
+                    "  ".repeat(p.depth),
+                    p.id,
+                    p.kind,
+                    p.weight,
+                    p.title,
+                    status
+                ));
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decision(id: &str, title: &str, weight: Weight) -> Decision {
+        Decision::test(id).with_title(title).with_kind(Kind::Rule).with_weight(weight)
+    }
+
+    fn decisions() -> Vec<Decision> {
+        vec![
+            decision("d-1", "Keep latency low for interactive requests", Weight::Must),
+            decision("d-2", "Cache user sessions in Redis", Weight::Should),
+            decision("d-3", "Use Postgres for billing records", Weight::Should),
+            decision("d-4", "Expire cached sessions after an hour", Weight::May),
+        ]
+    }
+
+    fn ask(question: &str) -> Answer {
+        let refines = vec![
+            ("d-2".to_string(), "d-1".to_string()),
+            ("d-4".to_string(), "d-2".to_string()),
+        ];
+        answer(question, &decisions(), &decisions(), &refines, 5, MIN_SCORE).unwrap()
+    }
+
+    #[test]
+    fn answers_with_the_best_match_first() {
+        let a = ask("Should we cache sessions in Redis?");
+        assert!(a.governed && a.nearest.is_none());
+        assert_eq!(a.decisions[0].decision.id, "d-2");
+        assert!(a.decisions.iter().all(|g| g.decision.id != "d-3"));
+        // Three matched terms
+        assert_eq!(a.confidence, (a.decisions[0].score * 0.875 * 100.0).round() / 100.0);
+    }
+
+    #[test]
+    fn follows_refines_links_up_the_chain() {
+        let a = ask("Should we cache sessions in Redis?");
+        let chain: Vec<(&str, usize)> = a.decisions[0].chain.iter().map(|p| (p.id.as_str(), p.depth)).collect();
+        assert_eq!(chain, [("d-1", 1)]);
+        let expiry = a.decisions.iter().find(|g| g.decision.id == "d-4").unwrap();
+        assert_eq!(expiry.chain.len(), 2);
+    }
+
+    #[test]
+    fn says_so_when_nothing_governs() {
+        // "use" alone must not pull in "Use Postgres ..."
+        let none = ask("What logging library should we use?");
+        assert!(!none.governed && none.decisions.is_empty());
+        assert_eq!(none.confidence, 1.0);
+        assert!(none.nearest.is_none());
+    }
+
+    #[test]
+    fn a_single_matched_term_is_not_certain() {
+        let decisions = vec![decision("d-1", "Use Postgres as the primary database", Weight::Must)];
+        let a = answer("What database should we use?", &decisions, &decisions, &[], 5, MIN_SCORE).unwrap();
+        assert!(a.governed);
+        assert_eq!(a.decisions[0].score, 1.0);
+        assert_eq!(a.confidence, 0.5);
+    }
+
+    #[test]
+    fn rejects_questions_with_nothing_to_match() {
+        let decisions = vec![decision("d-1", "Use Postgres as the primary database", Weight::Must)];
+        for question in ["", "   ", "should we?", "can we do it, or is it for you?"] {
+            let err = answer(question, &decisions, &decisions, &[], 5, MIN_SCORE).unwrap_err();
+            assert!(matches!(err, DictumError::EmptyQuestion(_)), "{:?}", question);
+        }
+    }
+}
@@ -0,0 +1,209 @@
+use std::io::{self, BufRead, Read};
+use std::path::Path;
+use std::process::Command;
+
+use crate::db::{self, ListFilter};
+use crate::error::{DictumError, Result};
+use crate::format::OutputFormat;
+use crate::model::{Decision, Link, Status, Weight};
+use crate::reasoning::consistency::{self, ConsistencyReport};
+use crate::reasoning::patch::{self, FileChange, Hit};
+
+pub fn run_consistency(
+    path: &Path,
+    ids: &[String],
+    draft: Option<String>,
+    fmt: Option<String>,
+    is_tty: bool,
+) -> Result<()> {
+    let dictum_dir = path.join(".dictum");
+    crate::cli::ensure_init(&dictum_dir)?;
+
+    let store = db::open(&dictum_dir)?;
+
+    let mut candidates = Vec::new();
+    for id in ids {
+        candidates.push(store.decision_get(id)?);
+    }
+
+    let mut draft_links = Vec::new();
+    if let Some(ref file) = draft {
+        let (decisions, links) = read_drafts(file)?;
+        candidates.extend(decisions);
+        draft_links = links;
+    }
+
+    if candidates.is_empty() {
+        return Err(DictumError::Config(
+            "no candidates given: pass decision IDs or --draft <file>".to_string(),
+        ));
+    }
+
+    let report = consistency::check(&*store, &candidates, &draft_links)?;
+
+    let format = OutputFormat::from_str_or_auto(fmt.as_deref(), is_tty);
+    match format {
+        OutputFormat::Text => print!("{}", format_report_text(&report)),
+        _ => println!("{}", serde_json::to_string_pretty(&report)?),
+    }
+
+    if !report.is_consistent() {
+        let fatal = report.issues.iter().filter(|i| i.kind.is_fatal()).count();
+        return Err(DictumError::Inconsistent(fatal));
+    }
+    Ok(())
+}
+
+/// Report the active decisions a diff touches. The diff comes from `file`
+/// ("-" for stdin) or, by default, from `git diff` in the repository.
+pub fn run_diff(
+    path: &Path,
+    file: Option<String>,
+    staged: bool,
+    strict: bool,
+    fmt: Option<String>,
+    is_tty: bool,
+) -> Result<()> {
+    let dictum_dir = path.join(".dictum");
+    crate::cli::ensure_init(&dictum_dir)?;
+
+    let diff = match file.as_deref() {
+        Some("-") => {
+            let mut text = String::new();
+            io::stdin().read_to_string(&mut text)?;
+            text
+        }
+        Some(file) => std::fs::read_to_string(file)?,
+        None => git_diff(path, staged)?,
+    };
+    let files = patch::parse(&diff);
+
+    let store = db::open(&dictum_dir)?;
+    let active = store.decision_list(&ListFilter {
+        status: Some(Status::Active),
+        ..Default::default()
+    })?;
+    let hits = patch::check(&files, &active);
+    let must = hits.iter().filter(|h| h.decision.weight == Weight::Must).count();
+
+    let format = OutputFormat::from_str_or_auto(fmt.as_deref(), is_tty);
+    match format {
+        OutputFormat::Text => print!("{}", format_diff_text(&files, &hits, must)),
+        _ => {
+            let value = serde_json::json!({
+                "files": files,
+                "added_lines": files.iter().map(|f| f.added_lines).sum::<usize>(),
+                "matches": hits,
+                "must_count": must,
+            });
+            println!("{}", serde_json::to_string_pretty(&value)?);
+        }
+    }
+
+    if strict && must > 0 {
+        return Err(DictumError::MustRulesImplicated(must));
+    }
+    Ok(())
+}
+
+fn git_diff(path: &Path, staged: bool) -> Result<String> {
+    let mut cmd = Command::new("git");
+    cmd.arg("-C").arg(path).args(["diff", "--no-color", "--no-ext-diff"]);
+    if staged {
+        cmd.arg("--cached");
+    }
+    let output = cmd.output().map_err(|e| DictumError::GitDiff(e.to_string()))?;
+    if !output.status.success() {
+        return Err(DictumError::GitDiff(
+            String::from_utf8_lossy(&output.stderr)
+                .lines()
+                .next()
+                .unwrap_or_default()
+                .to_string(),
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+fn format_diff_text(files: &[FileChange], hits: &[Hit], must: usize) -> String {
+    let added: usize = files.iter().map(|f| f.added_lines).sum();
+    let mut out = format!("Diff: {} file(s), {} added line(s)\n", files.len(), added);
+    if hits.is_empty() {
+        out.push_str("\nNo active decisions implicated.\n");
+        return out;
+    }
+
+    for hit in hits {
+        let d = &hit.decision;
+        if d.weight == Weight::Must {
+            out.push_str(&format!("\nMUST [{}] {}\n", d.id, d.title));
+        } else {
+            out.push_str(&format!("\n[{}] ({}) {}\n", d.id, d.weight, d.title));
+        }
+        for reason in &hit.reasons {
+            out.push_str(&format!(
+                "    {} {}: {}\n",
+                reason.via,
+                reason.detail,
+                reason.paths.join(", ")
+            ));
+        }
+        if let Some(ref rebuttal) = d.rebuttal {
+            out.push_str(&format!("    UNLESS: {}\n", rebuttal));
+        }
+    }
+
+    out.push_str(&format!(
+        "\n{} decision(s) implicated, {} must-weight.\n",
+        hits.len(),
+        must
+    ));
+    out
+}
+
+/// Read draft decisions in export format (one JSON object per line, with optional `links`).
+fn read_drafts(file: &str) -> Result<(Vec<Decision>, Vec<Link>)> {
+    let reader: Box<dyn BufRead> = if file == "-" {
+        Box::new(io::BufReader::new(io::stdin()))
+    } else {
+        Box::new(io::BufReader::new(std::fs::File::open(file)?))
+    };
+
+    let mut decisions = Vec::new();
+    let mut links = Vec::new();
+    for line in reader.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let value: serde_json::Value = serde_json::from_str(&line)?;
+        decisions.push(serde_json::from_value::<Decision>(value.clone())?);
+        if let Some(l) = value.get("links") {
+            links.extend(serde_json::from_value::<Vec<Link>>(l.clone())?);
+        }
+    }
+    Ok((decisions, links))
+}
+
+fn format_report_text(report: &ConsistencyReport) -> String {
+    let mut out = String::new();
+    out.push_str(&format!("Candidates: {}\n", report.candidates.join(", ")));
+    out.push_str(&format!("Requires closure: {}\n", report.closure.join(", ")));
+
+    for issue in &report.issues {
+        let tag = serde_json::to_value(&issue.kind)
+            .ok()
+            .and_then(|v| v.as_str().map(|s| s.to_string()))
+            .unwrap_or_default();
+        let severity = if issue.kind.is_fatal() { "error" } else { "warning" };
+        out.push_str(&format!("\n{} [{}]: {}\n", severity, tag, issue.ids.join(", ")));
+        for step in &issue.explanation {
+            out.push_str(&format!("    {}\n", step));
+        }
+    }
+
+    if report.is_consistent() {
+        out.push_str("\nConsistent.\n");
+    }
+    out
+}
@@ -1,52 +1,331 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::Path;
 
+use crate::budget::{self, Selection};
 use crate::db;
-use crate::db::ListFilter;
+use crate::db::history::{self, Changes};
+use crate::db::{ListFilter, Sort};
 use crate::db::Store;
 use crate::error::Result;
 use crate::format::OutputFormat;
-use crate::model::{Decision, Kind, Status, Weight};
+use crate::model::{Decision, Kind, Level, Status, Weight};
+use crate::reasoning::entailment::{self, EntailmentReport};
+use crate::relevance::{self, Relevance};
 
+#[derive(Default)]
 pub struct ContextArgs {
     pub format: Option<String>,
     pub kind: Option<Kind>,
     pub weight: Option<Weight>,
     pub scope: Option<String>,
+    pub exact: bool,
+    pub where_expr: Option<String>,
+    pub view: Option<String>,
+    pub entailments: bool,
+    pub as_of: Option<String>,
+    pub max_tokens: Option<usize>,
+    pub task: Option<String>,
+    pub since: Option<String>,
+    /// User template file; its data placeholder is replaced with the context
+    /// in `format`, JSON unless given
+    pub template: Option<String>,
+}
+
+/// The selected decisions and what the renderers add to each entry.
+pub struct Context {
+    pub decisions: Vec<Decision>,
+    pub entailments: Option<EntailmentReport>,
+    pub selection: Option<Selection>,
+    pub relevance: Option<HashMap<String, Relevance>>,
+    /// With `--since`: what changed, and the token to pass next time
+    pub changes: Option<Changes>,
+    pub token: Option<String>,
+    /// Requested output format, from the flags or the view
+    pub format: Option<String>,
+    pub template: Option<String>,
 }
 
 pub fn run(path: &Path, args: ContextArgs, is_tty: bool) -> Result<()> {
     let dictum_dir = path.join(".dictum");
     crate::cli::ensure_init(&dictum_dir)?;
 
-    let store = db::open(&dictum_dir)?;
+    let store = db::open_as_of(&dictum_dir, path, args.as_of.as_deref())?;
+    let ctx = collect(&*store, &dictum_dir, args, is_tty)?;
+    let template = ctx.template.as_deref().map(std::fs::read_to_string).transpose()?;
+    print!("{}", render(&*store, &ctx, template.as_deref(), is_tty)?);
+
+    Ok(())
+}
+
+/// The context as printed in the requested format, placed into `template`
+/// when given. A template gets JSON unless another format is asked for.
+fn render(store: &dyn Store, ctx: &Context, template: Option<&str>, is_tty: bool) -> Result<String> {
+    if let Some(template) = template {
+        let content = render(store, ctx, None, false)?;
+        let name = ctx.template.as_deref().unwrap_or_default();
+        return crate::format::template::fill(template, name, content.trim_end());
+    }
+    match ctx.format.as_deref() {
+        Some("markdown" | "md") => return markdown_string(store, ctx),
+        Some("xml") => return xml_string(store, ctx),
+        _ => {}
+    }
+    Ok(match OutputFormat::from_str_or_auto(ctx.format.as_deref(), is_tty) {
+        OutputFormat::Compact => serde_json::to_string(&compact_value(store, ctx)?)? + "\n",
+        OutputFormat::Json => serde_json::to_string_pretty(&json_value(store, ctx)?)? + "\n",
+        _ => text_string(store, ctx)?,
+    })
+}
+
+/// Select the decisions for a context request: filters and view, then
+/// entailments, task relevance and the token budget, in that order. The
+/// budget is measured on the output as `is_tty` and the format render it.
+pub fn collect(store: &dyn Store, dictum_dir: &Path, args: ContextArgs, is_tty: bool) -> Result<Context> {
+    // Explicit flags take precedence over the view's values. Context is always
+    // active decisions, so a view's status is ignored here.
+    let view = match args.view {
+        Some(ref name) => crate::cli::view::load(dictum_dir, name)?,
+        None => Default::default(),
+    };
+    let kind = match args.kind {
+        Some(k) => Some(k),
+        None => view.kind.as_deref().map(str::parse::<Kind>).transpose()?,
+    };
+    let weight = match args.weight {
+        Some(w) => Some(w),
+        None => view.weight.as_deref().map(str::parse::<Weight>).transpose()?,
+    };
+    let where_expr = crate::cli::view::merge_where(args.where_expr, &view);
+    let scope = args.scope.or(view.scope.clone());
 
-    let decisions = store.decision_list(&ListFilter {
-        level: None,
+    let now = chrono::Utc::now();
+    let filter = ListFilter {
+        level: view.level.as_deref().map(str::parse::<Level>).transpose()?,
         status: Some(Status::Active),
-        label: None,
-        kind: args.kind,
-        weight: args.weight,
-        scope: args.scope,
-    })?;
+        label: view.label.clone(),
+        kind,
+        weight,
+        scope: scope.clone(),
+        scope_exact: args.exact || view.exact,
+        expr: where_expr.as_deref().map(db::expr::parse).transpose()?,
+        sort: view.sort.as_deref().map(str::parse::<Sort>).transpose()?,
+        ..Default::default()
+    };
+    let mut decisions = store.decision_list(&filter)?;
+
+    // Entailed decisions are binding even when the filters above excluded them
+    let entailments = if args.entailments {
+        let report = entailment::closure(store, &decisions)?;
+        for e in &report.binding {
+            if !decisions.iter().any(|d| d.id == e.decision.id) {
+                decisions.push(e.decision.clone());
+            }
+        }
+        Some(report)
+    } else {
+        None
+    };
+
+    // Only what changed since an earlier call, so long-running sessions can
+    // update what they know instead of re-reading everything
+    let changes = match args.since {
+        Some(ref spec) => {
+            let repo = dictum_dir.parent().unwrap_or(dictum_dir);
+            let changes = history::changes_since(store, &filter, history::resolve_since(spec, repo)?)?;
+            decisions.retain(|d| changes.changed.contains_key(&d.id));
+            Some(changes)
+        }
+        None => None,
+    };
+
+    // Keep what the task touches, plus the active decisions governing those one
+    // hop up even where the filters left them out, most relevant first. Binding
+    // entailments stay regardless.
+    let relevance = match args.task {
+        Some(ref task) => {
+            let mut scores = relevance::score(task, &decisions);
+            let mut matched: Vec<(String, f64)> =
+                scores.iter().map(|(id, r)| (id.clone(), r.score)).collect();
+            matched.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+            for (id, score) in matched {
+                for (parent, reason) in relevance::governing(&id, &store.links_for_decision(&id)?) {
+                    scores.entry(parent).or_insert(Relevance {
+                        score: (score * 50.0).round() / 100.0,
+                        reason,
+                        matched: 0,
+                    });
+                }
+            }
+            for e in entailments.iter().flat_map(|r| &r.binding) {
+                scores.entry(e.decision.id.clone()).or_insert(Relevance {
+                    score: 0.0,
+                    reason: "binding through entailment".to_string(),
+                    matched: 0,
+                });
+            }
+            let mut parents: Vec<&String> = scores
+                .keys()
+                .filter(|id| !decisions.iter().any(|d| d.id == **id))
+                .collect();
+            parents.sort();
+            for id in parents {
+                let parent = store.decision_get(id)?;
+                if parent.status == Status::Active {
+                    decisions.push(parent);
+                }
+            }
+            decisions.retain(|d| scores.contains_key(&d.id));
+            scores.retain(|id, _| decisions.iter().any(|d| d.id == *id));
+            decisions.sort_by(|a, b| scores[&b.id].score.total_cmp(&scores[&a.id].score));
+            Some(scores)
+        }
+        None => None,
+    };
 
-    let format = OutputFormat::from_str_or_auto(args.format.as_deref(), is_tty);
+    let mut ctx = Context {
+        decisions,
+        entailments,
+        selection: None,
+        relevance,
+        token: changes.is_some().then(|| history::continuation_token(now)),
+        changes,
+        format: args.format.or(view.format),
+        template: args.template,
+    };
 
-    match format {
-        OutputFormat::Compact => print_compact_context(&*store, &decisions)?,
-        OutputFormat::Json => print_json_context(&*store, &decisions)?,
-        _ => print_text_context(&*store, &decisions)?,
+    // Trim to the token budget last, so binding entailments compete as musts
+    if let Some(max_tokens) = args.max_tokens {
+        let binding: HashSet<String> = ctx
+            .entailments
+            .iter()
+            .flat_map(|r| r.binding.iter().map(|e| e.decision.id.clone()))
+            .collect();
+        let decisions = std::mem::take(&mut ctx.decisions);
+        let (overhead, costs) = entry_costs(store, &mut ctx, &decisions, max_tokens, is_tty)?;
+        let selection = budget::select(
+            decisions,
+            max_tokens,
+            overhead,
+            &binding,
+            |d| match ctx.relevance.as_ref().and_then(|r| r.get(&d.id)) {
+                Some(r) => r.score,
+                None => budget::scope_relevance(d, scope.as_deref()),
+            },
+            |d| costs[&d.id],
+        );
+        ctx.decisions = selection.kept.clone();
+        ctx.selection = Some(selection);
     }
 
-    Ok(())
+    Ok(ctx)
 }
 
-fn print_compact_context(store: &dyn Store, decisions: &[Decision]) -> Result<()> {
-    let decision_ids: std::collections::HashSet<&str> =
-        decisions.iter().map(|d| d.id.as_str()).collect();
+/// Estimated tokens of the output without any decisions, and of each
+/// decision's entry in it, links and markup included. The overhead assumes
+/// every decision is listed as omitted, the longest the trailer can get;
+/// entries are costed against an empty output without that list, so each
+/// render stays the size of one entry.
+fn entry_costs(
+    store: &dyn Store,
+    ctx: &mut Context,
+    decisions: &[Decision],
+    max_tokens: usize,
+    is_tty: bool,
+) -> Result<(usize, HashMap<String, usize>)> {
+    let template = ctx.template.as_deref().map(std::fs::read_to_string).transpose()?;
+    ctx.selection = Some(Selection {
+        kept: Vec::new(),
+        omitted: decisions.to_vec(),
+        max_tokens,
+        used_tokens: max_tokens,
+    });
+    let overhead = budget::estimate(&render(store, ctx, template.as_deref(), is_tty)?);
+    ctx.selection = None;
+    let empty = budget::estimate(&render(store, ctx, template.as_deref(), is_tty)?);
+    let mut costs = HashMap::new();
+    for d in decisions {
+        ctx.decisions = vec![d.clone()];
+        let tokens = budget::estimate(&render(store, ctx, template.as_deref(), is_tty)?);
+        costs.insert(d.id.clone(), tokens.saturating_sub(empty));
+    }
+    ctx.decisions.clear();
+    Ok((overhead, costs))
+}
+
+/// Mark an entry as binding through entailment, with the chain that derives it.
+fn annotate_binding(map: &mut serde_json::Map<String, serde_json::Value>, chain: &[String]) {
+    map.insert("binding".to_string(), serde_json::Value::Bool(true));
+    map.insert(
+        "entailed_by".to_string(),
+        serde_json::to_value(&chain[..chain.len().saturating_sub(1)]).unwrap_or_default(),
+    );
+}
+
+/// Record how relevant an entry is to `--task`, and why it was included.
+fn annotate_relevance(map: &mut serde_json::Map<String, serde_json::Value>, r: &Relevance) {
+    map.insert("relevance".to_string(), serde_json::json!(r.score));
+    map.insert("reason".to_string(), serde_json::Value::String(r.reason.clone()));
+}
+
+/// Wrap context entries together with entailment warnings when in entailment
+/// mode, and with the omitted IDs when a token budget was applied.
+fn wrap(entries: Vec<serde_json::Value>, ctx: &Context) -> serde_json::Value {
+    if ctx.entailments.is_none() && ctx.selection.is_none() && ctx.changes.is_none() {
+        return serde_json::Value::Array(entries);
+    }
+    let mut wrapped = serde_json::json!({ "decisions": entries });
+    let map = wrapped.as_object_mut().unwrap();
+    if let (Some(changes), Some(token)) = (&ctx.changes, &ctx.token) {
+        map.insert("since".to_string(), serde_json::json!(changes.since.to_rfc3339()));
+        map.insert("token".to_string(), serde_json::json!(token));
+        let superseded: Vec<serde_json::Value> = changes
+            .superseded
+            .iter()
+            .map(|(id, successor)| serde_json::json!({ "id": id, "successor": successor }))
+            .collect();
+        map.insert("superseded".to_string(), serde_json::Value::Array(superseded));
+        map.insert("deprecated".to_string(), serde_json::json!(changes.deprecated));
+    }
+    if let Some(report) = ctx.entailments.as_ref() {
+        map.insert(
+            "entailment_cycles".to_string(),
+            serde_json::to_value(&report.cycles).unwrap_or_default(),
+        );
+        map.insert("inactive_entailments".to_string(), inactive_entailments(report));
+    }
+    if let Some(sel) = ctx.selection.as_ref() {
+        let omitted: Vec<&str> = sel.omitted.iter().map(|d| d.id.as_str()).collect();
+        map.insert("omitted".to_string(), serde_json::to_value(omitted).unwrap_or_default());
+        map.insert(
+            "budget".to_string(),
+            serde_json::json!({ "max_tokens": sel.max_tokens, "used_tokens": sel.used_tokens }),
+        );
+    }
+    wrapped
+}
+
+fn inactive_entailments(report: &EntailmentReport) -> serde_json::Value {
+    let inactive: Vec<serde_json::Value> = report
+        .inactive
+        .iter()
+        .map(|e| {
+            serde_json::json!({
+                "id": e.decision.id,
+                "status": e.decision.status.to_string(),
+                "entailed_by": &e.chain[..e.chain.len().saturating_sub(1)],
+            })
+        })
+        .collect();
+    serde_json::Value::Array(inactive)
+}
+
+/// Minified entries with only agent-relevant fields, and links only between
+/// decisions in the context.
+pub fn compact_value(store: &dyn Store, ctx: &Context) -> Result<serde_json::Value> {
+    let decision_ids: HashSet<&str> = ctx.decisions.iter().map(|d| d.id.as_str()).collect();
 
     let mut entries = Vec::new();
-    for d in decisions {
+    for d in &ctx.decisions {
         let mut obj = serde_json::json!({
             "id": d.id,
             "kind": d.kind.to_string(),
@@ -67,6 +346,15 @@ fn print_compact_context(store: &dyn Store, decisions: &[Decision]) -> Result<()
         if !d.labels.is_empty() {
             map.insert("labels".to_string(), serde_json::to_value(&d.labels)?);
         }
+        if let Some(chain) = ctx.entailments.as_ref().and_then(|r| r.binding_chain(&d.id)) {
+            annotate_binding(map, chain);
+        }
+        if let Some(r) = ctx.relevance.as_ref().and_then(|r| r.get(&d.id)) {
+            annotate_relevance(map, r);
+        }
+        if let Some(change) = ctx.changes.as_ref().and_then(|c| c.changed.get(&d.id)) {
+            map.insert("change".to_string(), serde_json::json!(change.as_str()));
+        }
 
         let links = store.links_for_decision(&d.id)?;
         let relevant_links: Vec<serde_json::Value> = links
@@ -101,13 +389,12 @@ fn print_compact_context(store: &dyn Store, decisions: &[Decision]) -> Result<()
 
         entries.push(obj);
     }
-    println!("{}", serde_json::to_string(&entries)?);
-    Ok(())
+    Ok(wrap(entries, ctx))
 }
 
-fn print_json_context(store: &dyn Store, decisions: &[Decision]) -> Result<()> {
+pub fn json_value(store: &dyn Store, ctx: &Context) -> Result<serde_json::Value> {
     let mut entries = Vec::new();
-    for d in decisions {
+    for d in &ctx.decisions {
         let links = store.links_for_decision(&d.id)?;
         let mut value = serde_json::to_value(d)?;
         if let serde_json::Value::Object(ref mut map) = value {
@@ -134,18 +421,27 @@ fn print_json_context(store: &dyn Store, decisions: &[Decision]) -> Result<()> {
             map.remove("updated_at");
             map.remove("created_at");
             map.remove("status");
+            if let Some(chain) = ctx.entailments.as_ref().and_then(|r| r.binding_chain(&d.id)) {
+                annotate_binding(map, chain);
+            }
+            if let Some(r) = ctx.relevance.as_ref().and_then(|r| r.get(&d.id)) {
+                annotate_relevance(map, r);
+            }
+            if let Some(change) = ctx.changes.as_ref().and_then(|c| c.changed.get(&d.id)) {
+                map.insert("change".to_string(), serde_json::json!(change.as_str()));
+            }
         }
         entries.push(value);
     }
-    println!("{}", serde_json::to_string_pretty(&entries)?);
-    Ok(())
+    Ok(wrap(entries, ctx))
 }
 
-fn print_text_context(store: &dyn Store, decisions: &[Decision]) -> Result<()> {
-    if decisions.is_empty() {
-        println!("No active decisions.");
-        return Ok(());
+/// The Markdown-style text rendering, as `context --format text` prints it.
+pub fn text_string(store: &dyn Store, ctx: &Context) -> Result<String> {
+    if let Some(message) = empty_message(ctx) {
+        return Ok(message);
     }
+    let decisions = &ctx.decisions;
 
     let mut by_level: HashMap<String, Vec<&Decision>> = HashMap::new();
     for d in decisions {
@@ -159,35 +455,353 @@ fn print_text_context(store: &dyn Store, decisions: &[Decision]) -> Result<()> {
         parent_of.insert(source.as_str(), target.as_str());
     }
 
-    println!("# Active Decisions\n");
+    let mut out = heading(ctx);
 
     for level in &["strategic", "tactical", "operational"] {
         if let Some(decs) = by_level.get(*level) {
-            println!("## {}\n", capitalize(level));
+            out.push_str(&format!("## {}\n\n", capitalize(level)));
             for d in decs {
-                print!("- [{}] ({}/{}) {}", d.id, d.kind, d.weight, d.title);
+                out.push_str(&format!("- [{}] ({}/{}) {}", d.id, d.kind, d.weight, d.title));
                 if let Some(ref scope) = d.scope {
-                    print!(" [scope: {}]", scope);
+                    out.push_str(&format!(" [scope: {}]", scope));
                 }
                 if let Some(parent_id) = parent_of.get(d.id.as_str()) {
-                    print!(" (refines {})", parent_id);
+                    out.push_str(&format!(" (refines {})", parent_id));
                 }
-                println!();
+                if let Some(chain) = ctx.entailments.as_ref().and_then(|r| r.binding_chain(&d.id)) {
+                    out.push_str(&format!(" BINDING via {}", chain.join(" → ")));
+                }
+                out.push('\n');
                 if let Some(ref body) = d.body {
-                    println!("  {}", body);
+                    out.push_str(&format!("  {}\n", body));
                 }
                 if let Some(ref rebuttal) = d.rebuttal {
-                    println!("  UNLESS: {}", rebuttal);
+                    out.push_str(&format!("  UNLESS: {}\n", rebuttal));
                 }
                 if !d.labels.is_empty() {
-                    println!("  Labels: {}", d.labels.join(", "));
+                    out.push_str(&format!("  Labels: {}\n", d.labels.join(", ")));
+                }
+                if let Some(r) = ctx.relevance.as_ref().and_then(|r| r.get(&d.id)) {
+                    out.push_str(&format!("  Relevance: {:.2}, {}\n", r.score, r.reason));
+                }
+                if let Some(change) = ctx.changes.as_ref().and_then(|c| c.changed.get(&d.id)) {
+                    out.push_str(&format!("  Change: {}\n", change.as_str()));
                 }
             }
-            println!();
+            out.push('\n');
         }
     }
 
-    Ok(())
+    out.push_str(&trailer(ctx));
+    Ok(out)
+}
+
+/// Prompt-oriented Markdown: decisions grouped by weight, with what each
+/// kind and weight means, exceptions spelled out and links in both directions.
+pub fn markdown_string(store: &dyn Store, ctx: &Context) -> Result<String> {
+    if let Some(message) = empty_message(ctx) {
+        return Ok(message);
+    }
+    let mut out = heading(ctx);
+    out.push_str("Terms used below:\n\n");
+    for (term, meaning) in legend(&ctx.decisions) {
+        out.push_str(&format!("- **{}**: {}\n", term, meaning));
+    }
+    out.push('\n');
+
+    for weight in [Weight::Must, Weight::Should, Weight::May] {
+        let decs: Vec<&Decision> = ctx.decisions.iter().filter(|d| d.weight == weight).collect();
+        if decs.is_empty() {
+            continue;
+        }
+        out.push_str(&format!("## {}\n\n", capitalize(&weight.to_string())));
+        for d in decs {
+            out.push_str(&format!("### {}: {}\n\n", d.id, d.title));
+            let mut facts = format!("{}, {}", capitalize(&d.kind.to_string()), d.level);
+            if let Some(ref scope) = d.scope {
+                facts.push_str(&format!(", scope `{}`", scope));
+            }
+            out.push_str(&format!("- {}\n", facts));
+            if let Some(ref rebuttal) = d.rebuttal {
+                out.push_str(&format!("- Exception: {}\n", rebuttal));
+            }
+            let links = link_phrases(d, &store.links_for_decision(&d.id)?, |id| format!("`{}`", id));
+            if !links.is_empty() {
+                out.push_str(&format!("- Links: {}\n", links.join("; ")));
+            }
+            if !d.labels.is_empty() {
+                out.push_str(&format!("- Labels: {}\n", d.labels.join(", ")));
+            }
+            if let Some(chain) = ctx.entailments.as_ref().and_then(|r| r.binding_chain(&d.id)) {
+                out.push_str(&format!("- Binding via {}\n", chain.join(" → ")));
+            }
+            if let Some(r) = ctx.relevance.as_ref().and_then(|r| r.get(&d.id)) {
+                out.push_str(&format!("- Relevance: {:.2}, {}\n", r.score, r.reason));
+            }
+            if let Some(change) = ctx.changes.as_ref().and_then(|c| c.changed.get(&d.id)) {
+                out.push_str(&format!("- Change: {}\n", change.as_str()));
+            }
+            if let Some(ref body) = d.body {
+                out.push_str(&format!("\n{}\n", body.trim_end()));
+            }
+            out.push('\n');
+        }
+    }
+
+    out.push_str(&trailer(ctx));
+    Ok(out)
+}
+
+/// XML-tagged blocks, one `<decision>` element per decision with its kind
+/// and weight as attributes, for harnesses that prompt with tagged sections.
+pub fn xml_string(store: &dyn Store, ctx: &Context) -> Result<String> {
+    let mut out = String::from("<decisions");
+    if let Some(ref changes) = ctx.changes {
+        out.push_str(&format!(" since=\"{}\"", changes.since.to_rfc3339()));
+    }
+    out.push_str(">\n");
+    let legend = legend(&ctx.decisions);
+    if !legend.is_empty() {
+        out.push_str("<legend>\n");
+        for (term, meaning) in legend {
+            out.push_str(&format!("<term name=\"{}\">{}</term>\n", term, xml_escape(meaning)));
+        }
+        out.push_str("</legend>\n");
+    }
+
+    for d in &ctx.decisions {
+        let mut attrs = vec![
+            ("id", d.id.clone()),
+            ("kind", d.kind.to_string()),
+            ("weight", d.weight.to_string()),
+            ("level", d.level.to_string()),
+        ];
+        if let Some(ref scope) = d.scope {
+            attrs.push(("scope", scope.clone()));
+        }
+        if let Some(chain) = ctx.entailments.as_ref().and_then(|r| r.binding_chain(&d.id)) {
+            attrs.push(("binding", "true".to_string()));
+            attrs.push(("entailed_by", chain[..chain.len().saturating_sub(1)].join(" ")));
+        }
+        if let Some(r) = ctx.relevance.as_ref().and_then(|r| r.get(&d.id)) {
+            attrs.push(("relevance", format!("{:.2}", r.score)));
+        }
+        if let Some(change) = ctx.changes.as_ref().and_then(|c| c.changed.get(&d.id)) {
+            attrs.push(("change", change.as_str().to_string()));
+        }
+        out.push_str(&format!("<decision{}>\n", xml_attrs(&attrs)));
+        out.push_str(&format!("<title>{}</title>\n", xml_escape(&d.title)));
+        if let Some(ref body) = d.body {
+            out.push_str(&format!("<body>{}</body>\n", xml_escape(body.trim_end())));
+        }
+        if let Some(ref rebuttal) = d.rebuttal {
+            out.push_str(&format!("<exception>{}</exception>\n", xml_escape(rebuttal)));
+        }
+        if !d.labels.is_empty() {
+            out.push_str(&format!("<labels>{}</labels>\n", xml_escape(&d.labels.join(", "))));
+        }
+        if let Some(r) = ctx.relevance.as_ref().and_then(|r| r.get(&d.id)) {
+            out.push_str(&format!("<reason>{}</reason>\n", xml_escape(&r.reason)));
+        }
+        for l in store.links_for_decision(&d.id)? {
+            let end = if l.source_id == d.id {
+                ("target", l.target_id.clone())
+            } else {
+                ("source", l.source_id.clone())
+            };
+            let attrs = xml_attrs(&[("kind", l.kind.to_string()), end]);
+            match l.reason {
+                Some(ref reason) => out.push_str(&format!("<link{}>{}</link>\n", attrs, xml_escape(reason))),
+                None => out.push_str(&format!("<link{} />\n", attrs)),
+            }
+        }
+        out.push_str("</decision>\n");
+    }
+
+    if let Some(report) = ctx.entailments.as_ref() {
+        for cycle in &report.cycles {
+            out.push_str(&format!("<warning>entails cycle: {}</warning>\n", cycle.join(" → ")));
+        }
+        for e in &report.inactive {
+            out.push_str(&format!(
+                "<warning>{} is {} but entailed via {}</warning>\n",
+                e.decision.id,
+                e.decision.status,
+                e.chain.join(" → ")
+            ));
+        }
+    }
+    if let Some(sel) = ctx.selection.as_ref().filter(|sel| !sel.omitted.is_empty()) {
+        let ids: Vec<&str> = sel.omitted.iter().map(|d| d.id.as_str()).collect();
+        out.push_str(&format!(
+            "<omitted max_tokens=\"{}\" used_tokens=\"{}\">{}</omitted>\n",
+            sel.max_tokens,
+            sel.used_tokens,
+            ids.join(" ")
+        ));
+    }
+    if let Some(ref changes) = ctx.changes {
+        for (id, successor) in &changes.superseded {
+            out.push_str(&format!("<superseded id=\"{}\" successor=\"{}\" />\n", id, successor));
+        }
+        for id in &changes.deprecated {
+            out.push_str(&format!("<deprecated id=\"{}\" />\n", id));
+        }
+        out.push_str(&format!(
+            "<next since=\"{}\" />\n",
+            ctx.token.as_deref().unwrap_or_default()
+        ));
+    }
+    out.push_str("</decisions>\n");
+    Ok(out)
+}
+
+/// Meanings of the weights and kinds present, weights first, and of
+/// exceptions when any decision has one.
+fn legend(decisions: &[Decision]) -> Vec<(String, &'static str)> {
+    let mut terms: Vec<(String, &'static str)> = Vec::new();
+    for weight in [Weight::Must, Weight::Should, Weight::May] {
+        if decisions.iter().any(|d| d.weight == weight) {
+            let meaning = match weight {
+                Weight::Must => "mandatory; do not deviate",
+                Weight::Should => "the default; deviate only for a stated reason",
+                Weight::May => "optional guidance",
+            };
+            terms.push((weight.to_string(), meaning));
+        }
+    }
+    for kind in [Kind::Constraint, Kind::Principle, Kind::Rule, Kind::Choice, Kind::Goal, Kind::Assumption] {
+        if decisions.iter().any(|d| d.kind == kind) {
+            let meaning = match kind {
+                Kind::Constraint => "imposed from outside the project; not ours to change",
+                Kind::Principle => "a value that guides judgement where no rule applies",
+                Kind::Rule => "a concrete practice to follow",
+                Kind::Choice => "an option picked among alternatives; stay consistent with it",
+                Kind::Goal => "an outcome to work toward",
+                Kind::Assumption => "believed true but unverified; flag evidence against it",
+            };
+            terms.push((kind.to_string(), meaning));
+        }
+    }
+    if decisions.iter().any(|d| d.rebuttal.is_some()) {
+        terms.push(("exception".to_string(), "a condition under which the decision does not apply"));
+    }
+    terms
+}
+
+/// `refines X` for outgoing links and `X requires this` for incoming ones.
+fn link_phrases(d: &Decision, links: &[crate::model::Link], id: impl Fn(&str) -> String) -> Vec<String> {
+    links
+        .iter()
+        .map(|l| {
+            let mut phrase = if l.source_id == d.id {
+                format!("{} {}", l.kind, id(&l.target_id))
+            } else {
+                format!("{} {} this", id(&l.source_id), l.kind)
+            };
+            if let Some(ref reason) = l.reason {
+                phrase.push_str(&format!(" ({})", reason));
+            }
+            phrase
+        })
+        .collect()
+}
+
+fn xml_attrs(attrs: &[(&str, String)]) -> String {
+    attrs
+        .iter()
+        .map(|(name, value)| format!(" {}=\"{}\"", name, xml_escape(value)))
+        .collect()
+}
+
+fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// What to print instead of a listing when there is nothing to show.
+fn empty_message(ctx: &Context) -> Option<String> {
+    let omitted = ctx.selection.as_ref().map_or(&[][..], |sel| sel.omitted.as_slice());
+    match ctx.changes {
+        Some(ref changes)
+            if ctx.decisions.is_empty() && changes.superseded.is_empty() && changes.deprecated.is_empty() =>
+        {
+            Some(format!(
+                "No changes since {}.\n\nNext: dictum context --since {}\n",
+                changes.since.to_rfc3339(),
+                ctx.token.as_deref().unwrap_or_default()
+            ))
+        }
+        None if ctx.decisions.is_empty() && omitted.is_empty() => Some("No active decisions.\n".to_string()),
+        _ => None,
+    }
+}
+
+fn heading(ctx: &Context) -> String {
+    match ctx.changes {
+        Some(ref changes) => format!("# Decision changes since {}\n\n", changes.since.to_rfc3339()),
+        None => String::from("# Active Decisions\n\n"),
+    }
+}
+
+/// Entailment warnings, omitted IDs and `--since` bookkeeping, as Markdown
+/// sections after the decisions.
+fn trailer(ctx: &Context) -> String {
+    let mut out = String::new();
+    if let Some(report) = ctx.entailments.as_ref() {
+        if !report.cycles.is_empty() || !report.inactive.is_empty() {
+            out.push_str("## Entailment warnings\n\n");
+            for cycle in &report.cycles {
+                out.push_str(&format!("- entails cycle: {}\n", cycle.join(" → ")));
+            }
+            for e in &report.inactive {
+                out.push_str(&format!(
+                    "- [{}] is {} but entailed via {}\n",
+                    e.decision.id,
+                    e.decision.status,
+                    e.chain.join(" → ")
+                ));
+            }
+            out.push('\n');
+        }
+    }
+
+    if let Some(sel) = ctx.selection.as_ref().filter(|sel| !sel.omitted.is_empty()) {
+        out.push_str("## Omitted\n\n");
+        out.push_str(&format!(
+            "{} lower-priority decisions left out to fit --max-tokens {} (~{} used); fetch with `dictum decision show <id>`:\n",
+            sel.omitted.len(),
+            sel.max_tokens,
+            sel.used_tokens
+        ));
+        let ids: Vec<&str> = sel.omitted.iter().map(|d| d.id.as_str()).collect();
+        out.push_str(&format!("{}\n\n", ids.join(", ")));
+    }
+
+    if let Some(ref changes) = ctx.changes {
+        if !changes.superseded.is_empty() {
+            out.push_str("## Superseded\n\n");
+            for (id, successor) in &changes.superseded {
+                out.push_str(&format!("- [{}] → [{}]\n", id, successor));
+            }
+            out.push('\n');
+        }
+        if !changes.deprecated.is_empty() {
+            out.push_str("## Deprecated\n\n");
+            for id in &changes.deprecated {
+                out.push_str(&format!("- [{}]\n", id));
+            }
+            out.push('\n');
+        }
+        out.push_str(&format!(
+            "Next: dictum context --since {}\n",
+            ctx.token.as_deref().unwrap_or_default()
+        ));
+    }
+
+    out
 }
 
 fn capitalize(s: &str) -> String {
@@ -197,3 +811,134 @@ fn capitalize(s: &str) -> String {
         Some(f) => f.to_uppercase().to_string() + c.as_str(),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{Link, LinkKind, TestProject};
+
+    fn decision(id: &str, title: &str, scope: &str, status: Status) -> Decision {
+        Decision::test(id).with_title(title).with_scope(scope).with_status(status)
+    }
+
+    #[test]
+    fn task_keeps_active_governing_parents_outside_the_filters() {
+        let project = TestProject::new();
+        let dir = project.dictum_dir();
+        let mut store = db::open_cache(&dir).unwrap();
+        store.decision_insert(&decision("d-1", "Cache hot reads in Redis", "api", Status::Active)).unwrap();
+        store.decision_insert(&decision("d-2", "Keep p99 latency under 100ms", "platform", Status::Active)).unwrap();
+        store.decision_insert(&decision("d-3", "Run everything on one box", "platform", Status::Deprecated)).unwrap();
+        for (target, kind) in [("d-2", LinkKind::Refines), ("d-3", LinkKind::Requires)] {
+            store.link_insert(&Link {
+                source_id: "d-1".to_string(),
+                target_id: target.to_string(),
+                kind,
+                created_at: "2025-01-01T00:00:00Z".to_string(),
+                reason: None,
+            }).unwrap();
+        }
+
+        let args = ContextArgs {
+            scope: Some("api".to_string()),
+            exact: true,
+            task: Some("cache sessions in Redis".to_string()),
+            ..Default::default()
+        };
+        let ctx = collect(&*store, &dir, args, false).unwrap();
+        let ids: Vec<&str> = ctx.decisions.iter().map(|d| d.id.as_str()).collect();
+        assert_eq!(ids, ["d-1", "d-2"]);
+        let relevance = ctx.relevance.unwrap();
+        assert_eq!(relevance["d-2"].reason, "refined by d-1");
+        assert!(!relevance.contains_key("d-3"));
+    }
+
+    #[test]
+    fn json_is_an_array_until_entailments_add_their_fields() {
+        let project = TestProject::new();
+        let dir = project.dictum_dir();
+        let mut store = db::open_cache(&dir).unwrap();
+        store.decision_insert(&Decision::test("d-1").with_weight(Weight::Must).with_scope("api")).unwrap();
+        store.decision_insert(&Decision::test("d-2").with_scope("billing")).unwrap();
+        store.link_insert(&Link {
+            source_id: "d-1".to_string(),
+            target_id: "d-2".to_string(),
+            kind: LinkKind::Entails,
+            created_at: "2025-01-01T00:00:00Z".to_string(),
+            reason: None,
+        }).unwrap();
+        let json = |entailments: bool| {
+            let args = ContextArgs {
+                scope: Some("api".to_string()),
+                exact: true,
+                entailments,
+                ..Default::default()
+            };
+            json_value(&*store, &collect(&*store, &dir, args, false).unwrap()).unwrap()
+        };
+
+        let plain = json(false);
+        assert_eq!(plain.as_array().unwrap().len(), 1);
+
+        let wrapped = json(true);
+        let mut keys: Vec<&String> = wrapped.as_object().unwrap().keys().collect();
+        keys.sort();
+        assert_eq!(keys, ["decisions", "entailment_cycles", "inactive_entailments"]);
+        let entailed = &wrapped["decisions"][1];
+        assert_eq!((&entailed["id"], &entailed["binding"]), (&serde_json::json!("d-2"), &serde_json::json!(true)));
+        assert_eq!(entailed["entailed_by"], serde_json::json!(["d-1"]));
+    }
+
+    #[test]
+    fn template_gets_json_unless_another_format_is_asked_for() {
+        let project = TestProject::new();
+        let dir = project.dictum_dir();
+        let mut store = db::open_cache(&dir).unwrap();
+        store.decision_insert(&Decision::test("d-1").with_title("Use Postgres")).unwrap();
+        let template = "<script>const data = /*__DICTUM_DATA__*/null;</script>";
+        let rendered = |format: Option<&str>| {
+            let args = ContextArgs {
+                format: format.map(str::to_string),
+                template: Some("page.html".to_string()),
+                ..Default::default()
+            };
+            let ctx = collect(&*store, &dir, args, true).unwrap();
+            render(&*store, &ctx, Some(template), true).unwrap()
+        };
+
+        let json = rendered(None);
+        let data = json.strip_prefix("<script>const data = ").unwrap().strip_suffix(";</script>").unwrap();
+        let data: serde_json::Value = serde_json::from_str(data).unwrap();
+        assert_eq!(data[0]["title"], "Use Postgres");
+
+        let text = rendered(Some("text"));
+        assert!(text.contains("Use Postgres") && !text.contains("\"title\""));
+
+        let args = ContextArgs { template: Some("page.html".to_string()), ..Default::default() };
+        let ctx = collect(&*store, &dir, args, false).unwrap();
+        let err = render(&*store, &ctx, Some("{{decisions}}"), false).unwrap_err();
+        assert!(err.to_string().contains("page.html"));
+    }
+
+    #[test]
+    fn budgeted_output_fits_its_budget() {
+        let project = TestProject::new();
+        let dir = project.dictum_dir();
+        let mut store = project.open_cache();
+        for n in 0..30 {
+            let d = Decision::test(&format!("d-{}", n)).with_body("Keep the cache warm across deploys and restarts.");
+            store.decision_insert(&d).unwrap();
+        }
+        for format in ["json", "text", "markdown"] {
+            let args = ContextArgs {
+                format: Some(format.to_string()),
+                max_tokens: Some(400),
+                ..Default::default()
+            };
+            let ctx = collect(&*store, &dir, args, false).unwrap();
+            let selection = ctx.selection.as_ref().unwrap();
+            assert!(!selection.kept.is_empty() && !selection.omitted.is_empty(), "{}", format);
+            assert!(budget::estimate(&render(&*store, &ctx, None, false).unwrap()) <= 400, "{}", format);
+        }
+    }
+}
@@ -0,0 +1,436 @@
+//! `dictum diff`: what changed between two decision snapshots. Either side can
+//! be the live store, an export file, or an export as of a git revision, and
+//! all three are read into the model `dictum export` writes, so the comparison
+//! does not depend on where a snapshot came from.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+use std::process::Command;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::db;
+use crate::error::{DictumError, Result};
+use crate::format::OutputFormat;
+use crate::model::{Decision, Link, Status};
+
+/// The side spelled this way is the live store
+pub const STORE: &str = "store";
+
+/// Fields that change as a side effect of others and are not reported as amendments
+const BOOKKEEPING: &[&str] = &["status", "superseded_by", "labels", "updated_at"];
+
+/// Decisions keyed by ID, and links keyed by (source, kind, target)
+pub struct Snapshot {
+    pub decisions: BTreeMap<String, Decision>,
+    pub links: BTreeMap<(String, String, String), Link>,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct Diff {
+    pub from: String,
+    pub to: String,
+    pub added: Vec<Decision>,
+    pub removed: Vec<Decision>,
+    pub amended: Vec<Amended>,
+    pub status_changed: Vec<StatusChange>,
+    pub relabeled: Vec<Relabeled>,
+    pub links_added: Vec<Link>,
+    pub links_removed: Vec<Link>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Amended {
+    pub id: String,
+    pub title: String,
+    pub changes: Vec<FieldChange>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FieldChange {
+    pub field: String,
+    pub from: Value,
+    pub to: Value,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StatusChange {
+    pub id: String,
+    pub title: String,
+    pub from: Status,
+    pub to: Status,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub superseded_by: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Relabeled {
+    pub id: String,
+    pub title: String,
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+}
+
+impl Diff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.amended.is_empty()
+            && self.status_changed.is_empty()
+            && self.relabeled.is_empty()
+            && self.links_added.is_empty()
+            && self.links_removed.is_empty()
+    }
+}
+
+pub fn run(
+    path: &Path,
+    from: &str,
+    to: &str,
+    file: Option<String>,
+    fmt: Option<String>,
+    is_tty: bool,
+) -> Result<()> {
+    let file = export_file(path, file)?;
+    let diff = diff(from, to, &load(path, from, &file)?, &load(path, to, &file)?)?;
+
+    if matches!(fmt.as_deref(), Some("markdown" | "md")) {
+        print!("{}", format_diff_markdown(&diff));
+        return Ok(());
+    }
+    match OutputFormat::from_str_or_auto(fmt.as_deref(), is_tty) {
+        OutputFormat::Text => print!("{}", format_diff_text(&diff)),
+        _ => println!("{}", serde_json::to_string_pretty(&diff)?),
+    }
+    Ok(())
+}
+
+/// The export a bare revision is read from: `--file`, or else the configured one.
+fn export_file(path: &Path, file: Option<String>) -> Result<String> {
+    match file {
+        Some(file) => Ok(file),
+        None => Ok(crate::config::Config::load(&path.join(".dictum"))?.export),
+    }
+}
+
+/// Read one side: `store`, an existing file, `REV:PATH`, or a bare revision
+/// of `file`.
+pub fn load(path: &Path, spec: &str, file: &str) -> Result<Snapshot> {
+    if spec == STORE {
+        let dictum_dir = path.join(".dictum");
+        crate::cli::ensure_init(&dictum_dir)?;
+        let store = db::open(&dictum_dir)?;
+        let mut snapshot = Snapshot {
+            decisions: BTreeMap::new(),
+            links: BTreeMap::new(),
+        };
+        for d in store.decision_get_all()? {
+            for link in store.links_for_decision(&d.id)? {
+                snapshot.links.insert(link_key(&link), link);
+            }
+            snapshot.decisions.insert(d.id.clone(), d);
+        }
+        return Ok(snapshot);
+    }
+    let candidate = path.join(spec);
+    if candidate.is_file() {
+        return parse(&std::fs::read_to_string(candidate)?);
+    }
+    if spec.starts_with('-') {
+        return Err(DictumError::InvalidDiffSide(spec.to_string()));
+    }
+
+    // `./` keeps a bare revision's path relative to `path`, as the default is
+    let object = if spec.contains(':') {
+        spec.to_string()
+    } else {
+        format!("{}:./{}", spec, file)
+    };
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(path)
+        .args(["show", &object])
+        .output()
+        .map_err(|e| DictumError::InvalidDiffSide(format!("{} (git: {})", spec, e)))?;
+    if !output.status.success() {
+        return Err(DictumError::InvalidDiffSide(object));
+    }
+    parse(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Read export lines, as `dictum import` does.
+pub fn parse(text: &str) -> Result<Snapshot> {
+    let mut snapshot = Snapshot {
+        decisions: BTreeMap::new(),
+        links: BTreeMap::new(),
+    };
+    for line in text.lines().filter(|l| !l.trim().is_empty()) {
+        let value: Value = serde_json::from_str(line)?;
+        let links: Vec<Link> = value
+            .get("links")
+            .and_then(|l| serde_json::from_value(l.clone()).ok())
+            .unwrap_or_default();
+        for link in links {
+            snapshot.links.insert(link_key(&link), link);
+        }
+        let decision: Decision = serde_json::from_value(value)?;
+        snapshot.decisions.insert(decision.id.clone(), decision);
+    }
+    Ok(snapshot)
+}
+
+fn link_key(link: &Link) -> (String, String, String) {
+    (link.source_id.clone(), link.kind.to_string(), link.target_id.clone())
+}
+
+pub fn diff(from: &str, to: &str, a: &Snapshot, b: &Snapshot) -> Result<Diff> {
+    let mut diff = Diff {
+        from: from.to_string(),
+        to: to.to_string(),
+        ..Default::default()
+    };
+    diff.removed = a.decisions.values().filter(|d| !b.decisions.contains_key(&d.id)).cloned().collect();
+    diff.added = b.decisions.values().filter(|d| !a.decisions.contains_key(&d.id)).cloned().collect();
+
+    for (id, old) in &a.decisions {
+        let Some(new) = b.decisions.get(id) else { continue };
+
+        let (old_fields, new_fields) = (serde_json::to_value(old)?, serde_json::to_value(new)?);
+        let (old_fields, new_fields) = (old_fields.as_object(), new_fields.as_object());
+        let names: BTreeSet<&String> = old_fields.into_iter().chain(new_fields).flat_map(|m| m.keys()).collect();
+        let changes: Vec<FieldChange> = names
+            .into_iter()
+            .filter(|name| !BOOKKEEPING.contains(&name.as_str()))
+            .filter_map(|name| {
+                let before = old_fields.and_then(|m| m.get(name)).cloned().unwrap_or(Value::Null);
+                let after = new_fields.and_then(|m| m.get(name)).cloned().unwrap_or(Value::Null);
+                (before != after).then(|| FieldChange {
+                    field: name.clone(),
+                    from: before,
+                    to: after,
+                })
+            })
+            .collect();
+        if !changes.is_empty() {
+            diff.amended.push(Amended {
+                id: id.clone(),
+                title: new.title.clone(),
+                changes,
+            });
+        }
+
+        if old.status != new.status {
+            diff.status_changed.push(StatusChange {
+                id: id.clone(),
+                title: new.title.clone(),
+                from: old.status.clone(),
+                to: new.status.clone(),
+                superseded_by: new.superseded_by.clone(),
+            });
+        }
+
+        let (old_labels, new_labels): (BTreeSet<&String>, BTreeSet<&String>) =
+            (old.labels.iter().collect(), new.labels.iter().collect());
+        if old_labels != new_labels {
+            diff.relabeled.push(Relabeled {
+                id: id.clone(),
+                title: new.title.clone(),
+                added: new_labels.difference(&old_labels).map(|l| l.to_string()).collect(),
+                removed: old_labels.difference(&new_labels).map(|l| l.to_string()).collect(),
+            });
+        }
+    }
+
+    diff.links_removed = a.links.iter().filter(|(k, _)| !b.links.contains_key(*k)).map(|(_, l)| l.clone()).collect();
+    diff.links_added = b.links.iter().filter(|(k, _)| !a.links.contains_key(*k)).map(|(_, l)| l.clone()).collect();
+    Ok(diff)
+}
+
+/// Scalars bare, anything else as JSON
+fn show_value(v: &Value) -> String {
+    match v {
+        Value::Null => "(none)".to_string(),
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+fn summary(diff: &Diff) -> String {
+    let counts = [
+        (diff.added.len(), "added"),
+        (diff.removed.len(), "removed"),
+        (diff.amended.len(), "amended"),
+        (diff.status_changed.len(), "status changed"),
+        (diff.relabeled.len(), "relabeled"),
+        (diff.links_added.len(), "link(s) added"),
+        (diff.links_removed.len(), "link(s) removed"),
+    ];
+    counts
+        .iter()
+        .filter(|(n, _)| *n > 0)
+        .map(|(n, what)| format!("{} {}", n, what))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn format_diff_text(diff: &Diff) -> String {
+    if diff.is_empty() {
+        return format!("No changes between {} and {}.\n", diff.from, diff.to);
+    }
+    let mut out = format!("{} -> {}: {}\n", diff.from, diff.to, summary(diff));
+    for d in &diff.added {
+        out.push_str(&format!("\n+ [{}] ({}/{}) {}\n", d.id, d.kind, d.weight, d.title));
+    }
+    for d in &diff.removed {
+        out.push_str(&format!("\n- [{}] ({}/{}) {}\n", d.id, d.kind, d.weight, d.title));
+    }
+    for a in &diff.amended {
+        out.push_str(&format!("\n~ [{}] {}\n", a.id, a.title));
+        for c in &a.changes {
+            out.push_str(&format!("    {}: {} -> {}\n", c.field, show_value(&c.from), show_value(&c.to)));
+        }
+    }
+    for s in &diff.status_changed {
+        let by = s.superseded_by.as_ref().map(|id| format!(" by {}", id)).unwrap_or_default();
+        out.push_str(&format!("\n~ [{}] {}\n    status: {} -> {}{}\n", s.id, s.title, s.from, s.to, by));
+    }
+    for r in &diff.relabeled {
+        let labels: Vec<String> = r
+            .added
+            .iter()
+            .map(|l| format!("+{}", l))
+            .chain(r.removed.iter().map(|l| format!("-{}", l)))
+            .collect();
+        out.push_str(&format!("\n~ [{}] {}\n    labels: {}\n", r.id, r.title, labels.join(" ")));
+    }
+    if !diff.links_added.is_empty() || !diff.links_removed.is_empty() {
+        out.push_str("\nLinks:\n");
+        for l in &diff.links_added {
+            out.push_str(&format!("  + {} --{}--> {}\n", l.source_id, l.kind, l.target_id));
+        }
+        for l in &diff.links_removed {
+            out.push_str(&format!("  - {} --{}--> {}\n", l.source_id, l.kind, l.target_id));
+        }
+    }
+    out
+}
+
+/// Markdown for pasting into a pull request description.
+fn format_diff_markdown(diff: &Diff) -> String {
+    let mut out = String::from("## Decision changes\n\n");
+    if diff.is_empty() {
+        out.push_str(&format!("No changes between `{}` and `{}`.\n", diff.from, diff.to));
+        return out;
+    }
+    out.push_str(&format!("`{}` → `{}`: {}\n", diff.from, diff.to, summary(diff)));
+
+    let mut section = |title: &str, lines: Vec<String>| {
+        if !lines.is_empty() {
+            out.push_str(&format!("\n### {}\n\n{}\n", title, lines.join("\n")));
+        }
+    };
+    let decision = |d: &Decision| format!("- `{}` **{}** ({}/{})", d.id, d.title, d.kind, d.weight);
+    section("Added", diff.added.iter().map(decision).collect());
+    section("Removed", diff.removed.iter().map(decision).collect());
+    section(
+        "Amended",
+        diff.amended
+            .iter()
+            .map(|a| {
+                let changes: Vec<String> = a
+                    .changes
+                    .iter()
+                    .map(|c| format!("  - {}: `{}` → `{}`", c.field, show_value(&c.from), show_value(&c.to)))
+                    .collect();
+                format!("- `{}` **{}**\n{}", a.id, a.title, changes.join("\n"))
+            })
+            .collect(),
+    );
+    section(
+        "Status changes",
+        diff.status_changed
+            .iter()
+            .map(|s| {
+                let by = s.superseded_by.as_ref().map(|id| format!(" by `{}`", id)).unwrap_or_default();
+                format!("- `{}` **{}**: {} → {}{}", s.id, s.title, s.from, s.to, by)
+            })
+            .collect(),
+    );
+    section(
+        "Labels",
+        diff.relabeled
+            .iter()
+            .map(|r| {
+                let labels: Vec<String> = r
+                    .added
+                    .iter()
+                    .map(|l| format!("+`{}`", l))
+                    .chain(r.removed.iter().map(|l| format!("−`{}`", l)))
+                    .collect();
+                format!("- `{}` **{}**: {}", r.id, r.title, labels.join(" "))
+            })
+            .collect(),
+    );
+    section(
+        "Links",
+        diff.links_added
+            .iter()
+            .map(|l| format!("- added: `{}` {} `{}`", l.source_id, l.kind, l.target_id))
+            .chain(
+                diff.links_removed
+                    .iter()
+                    .map(|l| format!("- removed: `{}` {} `{}`", l.source_id, l.kind, l.target_id)),
+            )
+            .collect(),
+    );
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::TestProject;
+
+    #[test]
+    fn reports_each_kind_of_change() {
+        let line = |id: &str, title: &str, status: &str, weight: &str, labels: &str, links: &str| {
+            format!(
+                r#"{{"id":"{id}","title":"{title}","level":"tactical","status":"{status}","author":"t","created_at":"2025-01-01T00:00:00Z","updated_at":"2025-01-01T00:00:00Z","labels":[{labels}],"kind":"rule","weight":"{weight}","links":[{links}]}}"#
+            ) + "\n"
+        };
+        let link = r#"{"source_id":"d-1","target_id":"d-2","kind":"supports","created_at":"2025-01-01T00:00:00Z"}"#;
+        let before = line("d-1", "Cache sessions", "active", "should", r#""perf""#, link)
+            + &line("d-2", "Keep latency low", "active", "must", "", link)
+            + &line("d-3", "Use XML", "active", "may", "", "");
+        let after = line("d-1", "Cache sessions in Redis", "superseded", "must", r#""api""#, "")
+            + &line("d-2", "Keep latency low", "active", "must", "", "")
+            + &line("d-4", "Use JSON", "active", "should", "", "");
+
+        let d = diff("a", "b", &parse(&before).unwrap(), &parse(&after).unwrap()).unwrap();
+        let ids = |ds: &[Decision]| ds.iter().map(|d| d.id.clone()).collect::<Vec<_>>();
+        assert_eq!((ids(&d.added), ids(&d.removed)), (vec!["d-4".to_string()], vec!["d-3".to_string()]));
+        let fields: Vec<&str> = d.amended[0].changes.iter().map(|c| c.field.as_str()).collect();
+        assert_eq!((d.amended.len(), fields), (1, vec!["title", "weight"]));
+        assert_eq!((d.status_changed[0].from.clone(), d.status_changed[0].to.clone()), (Status::Active, Status::Superseded));
+        assert_eq!((d.relabeled[0].added.clone(), d.relabeled[0].removed.clone()), (vec!["api".to_string()], vec!["perf".to_string()]));
+        assert!(d.links_added.is_empty());
+        assert_eq!(d.links_removed.len(), 1);
+
+        let markdown = format_diff_markdown(&d);
+        assert!(markdown.contains("### Status changes\n\n- `d-1` **Cache sessions in Redis**: active → superseded"));
+        assert!(diff("a", "a", &parse(&before).unwrap(), &parse(&before).unwrap()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn bare_revisions_read_the_configured_export() {
+        let project = TestProject::empty();
+        let root = &project.root;
+        std::fs::create_dir_all(project.dictum_dir()).unwrap();
+        assert_eq!(export_file(root, None).unwrap(), ".dictum/decisions.jsonl");
+
+        std::fs::write(root.join(".dictum/config.toml"), "export = \".dictum/dictum.jsonl\"\n").unwrap();
+        assert_eq!(export_file(root, None).unwrap(), ".dictum/dictum.jsonl");
+        assert_eq!(export_file(root, Some("out.jsonl".to_string())).unwrap(), "out.jsonl");
+    }
+}
@@ -0,0 +1,144 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use serde_json::Value;
+
+use crate::db::{self, oplog};
+use crate::error::{DictumError, Result};
+use crate::format::{self, OutputFormat};
+
+pub struct GqlArgs {
+    pub query: String,
+    pub params: Vec<String>,
+    pub write: bool,
+    pub format: Option<String>,
+}
+
+pub fn run(path: &Path, args: GqlArgs, is_tty: bool) -> Result<()> {
+    let dictum_dir = path.join(".dictum");
+    crate::cli::ensure_init(&dictum_dir)?;
+
+    let mut query_params = HashMap::new();
+    for param in &args.params {
+        let (key, value) = parse_param(param)?;
+        query_params.insert(key, db::grafeo::json_to_value(&value));
+    }
+
+    let result = if args.write {
+        write(&dictum_dir, &args.query, query_params)?
+    } else {
+        db::open_grafeo(&dictum_dir)?.query_raw(&args.query, query_params, false)?
+    };
+
+    let fmt = match args.format.as_deref() {
+        Some("table") => OutputFormat::Text,
+        other => OutputFormat::from_str_or_auto(other, is_tty),
+    };
+    let output = match fmt {
+        OutputFormat::Json | OutputFormat::Compact => {
+            serde_json::to_string_pretty(&row_objects(&result.columns, &result.rows))? + "\n"
+        }
+        OutputFormat::Jsonl => {
+            let mut out = String::new();
+            for row in row_objects(&result.columns, &result.rows) {
+                out.push_str(&serde_json::to_string(&row)?);
+                out.push('\n');
+            }
+            out
+        }
+        _ => {
+            let cells: Vec<Vec<String>> = result
+                .rows
+                .iter()
+                .map(|row| row.iter().map(cell_text).collect())
+                .collect();
+            format::text::format_table(&result.columns, &cells)
+        }
+    };
+    print!("{}", output);
+
+    Ok(())
+}
+
+/// Run a mutating query and log what it changed, so a rebuild keeps it.
+/// A change the log has no op for (a deletion) is undone by replaying the log.
+fn write(
+    dictum_dir: &Path,
+    query: &str,
+    params: HashMap<String, ::grafeo::Value>,
+) -> Result<db::grafeo::RawResult> {
+    // Bring a stale database up to date before diffing against it.
+    drop(db::open(dictum_dir)?);
+    let store = db::open_grafeo(dictum_dir)?;
+    oplog::seed(dictum_dir, &store)?;
+    let before = oplog::Contents::of(&store)?;
+    let result = store.query_raw(query, params, true)?;
+    let after = oplog::Contents::of(&store)?;
+    match before.changes_to(&after, &oplog::now()) {
+        Ok(changes) => {
+            oplog::append(dictum_dir, &oplog::now(), changes)?;
+            Ok(result)
+        }
+        Err(reason) => {
+            drop(store);
+            oplog::rebuild(dictum_dir)?;
+            Err(DictumError::UnloggableWrite(reason))
+        }
+    }
+}
+
+/// `key=value`; the value is read as JSON when it parses (numbers, booleans,
+/// null, lists, quoted strings) and as a plain string otherwise.
+fn parse_param(param: &str) -> Result<(String, Value)> {
+    let (key, raw) = param
+        .split_once('=')
+        .filter(|(k, _)| !k.is_empty())
+        .ok_or_else(|| DictumError::InvalidParam(param.to_string()))?;
+    let value = serde_json::from_str(raw).unwrap_or_else(|_| Value::from(raw));
+    Ok((key.trim_start_matches('$').to_string(), value))
+}
+
+fn row_objects(columns: &[String], rows: &[Vec<Value>]) -> Vec<Value> {
+    rows.iter()
+        .map(|row| {
+            columns
+                .iter()
+                .cloned()
+                .zip(row.iter().cloned())
+                .collect::<serde_json::Map<_, _>>()
+                .into()
+        })
+        .collect()
+}
+
+fn cell_text(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn params_parse_as_json_or_string() {
+        assert_eq!(parse_param("n=3").unwrap(), ("n".to_string(), Value::from(3)));
+        assert_eq!(
+            parse_param("$kind=rule").unwrap(),
+            ("kind".to_string(), Value::from("rule"))
+        );
+        assert_eq!(
+            parse_param("id=\"42\"").unwrap(),
+            ("id".to_string(), Value::from("42"))
+        );
+        assert_eq!(
+            parse_param("q=a=b").unwrap(),
+            ("q".to_string(), Value::from("a=b"))
+        );
+        assert!(parse_param("novalue").is_err());
+        assert!(parse_param("=x").is_err());
+    }
+}
@@ -1,4 +1,5 @@
 use std::path::Path;
+use std::process::Command;
 
 use crate::config::Config;
 use crate::error::{DictumError, Result};
@@ -21,6 +22,9 @@ pub fn run(path: &Path) -> Result<()> {
     // Initialize database (open handles CREATE TABLE IF NOT EXISTS internally)
     let _store = crate::db::open(&dictum_dir)?;
 
+    // Start the operation log; it is tracked, the database is not
+    std::fs::write(crate::db::oplog::log_path(&dictum_dir), "")?;
+
     // Write .gitignore for backend-specific db artifacts
     let gitignore = crate::db::compiled_backend_gitignore();
     std::fs::write(dictum_dir.join(".gitignore"), gitignore)?;
@@ -28,3 +32,56 @@ pub fn run(path: &Path) -> Result<()> {
     println!("Initialized dictum in {}", dictum_dir.display());
     Ok(())
 }
+
+/// The .gitattributes line routing dictum's JSONL files to the merge driver
+const GIT_ATTRIBUTE: &str = ".dictum/*.jsonl merge=dictum";
+
+/// `init`, and with `git` also register the merge driver. An existing
+/// .dictum/ is kept, so `init --git` can be run in a clone; its .gitignore
+/// gets any entries added since it was written.
+pub fn run_with_git(path: &Path, git: bool) -> Result<()> {
+    if !git || !path.join(".dictum").exists() {
+        run(path)?;
+    } else {
+        crate::db::update_gitignore(&path.join(".dictum"))?;
+    }
+    if git {
+        install_merge_driver(path)?;
+    }
+    Ok(())
+}
+
+fn install_merge_driver(path: &Path) -> Result<()> {
+    let attributes = path.join(".gitattributes");
+    let existing = std::fs::read_to_string(&attributes).unwrap_or_default();
+    if !existing.lines().any(|l| l.trim() == GIT_ATTRIBUTE) {
+        let sep = if existing.is_empty() || existing.ends_with('\n') { "" } else { "\n" };
+        std::fs::write(&attributes, format!("{}{}{}\n", existing, sep, GIT_ATTRIBUTE))?;
+        println!("Added `{}` to {}", GIT_ATTRIBUTE, attributes.display());
+    }
+
+    // Driver definitions live in .git/config, which is not shared, so every
+    // clone needs this step
+    let settings = [
+        ("merge.dictum.name", "dictum record-level merge"),
+        ("merge.dictum.driver", "dictum merge-driver %O %A %B"),
+    ];
+    for (key, value) in settings {
+        let configured = Command::new("git")
+            .arg("-C")
+            .arg(path)
+            .args(["config", key, value])
+            .status()
+            .map(|s| s.success())
+            .unwrap_or(false);
+        if !configured {
+            eprintln!("Warning: could not run `git config`; in each clone, run:");
+            for (key, value) in settings {
+                eprintln!("  git config {} \"{}\"", key, value);
+            }
+            return Ok(());
+        }
+    }
+    println!("Registered the dictum merge driver in this clone's git config");
+    Ok(())
+}
@@ -0,0 +1,166 @@
+use std::path::Path;
+
+use crate::cli::context::ContextArgs;
+use crate::config::{Config, InstructionTarget};
+use crate::db::{self, Store};
+use crate::error::{DictumError, Result};
+use crate::model::{Kind, Weight};
+
+const BEGIN: &str = "<!-- dictum:begin -->";
+const END: &str = "<!-- dictum:end -->";
+const NOTE: &str = "<!-- Generated by `dictum sync-instructions`; edits between these markers are overwritten. -->";
+
+/// Render decisions into every configured instruction file. With `check`,
+/// nothing is written and stale files are an error.
+pub fn run(path: &Path, check: bool) -> Result<()> {
+    let dictum_dir = path.join(".dictum");
+    crate::cli::ensure_init(&dictum_dir)?;
+
+    let config = Config::load(&dictum_dir)?;
+    if config.instructions.is_empty() {
+        return Err(DictumError::Config(
+            "no instruction files configured: add [[instructions]] entries with a path to .dictum/config.toml"
+                .to_string(),
+        ));
+    }
+
+    let store = db::open(&dictum_dir)?;
+    let mut stale = 0;
+    for target in &config.instructions {
+        let file = path.join(&target.path);
+        let block = render(&*store, path, target)?;
+        let existing = match std::fs::read_to_string(&file) {
+            Ok(content) => Some(content),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+            Err(e) => return Err(e.into()),
+        };
+        let updated = splice(existing.as_deref(), &block)
+            .ok_or_else(|| DictumError::InvalidMarkers(target.path.clone()))?;
+
+        if existing.as_deref() == Some(updated.as_str()) {
+            println!("Up to date: {}", target.path);
+        } else if check {
+            println!("Out of date: {}", target.path);
+            stale += 1;
+        } else {
+            if let Some(parent) = file.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(&file, &updated)?;
+            let verb = if existing.is_some() { "Updated" } else { "Created" };
+            println!("{}: {}", verb, target.path);
+        }
+    }
+
+    if stale > 0 {
+        return Err(DictumError::InstructionsOutOfDate(stale));
+    }
+    Ok(())
+}
+
+/// The managed block for one target, markers included.
+fn render(store: &dyn Store, root: &Path, target: &InstructionTarget) -> Result<String> {
+    let args = ContextArgs {
+        format: Some("text".to_string()),
+        kind: target.kind.as_deref().map(str::parse::<Kind>).transpose()?,
+        weight: target.weight.as_deref().map(str::parse::<Weight>).transpose()?,
+        scope: target.scope.clone(),
+        exact: false,
+        where_expr: target.where_expr.clone(),
+        view: target.view.clone(),
+        entailments: target.entailments,
+        as_of: None,
+        max_tokens: target.max_tokens,
+        task: None,
+        since: None,
+        template: None,
+    };
+    let ctx = crate::cli::context::collect(store, &root.join(".dictum"), args, false)?;
+    let decisions = crate::cli::context::text_string(store, &ctx)?;
+
+    let body = match target.template {
+        Some(ref template) => {
+            let text = std::fs::read_to_string(root.join(template))?;
+            crate::format::template::fill(&text, template, decisions.trim_end())?
+        }
+        None => decisions,
+    };
+    Ok(format!("{}\n{}\n{}\n{}", BEGIN, NOTE, body.trim_end(), END))
+}
+
+/// Put `block` between the markers in `existing`, or append it when the file
+/// has none. Everything outside the markers is left byte for byte. `None` if
+/// the markers are unbalanced.
+fn splice(existing: Option<&str>, block: &str) -> Option<String> {
+    let Some(text) = existing else {
+        return Some(format!("{}\n", block));
+    };
+    if text.matches(BEGIN).count() > 1 || text.matches(END).count() > 1 {
+        return None;
+    }
+    match (text.find(BEGIN), text.find(END)) {
+        (Some(begin), Some(end)) if begin < end => {
+            Some(format!("{}{}{}", &text[..begin], block, &text[end + END.len()..]))
+        }
+        (None, None) => {
+            let separator = match text {
+                "" => "",
+                t if t.ends_with("\n\n") => "",
+                t if t.ends_with('\n') => "\n",
+                _ => "\n\n",
+            };
+            Some(format!("{}{}{}\n", text, separator, block))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::TestProject;
+
+    #[test]
+    fn splices_between_markers_only() {
+        let block = format!("{}\nnew\n{}", BEGIN, END);
+        assert_eq!(splice(None, &block).unwrap(), format!("{}\n", block));
+
+        let appended = splice(Some("# Agents\n\nBe nice."), &block).unwrap();
+        assert_eq!(appended, format!("# Agents\n\nBe nice.\n\n{}\n", block));
+        // Running again replaces the block in place and changes nothing else
+        assert_eq!(splice(Some(&appended), &block).unwrap(), appended);
+
+        let edited = appended.replace("new", "stale\nlines");
+        let edited = format!("{}\nHand-written footer\n", edited);
+        assert_eq!(
+            splice(Some(&edited), &block).unwrap(),
+            format!("# Agents\n\nBe nice.\n\n{}\n\nHand-written footer\n", block)
+        );
+
+        assert!(splice(Some(&format!("{}\nno end", BEGIN)), &block).is_none());
+        assert!(splice(Some(&format!("{}\n{}", END, BEGIN)), &block).is_none());
+    }
+
+    #[test]
+    fn template_placeholder_takes_the_rendered_decisions() {
+        let project = TestProject::new();
+        let mut store = project.open_cache();
+        store.decision_insert(&crate::model::Decision::test("d-1").with_title("Use Postgres")).unwrap();
+        std::fs::write(project.root.join("claude.tmpl"), "# Rules\n\n/*__DICTUM_DATA__*/null\n\nAsk first.\n").unwrap();
+        std::fs::write(project.root.join("old.tmpl"), "{{decisions}}\n").unwrap();
+        let target = |template: &str| InstructionTarget {
+            path: "CLAUDE.md".to_string(),
+            template: Some(template.to_string()),
+            ..Default::default()
+        };
+
+        let block = render(&*store, &project.root, &target("claude.tmpl")).unwrap();
+        let body = block.lines().skip(2).collect::<Vec<_>>();
+        assert_eq!(body.first(), Some(&"# Rules"));
+        assert!(block.contains("Use Postgres"));
+        assert!(block.ends_with(&format!("Ask first.\n{}", END)));
+
+        let err = render(&*store, &project.root, &target("old.tmpl")).unwrap_err();
+        assert!(err.to_string().contains("old.tmpl"));
+    }
+}
@@ -1,10 +1,17 @@
+use std::cmp::Ordering;
+use std::collections::BTreeSet;
 use std::io::{self, BufRead, IsTerminal, Write};
 use std::path::Path;
+use std::str::FromStr;
 
-use crate::db;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::db::{self, Store};
 use crate::error::{DictumError, Result};
 use crate::format::json::format_export_line;
-use crate::model::{Decision, Link};
+use crate::format::OutputFormat;
+use crate::model::{Decision, Link, Status, Transition};
 
 pub fn run_export(path: &Path, output_file: Option<String>) -> Result<()> {
     let dictum_dir = path.join(".dictum");
@@ -20,7 +27,8 @@ pub fn run_export(path: &Path, output_file: Option<String>) -> Result<()> {
 
     for d in &decisions {
         let links = store.links_for_decision(&d.id)?;
-        let line = format_export_line(d, &links)?;
+        let transitions = store.transitions(Some(&d.id))?;
+        let line = format_export_line(d, &links, &transitions)?;
         writeln!(writer, "{}", line)?;
     }
 
@@ -31,7 +39,189 @@ pub fn run_export(path: &Path, output_file: Option<String>) -> Result<()> {
     Ok(())
 }
 
-pub fn run_import(path: &Path, input_file: Option<String>, dry_run: bool) -> Result<()> {
+/// Recreate the database from the operation log.
+pub fn run_rebuild(path: &Path, fmt: Option<String>, is_tty: bool) -> Result<()> {
+    let dictum_dir = path.join(".dictum");
+    crate::cli::ensure_init(&dictum_dir)?;
+
+    let rebuilt = db::oplog::rebuild(&dictum_dir)?;
+    db::update_gitignore(&dictum_dir)?;
+
+    let format = OutputFormat::from_str_or_auto(fmt.as_deref(), is_tty);
+    match format {
+        OutputFormat::Text => {
+            println!(
+                "Rebuilt {} decisions from {} operations",
+                rebuilt.decisions, rebuilt.ops
+            );
+            for s in &rebuilt.skipped {
+                println!("  skipped {}: {}", s.op, s.reason);
+            }
+        }
+        _ => println!("{}", serde_json::to_string_pretty(&rebuilt)?),
+    }
+    Ok(())
+}
+
+/// What `import` does with a decision whose ID is already stored.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportMode {
+    /// Take incoming fields when newer, merge labels and links, never regress status
+    #[default]
+    Upsert,
+    /// Keep the stored decision as it is
+    Skip,
+    /// Take every incoming field, status included; labels and links are still merged
+    Overwrite,
+}
+
+impl FromStr for ImportMode {
+    type Err = DictumError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "upsert" => Ok(ImportMode::Upsert),
+            "skip" => Ok(ImportMode::Skip),
+            "overwrite" => Ok(ImportMode::Overwrite),
+            _ => Err(DictumError::InvalidImportMode(s.to_string())),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Action {
+    Added,
+    Updated,
+    Unchanged,
+    Skipped,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Side {
+    Local,
+    Incoming,
+}
+
+/// A field the stored and incoming copies disagree on, and which one won.
+#[derive(Debug, Serialize)]
+pub struct Conflict {
+    pub field: String,
+    pub local: Value,
+    pub incoming: Value,
+    pub kept: Side,
+}
+
+/// How one incoming decision was reconciled.
+#[derive(Debug, Serialize)]
+pub struct Outcome {
+    pub id: String,
+    pub title: String,
+    pub action: Action,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub conflicts: Vec<Conflict>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub labels_added: Vec<String>,
+    #[serde(skip_serializing_if = "is_zero")]
+    pub links_added: usize,
+}
+
+#[derive(Serialize)]
+struct ImportReport<'a> {
+    mode: ImportMode,
+    dry_run: bool,
+    decisions: &'a [Outcome],
+}
+
+fn is_zero(n: &usize) -> bool {
+    *n == 0
+}
+
+/// Later statuses are never undone by an upsert: a superseded, deprecated or
+/// rejected decision does not become active again, nor an active one a draft.
+fn status_rank(status: &Status) -> u8 {
+    match status {
+        Status::Draft => 0,
+        Status::Active => 1,
+        Status::Superseded | Status::Deprecated | Status::Rejected => 2,
+    }
+}
+
+fn is_newer(incoming: &str, local: &str) -> bool {
+    match (
+        chrono::DateTime::parse_from_rfc3339(incoming),
+        chrono::DateTime::parse_from_rfc3339(local),
+    ) {
+        (Ok(i), Ok(l)) => i > l,
+        _ => incoming > local,
+    }
+}
+
+/// Reconcile a stored decision with an incoming copy of it, field by field.
+/// Labels are not compared here; they are merged separately.
+pub fn merge(local: &Decision, incoming: &Decision, mode: ImportMode) -> Result<(Decision, Vec<Conflict>)> {
+    let newer = is_newer(&incoming.updated_at, &local.updated_at);
+    let status_side = match status_rank(&incoming.status).cmp(&status_rank(&local.status)) {
+        Ordering::Greater => Side::Incoming,
+        Ordering::Less => Side::Local,
+        Ordering::Equal if newer => Side::Incoming,
+        Ordering::Equal => Side::Local,
+    };
+
+    let Value::Object(local_fields) = serde_json::to_value(local)? else {
+        unreachable!("decisions serialize to objects")
+    };
+    let Value::Object(incoming_fields) = serde_json::to_value(incoming)? else {
+        unreachable!("decisions serialize to objects")
+    };
+    let names: BTreeSet<&String> = local_fields.keys().chain(incoming_fields.keys()).collect();
+
+    let mut merged = local_fields.clone();
+    let mut conflicts = Vec::new();
+    for name in names {
+        if matches!(name.as_str(), "id" | "labels" | "updated_at") {
+            continue;
+        }
+        let l = local_fields.get(name).cloned().unwrap_or(Value::Null);
+        let i = incoming_fields.get(name).cloned().unwrap_or(Value::Null);
+        if l == i {
+            continue;
+        }
+        let kept = match mode {
+            ImportMode::Skip => Side::Local,
+            ImportMode::Overwrite => Side::Incoming,
+            ImportMode::Upsert if matches!(name.as_str(), "status" | "superseded_by") => status_side,
+            ImportMode::Upsert if newer => Side::Incoming,
+            ImportMode::Upsert => Side::Local,
+        };
+        if kept == Side::Incoming {
+            merged.insert(name.clone(), i.clone());
+        }
+        conflicts.push(Conflict {
+            field: name.clone(),
+            local: l,
+            incoming: i,
+            kept,
+        });
+    }
+
+    let mut merged: Decision = serde_json::from_value(Value::Object(merged))?;
+    if mode == ImportMode::Overwrite || (mode == ImportMode::Upsert && newer) {
+        merged.updated_at = incoming.updated_at.clone();
+    }
+    Ok((merged, conflicts))
+}
+
+pub fn run_import(
+    path: &Path,
+    input_file: Option<String>,
+    mode: ImportMode,
+    dry_run: bool,
+    fmt: Option<String>,
+    is_tty: bool,
+) -> Result<()> {
     let dictum_dir = path.join(".dictum");
     crate::cli::ensure_init(&dictum_dir)?;
 
@@ -48,8 +238,7 @@ pub fn run_import(path: &Path, input_file: Option<String>, dry_run: bool) -> Res
     };
 
     let mut store = db::open(&dictum_dir)?;
-    let mut count = 0;
-    let mut link_count = 0;
+    let mut outcomes = Vec::new();
 
     for line in reader.lines() {
         let line = line?;
@@ -57,42 +246,334 @@ pub fn run_import(path: &Path, input_file: Option<String>, dry_run: bool) -> Res
             continue;
         }
 
-        let value: serde_json::Value = serde_json::from_str(&line)?;
-        let decision: Decision = serde_json::from_value(value.clone())?;
+        let value: Value = serde_json::from_str(&line)?;
+        if value.get(crate::cli::merge::CONFLICT_KEY).is_some() {
+            let id = value.get("id").and_then(Value::as_str).unwrap_or_default();
+            return Err(DictumError::UnresolvedConflict(id.to_string()));
+        }
+        let incoming: Decision = serde_json::from_value(value.clone())?;
+        let transitions: Vec<Transition> = match value.get("transitions") {
+            Some(t) => serde_json::from_value(t.clone())?,
+            None => Vec::new(),
+        };
+        let links: Vec<Link> = value
+            .get("links")
+            .and_then(|l| serde_json::from_value(l.clone()).ok())
+            .unwrap_or_default();
+        outcomes.push(import_one(&mut *store, incoming, &transitions, &links, mode, dry_run)?);
+    }
 
-        if dry_run {
-            println!("Would import: [{}] {}", decision.id, decision.title);
-        } else {
-            match store.decision_insert(&decision) {
-                Ok(_) => {}
-                Err(DictumError::DecisionAlreadyExists) => continue,
-                Err(e) => return Err(e),
-            }
+    let format = OutputFormat::from_str_or_auto(fmt.as_deref(), is_tty);
+    match format {
+        OutputFormat::Text => print!("{}", format_import_text(&outcomes, dry_run)),
+        _ => {
+            let report = ImportReport {
+                mode,
+                dry_run,
+                decisions: &outcomes,
+            };
+            println!("{}", serde_json::to_string_pretty(&report)?);
+        }
+    }
 
-            for label in &decision.labels {
-                store.label_add(&decision.id, label)?;
-            }
+    let count = |action: Action| outcomes.iter().filter(|o| o.action == action).count();
+    let links: usize = outcomes.iter().map(|o| o.links_added).sum();
+    eprintln!(
+        "{} {} added, {} updated, {} unchanged, {} skipped; {} links",
+        if dry_run { "Dry run:" } else { "Imported:" },
+        count(Action::Added),
+        count(Action::Updated),
+        count(Action::Unchanged),
+        count(Action::Skipped),
+        links
+    );
+
+    Ok(())
+}
+
+/// The change from `local` to `merged` that the imported `transitions` do
+/// not already record, if any. A status change they record is left out, and
+/// so are the fields they record previous values for.
+fn uncovered_transition(
+    local: &Decision,
+    merged: &Decision,
+    transitions: &[&Transition],
+    at: String,
+) -> Option<Transition> {
+    let mut t = Transition::between(local, merged, at)?;
+    t.from_fields
+        .retain(|name, _| !transitions.iter().any(|i| i.from_fields.contains_key(name)));
+    let status_recorded = t.from_status != t.to_status
+        && transitions
+            .iter()
+            .any(|i| i.from_status != i.to_status && i.to_status == t.to_status);
+    if status_recorded || (t.from_status == t.to_status && t.from_superseded_by == merged.superseded_by) {
+        if t.from_fields.is_empty() {
+            return None;
+        }
+        t.from_status = t.to_status.clone();
+        t.from_superseded_by = merged.superseded_by.clone();
+    }
+    Some(t)
+}
+
+fn import_one(
+    store: &mut dyn Store,
+    incoming: Decision,
+    transitions: &[Transition],
+    links: &[Link],
+    mode: ImportMode,
+    dry_run: bool,
+) -> Result<Outcome> {
+    let local = match store.decision_get(&incoming.id) {
+        Ok(d) => Some(d),
+        Err(DictumError::DecisionNotFound(_)) => None,
+        Err(e) => return Err(e),
+    };
+
+    let known_transitions = store.transitions(Some(&incoming.id)).unwrap_or_default();
+    let new_transitions: Vec<&Transition> =
+        transitions.iter().filter(|t| !known_transitions.contains(t)).collect();
 
-            if let Some(links) = value.get("links") {
-                if let Ok(links) = serde_json::from_value::<Vec<Link>>(links.clone()) {
-                    for link in &links {
-                        match store.link_insert(link) {
-                            Ok(_) => link_count += 1,
-                            Err(DictumError::LinkAlreadyExists) => {}
-                            Err(e) => return Err(e),
-                        }
-                    }
+    let (action, conflicts, labels_added) = match local {
+        None => {
+            if !dry_run {
+                store.decision_insert(&incoming)?;
+            }
+            (Action::Added, Vec::new(), incoming.labels.clone())
+        }
+        Some(ref local) => {
+            let (merged, conflicts) = merge(local, &incoming, mode)?;
+            if mode == ImportMode::Skip {
+                let action = if conflicts.is_empty() { Action::Unchanged } else { Action::Skipped };
+                return Ok(Outcome {
+                    id: incoming.id,
+                    title: incoming.title,
+                    action,
+                    conflicts,
+                    labels_added: Vec::new(),
+                    links_added: 0,
+                });
+            }
+            let changed = merged != *local;
+            if changed && !dry_run {
+                store.decision_replace(&merged)?;
+                // Recorded so `--as-of` still shows what was replaced
+                let at = incoming.updated_at.clone();
+                if let Some(t) = uncovered_transition(local, &merged, &new_transitions, at) {
+                    store.transition_insert(&t)?;
                 }
             }
+            let labels_added: Vec<String> = incoming
+                .labels
+                .iter()
+                .filter(|l| !local.labels.contains(l))
+                .cloned()
+                .collect();
+            let action = if changed || !labels_added.is_empty() {
+                Action::Updated
+            } else {
+                Action::Unchanged
+            };
+            (action, conflicts, labels_added)
         }
-        count += 1;
-    }
+    };
+
+    let known_links = store.links_for_decision(&incoming.id).unwrap_or_default();
+    let new_links: Vec<&Link> = links
+        .iter()
+        .filter(|l| {
+            !known_links
+                .iter()
+                .any(|k| k.source_id == l.source_id && k.kind == l.kind && k.target_id == l.target_id)
+        })
+        .collect();
 
+    let mut links_added = 0;
     if dry_run {
-        eprintln!("Dry run: {} decisions would be imported", count);
+        links_added = new_links.len();
     } else {
-        eprintln!("Imported {} decisions, {} links", count, link_count);
+        for label in &labels_added {
+            store.label_add(&incoming.id, label)?;
+        }
+        for t in &new_transitions {
+            store.transition_insert(t)?;
+        }
+        for link in new_links {
+            match store.link_insert(link) {
+                Ok(_) => links_added += 1,
+                Err(DictumError::LinkAlreadyExists) => {}
+                Err(e @ DictumError::LinkCycle { .. }) => {
+                    eprintln!(
+                        "Skipped link {} {} {}: {}",
+                        link.source_id, link.kind, link.target_id, e
+                    );
+                }
+                Err(e) => return Err(e),
+            }
+        }
     }
 
-    Ok(())
+    let action = match action {
+        Action::Unchanged if links_added > 0 => Action::Updated,
+        other => other,
+    };
+    Ok(Outcome {
+        id: incoming.id,
+        title: incoming.title,
+        action,
+        conflicts,
+        labels_added: if action == Action::Added { Vec::new() } else { labels_added },
+        links_added,
+    })
+}
+
+fn format_import_text(outcomes: &[Outcome], dry_run: bool) -> String {
+    let mut out = String::new();
+    for o in outcomes {
+        let verb = match (o.action, dry_run) {
+            (Action::Unchanged, _) if o.conflicts.is_empty() => continue,
+            (Action::Unchanged, _) => "Kept",
+            (Action::Added, false) => "Added",
+            (Action::Added, true) => "Would add",
+            (Action::Updated, false) => "Updated",
+            (Action::Updated, true) => "Would update",
+            (Action::Skipped, _) => "Skipped",
+        };
+        out.push_str(&format!("{}: [{}] {}\n", verb, o.id, o.title));
+        for c in &o.conflicts {
+            let kept = match c.kept {
+                Side::Local => "kept local",
+                Side::Incoming => "took incoming",
+            };
+            out.push_str(&format!(
+                "  {}: {} -> {} ({})\n",
+                c.field,
+                show_value(&c.local),
+                show_value(&c.incoming),
+                kept
+            ));
+        }
+        if !o.labels_added.is_empty() {
+            out.push_str(&format!("  labels: +{}\n", o.labels_added.join(", +")));
+        }
+        if o.links_added > 0 && o.action != Action::Added {
+            out.push_str(&format!("  links: +{}\n", o.links_added));
+        }
+    }
+    out
+}
+
+fn show_value(v: &Value) -> String {
+    match v {
+        Value::Null => "(none)".to_string(),
+        Value::String(s) => format!("{:?}", s),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{TestProject, Weight};
+
+    fn decision(status: Status, updated_at: &str) -> Decision {
+        let mut d = Decision::test("d-1").with_title("Cache sessions").with_status(status);
+        d.updated_at = updated_at.to_string();
+        d
+    }
+
+    /// A superseded local copy and a newer incoming one that is active and a must.
+    fn sides() -> (Decision, Decision) {
+        let mut local = decision(Status::Superseded, "2025-02-01T00:00:00Z");
+        local.superseded_by = Some("d-2".to_string());
+        let incoming = decision(Status::Active, "2025-03-01T00:00:00+01:00").with_weight(Weight::Must);
+        (local, incoming)
+    }
+
+    #[test]
+    fn upsert_takes_newer_fields_without_regressing_status() {
+        let (local, incoming) = sides();
+        let (merged, conflicts) = merge(&local, &incoming, ImportMode::Upsert).unwrap();
+        assert_eq!((merged.status.clone(), merged.weight.clone()), (Status::Superseded, Weight::Must));
+        assert_eq!(merged.superseded_by.as_deref(), Some("d-2"));
+        assert_eq!(merged.updated_at, incoming.updated_at);
+        let kept: Vec<(&str, Side)> = conflicts.iter().map(|c| (c.field.as_str(), c.kept)).collect();
+        assert_eq!(
+            kept,
+            [("status", Side::Local), ("superseded_by", Side::Local), ("weight", Side::Incoming)]
+        );
+    }
+
+    #[test]
+    fn upsert_of_an_older_copy_only_moves_status_forward() {
+        let older = decision(Status::Deprecated, "2025-01-15T00:00:00Z").with_title("Cache nothing");
+        let active = decision(Status::Active, "2025-02-01T00:00:00Z");
+        let (merged, _) = merge(&active, &older, ImportMode::Upsert).unwrap();
+        assert_eq!((merged.status, merged.title.as_str()), (Status::Deprecated, "Cache sessions"));
+        assert_eq!(merged.updated_at, active.updated_at);
+    }
+
+    #[test]
+    fn overwrite_and_skip_take_one_side_whole() {
+        let (local, incoming) = sides();
+        let (merged, _) = merge(&local, &incoming, ImportMode::Overwrite).unwrap();
+        assert_eq!(merged, incoming);
+        let (merged, conflicts) = merge(&local, &incoming, ImportMode::Skip).unwrap();
+        assert_eq!((merged, conflicts.len()), (local, 3));
+    }
+
+    /// An export of d-1 deprecated at `at`, holding the transition that did it.
+    fn deprecated_export(title: &str, at: &str) -> (Decision, Vec<Transition>) {
+        let incoming = decision(Status::Deprecated, at).with_title(title);
+        let transition = Transition {
+            decision_id: "d-1".to_string(),
+            from_status: Status::Active,
+            to_status: Status::Deprecated,
+            from_superseded_by: None,
+            from_fields: Default::default(),
+            at: at.to_string(),
+        };
+        (incoming, vec![transition])
+    }
+
+    #[test]
+    fn importing_an_exported_status_change_records_it_once() {
+        let (ours, theirs) = (TestProject::new(), TestProject::new());
+        let original = decision(Status::Active, "2025-01-01T00:00:00Z");
+        for project in [&ours, &theirs] {
+            db::open(&project.dictum_dir()).unwrap().decision_insert(&original).unwrap();
+        }
+        db::open(&theirs.dictum_dir()).unwrap().decision_update_status("d-1", &Status::Deprecated, None).unwrap();
+        let export = theirs.root.join("export.jsonl");
+        run_export(&theirs.root, Some(export.display().to_string())).unwrap();
+
+        let import = |project: &TestProject| {
+            run_import(&project.root, Some(export.display().to_string()), ImportMode::Upsert, false, Some("json".to_string()), false)
+                .unwrap();
+        };
+        import(&ours);
+        import(&ours);
+        let store = db::open(&ours.dictum_dir()).unwrap();
+        assert_eq!(store.decision_get("d-1").unwrap().status, Status::Deprecated);
+        let recorded = store.transitions(Some("d-1")).unwrap();
+        assert_eq!(recorded, db::open(&theirs.dictum_dir()).unwrap().transitions(Some("d-1")).unwrap());
+        assert_eq!(recorded.len(), 1);
+    }
+
+    #[test]
+    fn changes_the_export_does_not_record_are_dated_by_it() {
+        let project = TestProject::new();
+        let mut store = project.open_cache();
+        store.decision_insert(&decision(Status::Active, "2025-01-01T00:00:00Z")).unwrap();
+        let (incoming, transitions) = deprecated_export("Cache nothing", "2025-02-01T00:00:00Z");
+
+        import_one(&mut *store, incoming, &transitions, &[], ImportMode::Upsert, false).unwrap();
+        let recorded = store.transitions(Some("d-1")).unwrap();
+        assert_eq!(recorded.len(), 2);
+        let edit = recorded.iter().find(|t| t != &&transitions[0]).unwrap();
+        assert_eq!((&edit.from_status, &edit.to_status), (&Status::Deprecated, &Status::Deprecated));
+        assert_eq!(edit.from_fields["title"], "Cache sessions");
+        assert_eq!(edit.at, "2025-02-01T00:00:00Z");
+    }
 }
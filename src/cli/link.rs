@@ -1,6 +1,6 @@
 use std::path::Path;
 
-use crate::db;
+use crate::db::{self, Store};
 use crate::error::Result;
 use crate::model::{Link, LinkKind, Status};
 
@@ -9,8 +9,20 @@ pub fn run_link(path: &Path, source_id: &str, kind: &str, target_id: &str, reaso
     crate::cli::ensure_init(&dictum_dir)?;
 
     let mut store = db::open(&dictum_dir)?;
-    let kind: LinkKind = kind.parse()?;
+    let link = insert(&mut *store, source_id, kind.parse()?, target_id, reason)?;
+
+    println!("Linked: {} {} {}", source_id, link.kind, target_id);
+    Ok(())
+}
 
+/// Link two existing decisions; a `supersedes` link also marks the target superseded.
+pub fn insert(
+    store: &mut dyn Store,
+    source_id: &str,
+    kind: LinkKind,
+    target_id: &str,
+    reason: Option<String>,
+) -> Result<Link> {
     store.decision_get(source_id)?;
     store.decision_get(target_id)?;
 
@@ -27,9 +39,7 @@ pub fn run_link(path: &Path, source_id: &str, kind: &str, target_id: &str, reaso
     if kind == LinkKind::Supersedes {
         store.decision_update_status(target_id, &Status::Superseded, Some(source_id))?;
     }
-
-    println!("Linked: {} {} {}", source_id, link.kind, target_id);
-    Ok(())
+    Ok(link)
 }
 
 pub fn run_unlink(path: &Path, source_id: &str, kind: &str, target_id: &str) -> Result<()> {
@@ -1,7 +1,7 @@
 use std::path::Path;
 
 use crate::db;
-use crate::db::ListFilter;
+use crate::db::{ListFilter, Sort};
 use crate::error::Result;
 use crate::format::{self, OutputFormat};
 use crate::model::{Kind, Level, Status, Weight};
@@ -15,13 +15,45 @@ pub struct ListArgs {
     pub kind: Option<String>,
     pub weight: Option<String>,
     pub scope: Option<String>,
+    pub exact: bool,
+    pub where_expr: Option<String>,
+    pub sort: Option<String>,
+    pub limit: Option<usize>,
+    pub offset: usize,
+    pub fields: Option<String>,
+    pub view: Option<String>,
+    pub as_of: Option<String>,
 }
 
 pub fn run(path: &Path, args: ListArgs, is_tty: bool) -> Result<()> {
     let dictum_dir = path.join(".dictum");
     crate::cli::ensure_init(&dictum_dir)?;
 
-    let store = db::open(&dictum_dir)?;
+    let store = db::open_as_of(&dictum_dir, path, args.as_of.as_deref())?;
+
+    // Explicit flags take precedence over the view's values
+    let view = match args.view {
+        Some(ref name) => crate::cli::view::load(&dictum_dir, name)?,
+        None => Default::default(),
+    };
+    let args = ListArgs {
+        tree: args.tree || view.tree,
+        level: args.level.or(view.level.clone()),
+        status: args.status.or(view.status.clone()),
+        label: args.label.or(view.label.clone()),
+        format: args.format.or(view.format.clone()),
+        kind: args.kind.or(view.kind.clone()),
+        weight: args.weight.or(view.weight.clone()),
+        scope: args.scope.or(view.scope.clone()),
+        exact: args.exact || view.exact,
+        where_expr: crate::cli::view::merge_where(args.where_expr, &view),
+        sort: args.sort.or(view.sort.clone()),
+        limit: args.limit,
+        offset: args.offset,
+        fields: args.fields.or(view.fields.clone()),
+        view: args.view,
+        as_of: None,
+    };
 
     let level = args.level.map(|l| l.parse::<Level>()).transpose()?;
     let status = args.status.map(|s| s.parse::<Status>()).transpose()?;
@@ -35,7 +67,13 @@ pub fn run(path: &Path, args: ListArgs, is_tty: bool) -> Result<()> {
         kind,
         weight,
         scope: args.scope,
+        scope_exact: args.exact,
+        expr: args.where_expr.as_deref().map(db::expr::parse).transpose()?,
+        sort: args.sort.as_deref().map(str::parse::<Sort>).transpose()?,
+        limit: args.limit,
+        offset: args.offset,
     };
+    let fields = args.fields.as_deref().map(format::parse_fields).transpose()?;
 
     let decisions = store.decision_list(&filter)?;
     let fmt = OutputFormat::from_str_or_auto(args.format.as_deref(), is_tty);
@@ -45,18 +83,21 @@ pub fn run(path: &Path, args: ListArgs, is_tty: bool) -> Result<()> {
         let output = crate::format::tree::format_tree(&decisions, &refines_links);
         print!("{}", output);
     } else {
-        let output = format::format_decision_list(&decisions, &fmt)?;
+        let output = match fields {
+            Some(ref fields) => format::format_decision_fields(&decisions, fields, &fmt)?,
+            None => format::format_decision_list(&decisions, &fmt)?,
+        };
         print!("{}", output);
     }
 
     Ok(())
 }
 
-pub fn run_tree(path: &Path) -> Result<()> {
+pub fn run_tree(path: &Path, as_of: Option<String>) -> Result<()> {
     let dictum_dir = path.join(".dictum");
     crate::cli::ensure_init(&dictum_dir)?;
 
-    let store = db::open(&dictum_dir)?;
+    let store = db::open_as_of(&dictum_dir, path, as_of.as_deref())?;
     let decisions = store.decision_get_all()?;
     let refines_links = store.links_of_kind(&crate::model::LinkKind::Refines)?;
     let output = crate::format::tree::format_tree(&decisions, &refines_links);
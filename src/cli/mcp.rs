@@ -0,0 +1,680 @@
+//! `dictum mcp`: a Model Context Protocol server over stdio. Messages are
+//! newline-delimited JSON-RPC 2.0. Tools and resources go through the same
+//! `Store` calls and context rendering as the CLI, and the store is opened per
+//! request so the CLI can still be used while a server is running.
+
+use std::cell::RefCell;
+use std::io::{self, BufRead, Write};
+use std::path::Path;
+
+use serde_json::{json, Map, Value};
+
+use crate::cli::add::AddArgs;
+use crate::cli::context::ContextArgs;
+use crate::config::Config;
+use crate::db::{self, ListFilter, SearchQuery};
+use crate::error::{DictumError, Result};
+use crate::format::json as json_format;
+use crate::model::{Kind, Level, LinkKind, Origin, Status, Weight};
+use crate::reasoning::consistency;
+
+const PROTOCOL_VERSIONS: &[&str] = &["2025-06-18", "2025-03-26", "2024-11-05"];
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+const CONTEXT_URI: &str = "dictum://context";
+const DECISION_URI: &str = "dictum://decisions/";
+
+pub fn run(path: &Path) -> Result<()> {
+    let dictum_dir = path.join(".dictum");
+    crate::cli::ensure_init(&dictum_dir)?;
+
+    let server = Server {
+        path,
+        client: RefCell::new(None),
+    };
+    let stdin = io::stdin();
+    let mut stdout = io::stdout().lock();
+    for line in stdin.lock().lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        if let Some(response) = server.handle_line(&line) {
+            writeln!(stdout, "{}", serde_json::to_string(&response)?)?;
+            stdout.flush()?;
+        }
+    }
+    Ok(())
+}
+
+/// A JSON-RPC error, as opposed to a tool that ran and failed.
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+impl RpcError {
+    fn new(code: i64, message: impl Into<String>) -> Self {
+        RpcError {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+struct Server<'a> {
+    path: &'a Path,
+    /// `clientInfo.name` from `initialize`, the default agent for proposals
+    client: RefCell<Option<String>>,
+}
+
+impl Server<'_> {
+    fn dictum_dir(&self) -> std::path::PathBuf {
+        self.path.join(".dictum")
+    }
+
+    /// Handle one message; notifications get no response.
+    fn handle_line(&self, line: &str) -> Option<Value> {
+        let message: Value = match serde_json::from_str(line) {
+            Ok(v) => v,
+            Err(e) => return Some(error_response(Value::Null, RpcError::new(PARSE_ERROR, e.to_string()))),
+        };
+        if message.is_array() {
+            return Some(error_response(Value::Null, RpcError::new(INVALID_REQUEST, "batch requests are not supported")));
+        }
+        let id = message.get("id").cloned();
+        let Some(method) = message.get("method").and_then(Value::as_str) else {
+            return id.map(|id| error_response(id, RpcError::new(INVALID_REQUEST, "missing method")));
+        };
+        let params = message.get("params").cloned().unwrap_or(Value::Null);
+        let result = self.dispatch(method, &params);
+        let id = id?;
+        Some(match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err(e) => error_response(id, e),
+        })
+    }
+
+    fn dispatch(&self, method: &str, params: &Value) -> std::result::Result<Value, RpcError> {
+        match method {
+            "initialize" => {
+                let client = params.pointer("/clientInfo/name").and_then(Value::as_str);
+                *self.client.borrow_mut() = client.map(str::to_string);
+                Ok(initialize(params))
+            }
+            "ping" => Ok(json!({})),
+            "tools/list" => Ok(json!({ "tools": tool_definitions() })),
+            "tools/call" => {
+                let name = params
+                    .get("name")
+                    .and_then(Value::as_str)
+                    .ok_or_else(|| RpcError::new(INVALID_PARAMS, "missing tool name"))?;
+                let empty = Value::Object(Map::new());
+                let args = params.get("arguments").unwrap_or(&empty);
+                Ok(match self.call_tool(name, args) {
+                    Some(Ok(value)) => tool_result(value, false),
+                    Some(Err(e @ DictumError::InvalidArgument(_))) => {
+                        return Err(RpcError::new(INVALID_PARAMS, e.to_string()))
+                    }
+                    Some(Err(e)) => tool_result(Value::String(e.to_string()), true),
+                    None => return Err(RpcError::new(INVALID_PARAMS, format!("unknown tool: {}", name))),
+                })
+            }
+            "resources/list" => self
+                .list_resources()
+                .map_err(|e| RpcError::new(INVALID_PARAMS, e.to_string())),
+            "resources/templates/list" => Ok(json!({
+                "resourceTemplates": [{
+                    "uriTemplate": format!("{}{{id}}", DECISION_URI),
+                    "name": "decision",
+                    "description": "A decision with its links",
+                    "mimeType": "application/json",
+                }]
+            })),
+            "resources/read" => {
+                let uri = params
+                    .get("uri")
+                    .and_then(Value::as_str)
+                    .ok_or_else(|| RpcError::new(INVALID_PARAMS, "missing uri"))?;
+                self.read_resource(uri)
+                    .map_err(|e| RpcError::new(INVALID_PARAMS, e.to_string()))
+            }
+            _ if method.starts_with("notifications/") => Ok(Value::Null),
+            _ => Err(RpcError::new(METHOD_NOT_FOUND, format!("method not found: {}", method))),
+        }
+    }
+
+    fn call_tool(&self, name: &str, args: &Value) -> Option<Result<Value>> {
+        Some(match name {
+            "get_context" => self.get_context(args),
+            "ask" => self.ask(args),
+            "search_decisions" => self.search_decisions(args),
+            "show_decision" => self.show_decision(args),
+            "propose_decision" => self.propose_decision(args),
+            "link_decisions" => self.link_decisions(args),
+            "check_conflicts" => self.check_conflicts(args),
+            _ => return None,
+        })
+    }
+
+    fn get_context(&self, args: &Value) -> Result<Value> {
+        let dictum_dir = self.dictum_dir();
+        let as_of = string_arg(args, "as_of")?;
+        let store = db::open_as_of(&dictum_dir, self.path, as_of.as_deref())?;
+        let context_args = ContextArgs {
+            format: Some("compact".to_string()),
+            kind: string_arg(args, "kind")?.map(|s| s.parse::<Kind>()).transpose()?,
+            weight: string_arg(args, "weight")?.map(|s| s.parse::<Weight>()).transpose()?,
+            scope: string_arg(args, "scope")?,
+            exact: bool_arg(args, "exact")?,
+            where_expr: string_arg(args, "where")?,
+            view: string_arg(args, "view")?,
+            entailments: bool_arg(args, "entailments")?,
+            as_of,
+            max_tokens: int_arg(args, "max_tokens")?.map(|n| n as usize),
+            task: string_arg(args, "task")?,
+            since: string_arg(args, "since")?,
+            template: None,
+        };
+        let ctx = crate::cli::context::collect(&*store, &dictum_dir, context_args, false)?;
+        Ok(match crate::cli::context::compact_value(&*store, &ctx)? {
+            Value::Array(decisions) => json!({ "decisions": decisions }),
+            wrapped => wrapped,
+        })
+    }
+
+    fn ask(&self, args: &Value) -> Result<Value> {
+        let dictum_dir = self.dictum_dir();
+        let store = db::open(&dictum_dir)?;
+        let active = store.decision_list(&ListFilter {
+            status: Some(Status::Active),
+            scope: string_arg(args, "scope")?,
+            ..Default::default()
+        })?;
+        let answer = crate::cli::ask::answer(
+            &required_string(args, "question")?,
+            &active,
+            &store.decision_get_all()?,
+            &store.links_of_kind(&LinkKind::Refines)?,
+            int_arg(args, "top")?.map_or(5, |n| n as usize),
+            crate::cli::ask::MIN_SCORE,
+        )?;
+        Ok(serde_json::to_value(answer)?)
+    }
+
+    fn search_decisions(&self, args: &Value) -> Result<Value> {
+        let mut query = SearchQuery::parse(&required_string(args, "query")?)?;
+        query.status = string_arg(args, "status")?
+            .map(|s| s.parse::<Status>())
+            .transpose()?;
+        let store = db::open(&self.dictum_dir())?;
+        let hits: Vec<Value> = store
+            .decision_search(&query)?
+            .iter()
+            .map(json_format::search_hit_to_value)
+            .collect();
+        Ok(json!({ "hits": hits }))
+    }
+
+    fn show_decision(&self, args: &Value) -> Result<Value> {
+        let id = required_string(args, "id")?;
+        let store = db::open(&self.dictum_dir())?;
+        if let Some(depth) = int_arg(args, "depth")? {
+            let depth = u32::try_from(depth).map_err(|_| invalid_argument("depth", "too large"))?;
+            return Ok(json_format::neighborhood_to_value(&store.neighborhood(&id, depth)?));
+        }
+        let decision = store.decision_get(&id)?;
+        let links = store.links_for_decision(&id)?;
+        Ok(json!({ "decision": json_format::decision_to_value(&decision, Some(&links)) }))
+    }
+
+    fn propose_decision(&self, args: &Value) -> Result<Value> {
+        let dictum_dir = self.dictum_dir();
+        let config = Config::load(&dictum_dir)?;
+        let add_args = AddArgs {
+            title: required_string(args, "title")?,
+            level: parse_or(args, "level", Level::Tactical)?,
+            parent: string_arg(args, "parent")?,
+            label: string_list_arg(args, "labels")?,
+            body: string_arg(args, "body")?,
+            author: string_arg(args, "author")?,
+            format: None,
+            kind: parse_or(args, "kind", Kind::Choice)?,
+            weight: parse_or(args, "weight", Weight::Should)?,
+            rebuttal: string_arg(args, "rebuttal")?,
+            scope: string_arg(args, "scope")?,
+            strict: false,
+            origin: Some(Origin {
+                agent: string_arg(args, "agent")?.or_else(|| self.client.borrow().clone()),
+                session: string_arg(args, "session")?,
+                source: string_arg(args, "source")?,
+                rejection: None,
+            }),
+        };
+        let mut store = db::open(&dictum_dir)?;
+        let added = crate::cli::add::insert(&mut *store, &dictum_dir, &config, add_args, Status::Draft)?;
+        let mut result = json!({ "decision": added.decision });
+        if let Some((similar, title)) = added.similar {
+            result["similar_to"] = json!({ "id": similar.id, "score": similar.score, "title": title });
+        }
+        Ok(result)
+    }
+
+    fn link_decisions(&self, args: &Value) -> Result<Value> {
+        let mut store = db::open(&self.dictum_dir())?;
+        let link = crate::cli::link::insert(
+            &mut *store,
+            &required_string(args, "source")?,
+            required_string(args, "kind")?.parse()?,
+            &required_string(args, "target")?,
+            string_arg(args, "reason")?,
+        )?;
+        Ok(json!({ "link": link }))
+    }
+
+    fn check_conflicts(&self, args: &Value) -> Result<Value> {
+        let ids = string_list_arg(args, "ids")?;
+        if ids.is_empty() {
+            return Err(invalid_argument("ids", "pass at least one decision ID"));
+        }
+        let store = db::open(&self.dictum_dir())?;
+        let candidates = ids
+            .iter()
+            .map(|id| store.decision_get(id))
+            .collect::<Result<Vec<_>>>()?;
+        let report = consistency::check(&*store, &candidates, &[])?;
+        let mut value = serde_json::to_value(&report)?;
+        value["consistent"] = Value::Bool(report.is_consistent());
+        Ok(value)
+    }
+
+    fn list_resources(&self) -> Result<Value> {
+        let store = db::open(&self.dictum_dir())?;
+        let active = store.decision_list(&ListFilter {
+            status: Some(Status::Active),
+            ..Default::default()
+        })?;
+        let mut resources = vec![json!({
+            "uri": CONTEXT_URI,
+            "name": "active-decisions",
+            "title": "Active decisions",
+            "description": "Every active decision, as `dictum context --format compact` prints it",
+            "mimeType": "application/json",
+        })];
+        for d in &active {
+            resources.push(json!({
+                "uri": format!("{}{}", DECISION_URI, d.id),
+                "name": d.id,
+                "title": d.title,
+                "mimeType": "application/json",
+            }));
+        }
+        Ok(json!({ "resources": resources }))
+    }
+
+    fn read_resource(&self, uri: &str) -> Result<Value> {
+        let value = if uri == CONTEXT_URI {
+            self.get_context(&Value::Object(Map::new()))?
+        } else if let Some(id) = uri.strip_prefix(DECISION_URI) {
+            self.show_decision(&json!({ "id": id }))?
+        } else {
+            return Err(invalid_argument("uri", &format!("unknown resource {}", uri)));
+        };
+        Ok(json!({
+            "contents": [{
+                "uri": uri,
+                "mimeType": "application/json",
+                "text": serde_json::to_string(&value)?,
+            }]
+        }))
+    }
+}
+
+fn initialize(params: &Value) -> Value {
+    let requested = params.get("protocolVersion").and_then(Value::as_str);
+    let version = requested
+        .filter(|v| PROTOCOL_VERSIONS.contains(v))
+        .unwrap_or(PROTOCOL_VERSIONS[0]);
+    json!({
+        "protocolVersion": version,
+        "capabilities": { "tools": {}, "resources": {} },
+        "serverInfo": { "name": "dictum", "version": env!("CARGO_PKG_VERSION") },
+        "instructions": "Check dictum's decisions before making a choice the project may already have made. \
+            Use get_context (optionally with a task) to load what applies, and propose_decision for new \
+            ones; proposals are drafts until a human accepts them.",
+    })
+}
+
+fn error_response(id: Value, error: RpcError) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": error.code, "message": error.message },
+    })
+}
+
+/// Tool output as text for every client, and as structured content for
+/// clients that read it.
+fn tool_result(value: Value, is_error: bool) -> Value {
+    let text = match &value {
+        Value::String(s) => s.clone(),
+        v => serde_json::to_string(v).unwrap_or_default(),
+    };
+    let mut result = json!({
+        "content": [{ "type": "text", "text": text }],
+        "isError": is_error,
+    });
+    if value.is_object() {
+        result["structuredContent"] = value;
+    }
+    result
+}
+
+fn invalid_argument(name: &str, problem: &str) -> DictumError {
+    DictumError::InvalidArgument(format!("{}: {}", name, problem))
+}
+
+fn string_arg(args: &Value, name: &str) -> Result<Option<String>> {
+    match args.get(name) {
+        None | Some(Value::Null) => Ok(None),
+        Some(Value::String(s)) => Ok(Some(s.clone())),
+        Some(_) => Err(invalid_argument(name, "expected a string")),
+    }
+}
+
+fn required_string(args: &Value, name: &str) -> Result<String> {
+    string_arg(args, name)?.ok_or_else(|| invalid_argument(name, "required"))
+}
+
+fn bool_arg(args: &Value, name: &str) -> Result<bool> {
+    match args.get(name) {
+        None | Some(Value::Null) => Ok(false),
+        Some(Value::Bool(b)) => Ok(*b),
+        Some(_) => Err(invalid_argument(name, "expected a boolean")),
+    }
+}
+
+fn int_arg(args: &Value, name: &str) -> Result<Option<u64>> {
+    match args.get(name) {
+        None | Some(Value::Null) => Ok(None),
+        Some(v) => v
+            .as_u64()
+            .map(Some)
+            .ok_or_else(|| invalid_argument(name, "expected a non-negative integer")),
+    }
+}
+
+fn string_list_arg(args: &Value, name: &str) -> Result<Vec<String>> {
+    match args.get(name) {
+        None | Some(Value::Null) => Ok(Vec::new()),
+        Some(Value::Array(items)) => items
+            .iter()
+            .map(|v| {
+                v.as_str()
+                    .map(str::to_string)
+                    .ok_or_else(|| invalid_argument(name, "expected an array of strings"))
+            })
+            .collect(),
+        Some(_) => Err(invalid_argument(name, "expected an array of strings")),
+    }
+}
+
+fn parse_or<T>(args: &Value, name: &str, default: T) -> Result<T>
+where
+    T: std::str::FromStr<Err = DictumError>,
+{
+    string_arg(args, name)?.map_or(Ok(default), |s| s.parse())
+}
+
+fn tool_definitions() -> Value {
+    let string = |description: &str| json!({ "type": "string", "description": description });
+    let kind = json!({
+        "type": "string",
+        "enum": ["principle", "constraint", "assumption", "choice", "rule", "goal"],
+    });
+    let weight = json!({ "type": "string", "enum": ["must", "should", "may"] });
+    json!([
+        {
+            "name": "get_context",
+            "title": "Get decision context",
+            "description": "Active decisions that apply, in dictum's compact context format. \
+                Narrow with a task description, scope or filters, and cap the size with max_tokens; \
+                omitted decision IDs are listed so they can be fetched with show_decision. \
+                Pass the returned token as since on the next call to get only what changed.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "task": string("What you are about to do; ranks decisions by relevance"),
+                    "scope": string("Slash-separated scope, e.g. billing/invoices; includes enclosing scopes"),
+                    "exact": { "type": "boolean", "description": "Match scope exactly" },
+                    "kind": kind,
+                    "weight": weight,
+                    "where": string("Filter expression, e.g. \"label:security and not scope:legacy*\""),
+                    "view": string("Name of a saved view"),
+                    "entailments": { "type": "boolean", "description": "Include decisions entailed by must-decisions" },
+                    "max_tokens": { "type": "integer", "minimum": 0, "description": "Approximate token budget" },
+                    "as_of": string("RFC3339 time, YYYY-MM-DD or git revision"),
+                    "since": string("Only changes since this time, or the token returned by an earlier call"),
+                },
+            },
+            "annotations": { "readOnlyHint": true },
+        },
+        {
+            "name": "ask",
+            "title": "Ask whether a decision applies",
+            "description": "Before guessing, ask in plain words whether something has been decided. \
+                Returns the most relevant active decisions with their weight, rebuttal and the chain \
+                of decisions they refine, or governed: false with a confidence when nothing applies.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "question": string("The question, e.g. \"should sessions be cached in Redis?\""),
+                    "scope": string("Slash-separated scope; includes enclosing scopes"),
+                    "top": { "type": "integer", "minimum": 1, "description": "Maximum number of decisions (default 5)" },
+                },
+                "required": ["question"],
+            },
+            "annotations": { "readOnlyHint": true },
+        },
+        {
+            "name": "search_decisions",
+            "title": "Search decisions",
+            "description": "Full-text search over decisions: words, \"exact phrases\", prefix*, field:term.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "query": string("Search query"),
+                    "status": { "type": "string", "enum": ["active", "superseded", "deprecated", "draft", "rejected"] },
+                },
+                "required": ["query"],
+            },
+            "annotations": { "readOnlyHint": true },
+        },
+        {
+            "name": "show_decision",
+            "title": "Show decision",
+            "description": "One decision with its links, or its N-hop neighborhood when depth is given.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "id": string("Decision ID"),
+                    "depth": { "type": "integer", "minimum": 0 },
+                },
+                "required": ["id"],
+            },
+            "annotations": { "readOnlyHint": true },
+        },
+        {
+            "name": "propose_decision",
+            "title": "Propose a decision",
+            "description": "Record a decision you made or discovered as a draft for a human to review. \
+                Reports a similar active decision if one exists, and refuses one that repeats a pending \
+                or rejected proposal (with the reason it was rejected).",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "title": string("The decision, as one statement"),
+                    "body": string("Rationale"),
+                    "level": { "type": "string", "enum": ["strategic", "tactical", "operational"] },
+                    "kind": kind,
+                    "weight": weight,
+                    "scope": string("Where it applies"),
+                    "rebuttal": string("When it can be overridden"),
+                    "labels": { "type": "array", "items": { "type": "string" } },
+                    "parent": string("ID of the decision this refines"),
+                    "author": string("Who is proposing it"),
+                    "agent": string("Agent name; defaults to the client name given at initialize"),
+                    "session": string("Session or conversation ID"),
+                    "source": string("File or diff that prompted the proposal"),
+                },
+                "required": ["title"],
+            },
+        },
+        {
+            "name": "link_decisions",
+            "title": "Link decisions",
+            "description": "Create a relationship between two decisions.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "source": string("Source decision ID"),
+                    "kind": {
+                        "type": "string",
+                        "enum": ["refines", "supports", "supersedes", "conflicts", "requires", "entails", "excludes"],
+                    },
+                    "target": string("Target decision ID"),
+                    "reason": string("Why they are related"),
+                },
+                "required": ["source", "kind", "target"],
+            },
+        },
+        {
+            "name": "check_conflicts",
+            "title": "Check conflicts",
+            "description": "Whether these decisions can be adopted together: follows requires links and \
+                reports conflicts, exclusions and inactive dependencies with explanations.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "ids": { "type": "array", "items": { "type": "string" }, "minItems": 1 },
+                },
+                "required": ["ids"],
+            },
+            "annotations": { "readOnlyHint": true },
+        },
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::TestProject;
+
+    #[test]
+    fn answers_protocol_messages() {
+        let project = TestProject::empty();
+        let server = Server {
+            path: &project.root,
+            client: RefCell::new(None),
+        };
+
+        let init = server
+            .handle_line(r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{"protocolVersion":"2024-11-05"}}"#)
+            .unwrap();
+        assert_eq!(init["result"]["protocolVersion"], "2024-11-05");
+        assert!(server
+            .handle_line(r#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#)
+            .is_none());
+
+        let tools = server.handle_line(r#"{"jsonrpc":"2.0","id":2,"method":"tools/list"}"#).unwrap();
+        let names: Vec<&str> = tools["result"]["tools"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|t| t["name"].as_str().unwrap())
+            .collect();
+        assert!(names.contains(&"get_context") && names.contains(&"propose_decision"));
+
+        let missing = server
+            .handle_line(r#"{"jsonrpc":"2.0","id":3,"method":"tools/call","params":{"name":"show_decision","arguments":{}}}"#)
+            .unwrap();
+        assert_eq!(missing["error"]["code"], INVALID_PARAMS);
+        assert_eq!(missing["error"]["message"], "invalid argument id: required");
+
+        let unknown = server.handle_line(r#"{"jsonrpc":"2.0","id":4,"method":"nope"}"#).unwrap();
+        assert_eq!(unknown["error"]["code"], METHOD_NOT_FOUND);
+        assert_eq!(server.handle_line("{").unwrap()["error"]["code"], PARSE_ERROR);
+
+        let batch = server.handle_line(r#"[{"jsonrpc":"2.0","id":5,"method":"ping"}]"#).unwrap();
+        assert_eq!(batch["error"]["code"], INVALID_REQUEST);
+        assert_eq!(batch["id"], Value::Null);
+    }
+
+    /// `tools/call` for `name`, returning the JSON-RPC response.
+    fn call(server: &Server, name: &str, arguments: Value) -> Value {
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "tools/call",
+            "params": { "name": name, "arguments": arguments },
+        });
+        server.handle_line(&request.to_string()).unwrap()
+    }
+
+    /// The structured content of a successful tool call.
+    fn content(response: Value) -> Value {
+        assert_eq!(response["result"]["isError"], false, "{}", response);
+        response["result"]["structuredContent"].clone()
+    }
+
+    #[test]
+    fn calls_each_tool_on_a_store() {
+        let project = TestProject::new();
+        {
+            let mut store = db::open(&project.dictum_dir()).unwrap();
+            store
+                .decision_insert(&crate::model::Decision::test("d-1").with_title("Cache sessions in Redis").with_scope("api"))
+                .unwrap();
+            store
+                .decision_insert(&crate::model::Decision::test("d-2").with_title("Keep services stateless"))
+                .unwrap();
+        }
+        let server = Server {
+            path: &project.root,
+            client: RefCell::new(Some("test-agent".to_string())),
+        };
+
+        let context = content(call(&server, "get_context", json!({ "scope": "api", "exact": true })));
+        assert_eq!(context["decisions"].as_array().unwrap().len(), 1);
+
+        let answer = content(call(&server, "ask", json!({ "question": "where are sessions cached?" })));
+        assert_eq!(answer["governed"], true);
+
+        let hits = content(call(&server, "search_decisions", json!({ "query": "redis" })));
+        assert_eq!(hits["hits"][0]["id"], "d-1");
+
+        let link = content(call(&server, "link_decisions", json!({ "source": "d-1", "kind": "excludes", "target": "d-2" })));
+        assert_eq!(link["link"]["kind"], "excludes");
+
+        let shown = content(call(&server, "show_decision", json!({ "id": "d-1" })));
+        assert_eq!(shown["decision"]["title"], "Cache sessions in Redis");
+        let neighborhood = content(call(&server, "show_decision", json!({ "id": "d-1", "depth": 1 })));
+        assert_eq!(neighborhood["decisions"].as_array().unwrap().len(), 2);
+        let too_deep = call(&server, "show_decision", json!({ "id": "d-1", "depth": u64::from(u32::MAX) + 1 }));
+        assert_eq!(too_deep["error"]["code"], INVALID_PARAMS);
+
+        let conflicts = content(call(&server, "check_conflicts", json!({ "ids": ["d-1", "d-2"] })));
+        assert_eq!(conflicts["issues"][0]["kind"], "internal_conflict");
+
+        let proposed = content(call(&server, "propose_decision", json!({ "title": "Log every cache miss" })));
+        assert_eq!(proposed["decision"]["status"], "draft");
+        assert_eq!(proposed["decision"]["origin"]["agent"], "test-agent");
+
+        let missing = call(&server, "show_decision", json!({ "id": "d-9" }));
+        assert_eq!(missing["result"]["isError"], true);
+    }
+}
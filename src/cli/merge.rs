@@ -0,0 +1,327 @@
+//! `dictum merge-driver`: a git merge driver for dictum's JSONL files.
+//!
+//! The operation log merges by union, since ops are only ever appended.
+//! Exports merge record by record, keyed by decision ID: each field is merged
+//! three ways against the common ancestor, labels are unioned, links (keyed by
+//! source, kind and target) are merged like fields, and the more advanced
+//! status wins. A field both sides changed differently is left on the record
+//! under `_conflict` with all three values, and the merge is reported as
+//! conflicted so git stops for it.
+
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::path::Path;
+
+use serde_json::{Map, Value};
+
+use crate::error::{DictumError, Result};
+use crate::model::Status;
+
+/// The key an unresolved record carries its conflicting fields under.
+pub const CONFLICT_KEY: &str = "_conflict";
+
+/// Merge `theirs` into `ours` (git's %A and %B) against `base` (%O), writing
+/// the result over `ours`. Fails after writing when conflicts remain.
+pub fn run(base: &Path, ours: &Path, theirs: &Path) -> Result<()> {
+    let read = |p: &Path| -> Result<String> {
+        // A file added on both sides has no ancestor
+        Ok(std::fs::read_to_string(p).unwrap_or_default())
+    };
+    let merged = merge(&read(base)?, &read(ours)?, &read(theirs)?)?;
+    std::fs::write(ours, &merged.text)?;
+    if merged.conflicts > 0 {
+        return Err(DictumError::MergeConflicts(merged.conflicts));
+    }
+    Ok(())
+}
+
+pub struct Merged {
+    pub text: String,
+    /// Records left with a `_conflict` entry
+    pub conflicts: usize,
+}
+
+pub fn merge(base: &str, ours: &str, theirs: &str) -> Result<Merged> {
+    let (base, ours, theirs) = (parse(base)?, parse(ours)?, parse(theirs)?);
+    let is_log = [&base, &ours, &theirs]
+        .iter()
+        .flat_map(|records| records.first())
+        .any(|r| r.get("op").is_some() && r.get("at").is_some());
+    if is_log {
+        return Ok(Merged {
+            text: render(&union_ops(ours, theirs)),
+            conflicts: 0,
+        });
+    }
+
+    let key = |r: &Value| r.get("id").and_then(Value::as_str).unwrap_or_default().to_string();
+    let index = |records: &[Value]| -> BTreeMap<String, Value> {
+        records.iter().map(|r| (key(r), r.clone())).collect()
+    };
+    let (base_by_id, ours_by_id, theirs_by_id) = (index(&base), index(&ours), index(&theirs));
+
+    // Our order, then records only they have, in theirs
+    let mut ids: Vec<String> = ours.iter().map(key).collect();
+    let known: HashSet<String> = ids.iter().cloned().collect();
+    ids.extend(theirs.iter().map(key).filter(|id| !known.contains(id)));
+
+    let mut records = Vec::new();
+    let mut conflicts = 0;
+    for id in ids {
+        let (b, o, t) = (base_by_id.get(&id), ours_by_id.get(&id), theirs_by_id.get(&id));
+        let record = match (o, t) {
+            (Some(o), Some(t)) => Some(merge_record(b, o, t)),
+            // Deleted on one side: gone if the other side left it alone
+            _ => match three_way(b, o, t) {
+                Ok(kept) => kept.cloned(),
+                Err(()) => {
+                    let mut record = o.or(t).cloned().unwrap_or_default();
+                    if let Value::Object(ref mut map) = record {
+                        map.insert(CONFLICT_KEY.to_string(), serde_json::json!({ "record": conflict(b, o, t) }));
+                    }
+                    Some(record)
+                }
+            },
+        };
+        if let Some(record) = record {
+            conflicts += usize::from(record.get(CONFLICT_KEY).is_some());
+            records.push(record);
+        }
+    }
+    Ok(Merged {
+        text: render(&records),
+        conflicts,
+    })
+}
+
+fn parse(text: &str) -> Result<Vec<Value>> {
+    text.lines()
+        .filter(|l| !l.trim().is_empty())
+        .map(|l| Ok(serde_json::from_str(l)?))
+        .collect()
+}
+
+fn render(records: &[Value]) -> String {
+    records.iter().map(|r| format!("{}\n", r)).collect()
+}
+
+fn union_ops(ours: Vec<Value>, theirs: Vec<Value>) -> Vec<Value> {
+    let mut seen = HashSet::new();
+    ours.into_iter()
+        .chain(theirs)
+        .filter(|op| seen.insert(op.get("id").map(Value::to_string).unwrap_or_else(|| op.to_string())))
+        .collect()
+}
+
+/// The usual three-way rule: agreement wins, otherwise the side that changed
+/// the ancestor's value. `Err` when both changed it differently.
+fn three_way<'a>(base: Option<&'a Value>, ours: Option<&'a Value>, theirs: Option<&'a Value>) -> std::result::Result<Option<&'a Value>, ()> {
+    if ours == theirs || theirs == base {
+        Ok(ours)
+    } else if ours == base {
+        Ok(theirs)
+    } else {
+        Err(())
+    }
+}
+
+/// A `_conflict` entry holding all three sides' values
+fn conflict(base: Option<&Value>, ours: Option<&Value>, theirs: Option<&Value>) -> Value {
+    serde_json::json!({
+        "base": base.cloned().unwrap_or(Value::Null),
+        "ours": ours.cloned().unwrap_or(Value::Null),
+        "theirs": theirs.cloned().unwrap_or(Value::Null),
+    })
+}
+
+fn status_rank(record: &Value) -> u8 {
+    match record.get("status").and_then(Value::as_str).and_then(|s| s.parse().ok()) {
+        Some(Status::Draft) => 0,
+        Some(Status::Superseded | Status::Deprecated | Status::Rejected) => 2,
+        _ => 1,
+    }
+}
+
+fn link_key(link: &Value) -> (String, String, String) {
+    let field = |name: &str| link.get(name).and_then(Value::as_str).unwrap_or_default().to_string();
+    (field("source_id"), field("kind"), field("target_id"))
+}
+
+fn merge_record(base: Option<&Value>, ours: &Value, theirs: &Value) -> Value {
+    let empty = Map::new();
+    let fields = |v: Option<&Value>| v.and_then(Value::as_object).unwrap_or(&empty).clone();
+    let (b, o, t) = (fields(base), fields(Some(ours)), fields(Some(theirs)));
+
+    let mut merged = Map::new();
+    let mut conflicts = Map::new();
+    let names: BTreeSet<&String> = o.keys().chain(t.keys()).collect();
+    for name in names {
+        let (bv, ov, tv) = (b.get(name), o.get(name), t.get(name));
+        match name.as_str() {
+            "labels" | "links" | "transitions" | "status" | "superseded_by" | "updated_at" | CONFLICT_KEY => {}
+            _ => match three_way(bv, ov, tv) {
+                Ok(Some(v)) => {
+                    merged.insert(name.clone(), v.clone());
+                }
+                Ok(None) => {}
+                Err(()) => {
+                    merged.insert(name.clone(), ov.cloned().unwrap_or(Value::Null));
+                    conflicts.insert(name.clone(), conflict(bv, ov, tv));
+                }
+            },
+        }
+    }
+
+    // The more advanced status wins, with its superseded_by; equal ranks merge three ways
+    let status_of = |m: &Map<String, Value>| -> Value {
+        serde_json::json!([m.get("status"), m.get("superseded_by")])
+    };
+    let winner = match status_rank(ours).cmp(&status_rank(theirs)) {
+        std::cmp::Ordering::Greater => Some(&o),
+        std::cmp::Ordering::Less => Some(&t),
+        std::cmp::Ordering::Equal => {
+            let (bs, os, ts) = (base.map(|_| status_of(&b)), status_of(&o), status_of(&t));
+            match three_way(bs.as_ref(), Some(&os), Some(&ts)) {
+                Ok(Some(s)) if *s == ts => Some(&t),
+                Ok(_) => Some(&o),
+                Err(()) => {
+                    conflicts.insert("status".to_string(), conflict(bs.as_ref(), Some(&os), Some(&ts)));
+                    Some(&o)
+                }
+            }
+        }
+    };
+    for name in ["status", "superseded_by"] {
+        if let Some(v) = winner.and_then(|w| w.get(name)) {
+            merged.insert(name.to_string(), v.clone());
+        }
+    }
+
+    // Timestamps compare as text; export always writes RFC3339 UTC
+    let updated = [o.get("updated_at"), t.get("updated_at")]
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str)
+        .max();
+    if let Some(updated) = updated {
+        merged.insert("updated_at".to_string(), Value::from(updated));
+    }
+
+    let labels: BTreeSet<&str> = [o.get("labels"), t.get("labels")]
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_array)
+        .flatten()
+        .filter_map(Value::as_str)
+        .collect();
+    if !labels.is_empty() {
+        merged.insert("labels".to_string(), labels.into_iter().map(Value::from).collect());
+    }
+
+    let links_of = |m: &Map<String, Value>| -> BTreeMap<(String, String, String), Value> {
+        m.get("links")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .map(|l| (link_key(l), l.clone()))
+            .collect()
+    };
+    let (bl, ol, tl) = (links_of(&b), links_of(&o), links_of(&t));
+    let mut links = Vec::new();
+    let keys: BTreeSet<&(String, String, String)> = ol.keys().chain(tl.keys()).collect();
+    for k in keys {
+        match three_way(bl.get(k), ol.get(k), tl.get(k)) {
+            Ok(Some(l)) => links.push(l.clone()),
+            Ok(None) => {}
+            Err(()) => {
+                links.extend(ol.get(k).cloned());
+                conflicts.insert(format!("link {} {} {}", k.0, k.1, k.2), conflict(bl.get(k), ol.get(k), tl.get(k)));
+            }
+        }
+    }
+    if !links.is_empty() || o.contains_key("links") || t.contains_key("links") {
+        merged.insert("links".to_string(), Value::Array(links));
+    }
+
+    let mut transitions: Vec<Value> = Vec::new();
+    for tr in [o.get("transitions"), t.get("transitions")].into_iter().flatten().filter_map(Value::as_array).flatten() {
+        if !transitions.contains(tr) {
+            transitions.push(tr.clone());
+        }
+    }
+    if !transitions.is_empty() {
+        transitions.sort_by(|a, b| a.get("at").and_then(Value::as_str).cmp(&b.get("at").and_then(Value::as_str)));
+        merged.insert("transitions".to_string(), Value::Array(transitions));
+    }
+
+    if !conflicts.is_empty() {
+        merged.insert(CONFLICT_KEY.to_string(), Value::Object(conflicts));
+    }
+    Value::Object(merged)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(v: Value) -> String {
+        format!("{}\n", v)
+    }
+
+    fn record(title: &str, status: &str, labels: Value, links: Value, updated: &str) -> String {
+        line(serde_json::json!({
+            "id": "d-1", "title": title, "status": status, "labels": labels,
+            "links": links, "updated_at": updated, "weight": "should",
+        }))
+    }
+
+    fn link(target: &str) -> Value {
+        serde_json::json!({ "source_id": "d-1", "kind": "supports", "target_id": target })
+    }
+
+    fn base() -> String {
+        record("Cache", "active", serde_json::json!(["perf"]), serde_json::json!([link("d-2")]), "2025-01-01T00:00:00Z")
+    }
+
+    #[test]
+    fn records_from_either_side_are_kept() {
+        let ours = base() + &line(serde_json::json!({ "id": "d-4", "title": "Ours only" }));
+        let theirs = base() + &line(serde_json::json!({ "id": "d-5", "title": "Theirs only" }));
+        let merged = merge(&base(), &ours, &theirs).unwrap();
+        assert_eq!(merged.conflicts, 0);
+        let records = parse(&merged.text).unwrap();
+        let ids: Vec<&str> = records.iter().filter_map(|r| r["id"].as_str()).collect();
+        assert_eq!(ids, ["d-1", "d-4", "d-5"]);
+    }
+
+    #[test]
+    fn changes_on_different_fields_combine() {
+        let ours = record("Cache", "superseded", serde_json::json!(["perf"]), serde_json::json!([link("d-2"), link("d-3")]), "2025-01-02T00:00:00Z");
+        let theirs = record("Cache sessions", "active", serde_json::json!(["perf", "api"]), serde_json::json!([]), "2025-01-03T00:00:00Z");
+        let merged = merge(&base(), &ours, &theirs).unwrap();
+        assert_eq!(merged.conflicts, 0);
+        let r = &parse(&merged.text).unwrap()[0];
+        assert_eq!((r["title"].as_str(), r["status"].as_str()), (Some("Cache sessions"), Some("superseded")));
+        assert_eq!(r["labels"], serde_json::json!(["api", "perf"]));
+        // They removed the d-2 link, we added d-3
+        assert_eq!(r["links"], serde_json::json!([link("d-3")]));
+        assert_eq!(r["updated_at"], "2025-01-03T00:00:00Z");
+    }
+
+    #[test]
+    fn a_field_both_sides_changed_keeps_ours_and_records_all_three() {
+        let ours = base().replace("should", "must");
+        let theirs = base().replace("should", "may");
+        let merged = merge(&base(), &ours, &theirs).unwrap();
+        assert_eq!(merged.conflicts, 1);
+        let r = &parse(&merged.text).unwrap()[0];
+        assert_eq!(r["weight"], "must");
+        assert_eq!(r[CONFLICT_KEY]["weight"], serde_json::json!({ "base": "should", "ours": "must", "theirs": "may" }));
+    }
+
+    #[test]
+    fn operation_logs_are_unioned() {
+        let op = |id: &str| line(serde_json::json!({ "id": id, "at": "2025-01-01T00:00:00Z", "op": "label_add" }));
+        let merged = merge(&op("a"), &(op("a") + &op("b")), &(op("a") + &op("c"))).unwrap();
+        assert_eq!(merged.text, op("a") + &op("b") + &op("c"));
+    }
+}
@@ -1,13 +1,25 @@
 pub mod add;
 pub mod amend;
+pub mod ask;
+pub mod check;
 pub mod context;
+pub mod diff;
+#[cfg(feature = "grafeo")]
+pub mod gql;
 pub mod init;
+pub mod instructions;
 pub mod io;
 pub mod link;
 pub mod list;
+pub mod mcp;
+pub mod merge;
+pub mod proposal;
 pub mod query;
 pub mod report;
+pub mod scope;
 pub mod show;
+pub mod similar;
+pub mod view;
 
 use std::path::Path;
 
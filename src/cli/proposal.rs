@@ -0,0 +1,296 @@
+//! The review queue for decisions agents propose. `propose` stores a draft
+//! with its origin, `inbox` lists what is waiting, and `accept`/`reject`
+//! settle it. Rejected proposals stay in the store with the reason, so the
+//! same proposal is refused if it comes back.
+
+use std::path::Path;
+
+use crate::cli::add::AddArgs;
+use crate::cli::amend::AmendArgs;
+use crate::config::Config;
+use crate::db::{self, ListFilter, Store};
+use crate::error::{DictumError, Result};
+use crate::format::OutputFormat;
+use crate::model::{Decision, Origin, Status};
+
+pub fn run_propose(path: &Path, args: AddArgs, is_tty: bool) -> Result<()> {
+    let dictum_dir = path.join(".dictum");
+    crate::cli::ensure_init(&dictum_dir)?;
+
+    let config = Config::load(&dictum_dir)?;
+    let mut store = db::open(&dictum_dir)?;
+
+    let format = OutputFormat::from_str_or_auto(args.format.as_deref(), is_tty);
+    let added = crate::cli::add::insert(&mut *store, &dictum_dir, &config, args, Status::Draft)?;
+    if let Some((similar, title)) = &added.similar {
+        eprintln!(
+            "Warning: similar to existing decision {} ({:.2}): {}",
+            similar.id, similar.score, title
+        );
+    }
+
+    match format {
+        OutputFormat::Text => println!("Proposed: {} (pending review, see `dictum inbox`)", added.decision.id),
+        _ => println!("{}", serde_json::to_string(&added.decision)?),
+    }
+    Ok(())
+}
+
+/// Pending proposals, oldest first; rejected ones instead with `rejected`.
+pub fn pending(store: &dyn Store, rejected: bool) -> Result<Vec<Decision>> {
+    let mut proposals = store.decision_list(&ListFilter {
+        status: Some(if rejected { Status::Rejected } else { Status::Draft }),
+        ..Default::default()
+    })?;
+    proposals.sort_by(|a, b| a.created_at.cmp(&b.created_at).then_with(|| a.id.cmp(&b.id)));
+    Ok(proposals)
+}
+
+pub fn run_inbox(path: &Path, rejected: bool, fmt: Option<String>, is_tty: bool) -> Result<()> {
+    let dictum_dir = path.join(".dictum");
+    crate::cli::ensure_init(&dictum_dir)?;
+
+    let store = db::open(&dictum_dir)?;
+    let proposals = pending(&*store, rejected)?;
+
+    let format = OutputFormat::from_str_or_auto(fmt.as_deref(), is_tty);
+    match format {
+        OutputFormat::Text => print!("{}", format_inbox_text(&proposals, rejected)),
+        _ => print!("{}", crate::format::format_decision_list(&proposals, &format)?),
+    }
+    Ok(())
+}
+
+fn format_inbox_text(proposals: &[Decision], rejected: bool) -> String {
+    if proposals.is_empty() {
+        return if rejected { "No rejected proposals.\n" } else { "No pending proposals.\n" }.to_string();
+    }
+    let mut out = format!(
+        "{} {} proposal(s):\n",
+        proposals.len(),
+        if rejected { "rejected" } else { "pending" }
+    );
+    for d in proposals {
+        out.push_str(&format!("\n[{}] ({}/{}, {}) {}\n", d.id, d.kind, d.weight, d.level, d.title));
+        let origin = d.origin.as_ref().map(|o| o.to_string()).unwrap_or_default();
+        let date = d.created_at.get(..10).unwrap_or(&d.created_at);
+        if origin.is_empty() {
+            out.push_str(&format!("  Proposed {} by {}\n", date, d.author));
+        } else {
+            out.push_str(&format!("  Proposed {} {}\n", date, origin));
+        }
+        if let Some(ref scope) = d.scope {
+            out.push_str(&format!("  Scope: {}\n", scope));
+        }
+        if let Some(ref rebuttal) = d.rebuttal {
+            out.push_str(&format!("  Unless: {}\n", rebuttal));
+        }
+        if let Some(ref body) = d.body {
+            out.push_str(&format!("  {}\n", body));
+        }
+        if let Some(rejection) = d.origin.as_ref().and_then(|o| o.rejection.as_ref()) {
+            out.push_str(&format!("  Rejected: {}\n", rejection));
+        }
+    }
+    if !rejected {
+        out.push_str("\nReview with `dictum accept <id>` (optionally with edits) or `dictum reject <id> --reason ...`.\n");
+    }
+    out
+}
+
+/// Make a pending proposal active. With any edit in `edits`, an edited copy
+/// is accepted instead and supersedes the proposal, as `decision amend` would.
+pub fn accept(store: &mut dyn Store, config: &Config, edits: &AmendArgs) -> Result<Decision> {
+    let proposal = pending_proposal(store, &edits.id)?;
+    let edited = edits.title.is_some()
+        || edits.body.is_some()
+        || edits.kind.is_some()
+        || edits.weight.is_some()
+        || edits.rebuttal.is_some()
+        || edits.scope.is_some();
+    if edited {
+        return crate::cli::amend::supersede(store, config, edits);
+    }
+    store.decision_update_status(&proposal.id, &Status::Active, None)?;
+    store.decision_get(&proposal.id)
+}
+
+/// Turn a pending proposal down, keeping it with the reason.
+pub fn reject(store: &mut dyn Store, id: &str, reason: Option<String>) -> Result<Decision> {
+    let proposal = pending_proposal(store, id)?;
+    let origin = Origin {
+        rejection: reason,
+        ..proposal.origin.unwrap_or_default()
+    };
+    store.decision_reject(id, &origin)?;
+    store.decision_get(id)
+}
+
+fn pending_proposal(store: &dyn Store, id: &str) -> Result<Decision> {
+    let decision = store.decision_get(id)?;
+    if decision.status != Status::Draft {
+        return Err(DictumError::NotPending(id.to_string(), decision.status.to_string()));
+    }
+    Ok(decision)
+}
+
+pub fn run_accept(path: &Path, edits: AmendArgs, is_tty: bool) -> Result<()> {
+    let dictum_dir = path.join(".dictum");
+    crate::cli::ensure_init(&dictum_dir)?;
+
+    let config = Config::load(&dictum_dir)?;
+    let mut store = db::open(&dictum_dir)?;
+    let accepted = accept(&mut *store, &config, &edits)?;
+
+    let format = OutputFormat::from_str_or_auto(edits.format.as_deref(), is_tty);
+    match format {
+        OutputFormat::Text if accepted.id == edits.id => println!("Accepted: {}", accepted.id),
+        OutputFormat::Text => println!("Accepted: {} -> {} (edited)", edits.id, accepted.id),
+        _ => println!("{}", serde_json::to_string(&accepted)?),
+    }
+    Ok(())
+}
+
+pub fn run_reject(
+    path: &Path,
+    id: &str,
+    reason: Option<String>,
+    fmt: Option<String>,
+    is_tty: bool,
+) -> Result<()> {
+    let dictum_dir = path.join(".dictum");
+    crate::cli::ensure_init(&dictum_dir)?;
+
+    let mut store = db::open(&dictum_dir)?;
+    let rejected = reject(&mut *store, id, reason)?;
+
+    let format = OutputFormat::from_str_or_auto(fmt.as_deref(), is_tty);
+    match format {
+        OutputFormat::Text => println!("Rejected: {}", id),
+        _ => println!("{}", serde_json::to_string(&rejected)?),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{Kind, Level, TestProject, Weight};
+
+    fn proposal(title: &str) -> AddArgs {
+        AddArgs {
+            title: title.to_string(),
+            level: Level::Tactical,
+            parent: None,
+            label: Vec::new(),
+            body: None,
+            author: None,
+            format: None,
+            kind: Kind::Rule,
+            weight: Weight::Should,
+            rebuttal: None,
+            scope: None,
+            strict: false,
+            origin: Some(Origin {
+                agent: Some("coding-agent".to_string()),
+                session: Some("s1".to_string()),
+                source: Some("src/cache.rs".to_string()),
+                rejection: None,
+            }),
+        }
+    }
+
+    fn edits(id: &str) -> AmendArgs {
+        AmendArgs {
+            id: id.to_string(),
+            title: None,
+            body: None,
+            format: None,
+            kind: None,
+            weight: None,
+            rebuttal: None,
+            scope: None,
+        }
+    }
+
+    fn propose(store: &mut dyn Store, dir: &Path, title: &str) -> Result<Decision> {
+        let config = Config::load(dir).unwrap();
+        Ok(crate::cli::add::insert(store, dir, &config, proposal(title), Status::Draft)?.decision)
+    }
+
+    #[test]
+    fn proposals_keep_their_origin_and_are_not_repeated() {
+        let project = TestProject::new();
+        let dir = project.dictum_dir();
+        let mut store = db::open(&dir).unwrap();
+
+        let first = propose(&mut *store, &dir, "Cache user sessions in Redis").unwrap();
+        propose(&mut *store, &dir, "Wrap every handler in a retry loop").unwrap();
+        assert_eq!(first.origin.as_ref().unwrap().agent.as_deref(), Some("coding-agent"));
+        assert!(matches!(
+            propose(&mut *store, &dir, "Cache user sessions in Redis"),
+            Err(DictumError::AlreadyProposed(id)) if id == first.id
+        ));
+        assert_eq!(pending(&*store, false).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn accepting_makes_a_proposal_active() {
+        let project = TestProject::new();
+        let dir = project.dictum_dir();
+        let config = Config::load(&dir).unwrap();
+        let mut store = db::open(&dir).unwrap();
+
+        let proposed = propose(&mut *store, &dir, "Cache user sessions in Redis").unwrap();
+        let accepted = accept(&mut *store, &config, &edits(&proposed.id)).unwrap();
+        assert_eq!((accepted.id.as_str(), accepted.status), (proposed.id.as_str(), Status::Active));
+        assert!(matches!(reject(&mut *store, &proposed.id, None), Err(DictumError::NotPending(..))));
+        assert!(pending(&*store, false).unwrap().is_empty());
+    }
+
+    #[test]
+    fn rejections_survive_a_rebuild_and_block_the_same_proposal() {
+        let project = TestProject::new();
+        let dir = project.dictum_dir();
+        let mut store = db::open(&dir).unwrap();
+
+        let proposed = propose(&mut *store, &dir, "Wrap every handler in a retry loop").unwrap();
+        let rejected = reject(&mut *store, &proposed.id, Some("retries belong in the client".to_string())).unwrap();
+        assert_eq!(rejected.status, Status::Rejected);
+        assert_eq!(rejected.origin.as_ref().unwrap().source.as_deref(), Some("src/cache.rs"));
+        let to_rejected = |store: &dyn Store| {
+            store.transitions(Some(&proposed.id)).unwrap().into_iter().filter(|t| t.to_status == Status::Rejected).count()
+        };
+        assert_eq!(to_rejected(&*store), 1);
+
+        drop(store);
+        db::oplog::rebuild(&dir).unwrap();
+        let mut store = db::open(&dir).unwrap();
+        assert_eq!(store.decision_get(&proposed.id).unwrap(), rejected);
+        assert_eq!(to_rejected(&*store), 1);
+        assert!(pending(&*store, false).unwrap().is_empty());
+        match propose(&mut *store, &dir, "Wrap every handler in a retry loop") {
+            Err(DictumError::PreviouslyRejected { id, reason }) => {
+                assert_eq!(id, proposed.id);
+                assert_eq!(reason, "retries belong in the client");
+            }
+            other => panic!("expected a rejection, got {:?}", other.map(|d| d.id)),
+        }
+    }
+
+    #[test]
+    fn accepting_with_edits_supersedes_the_proposal() {
+        let project = TestProject::new();
+        let dir = project.dictum_dir();
+        let config = Config::load(&dir).unwrap();
+        let mut store = db::open(&dir).unwrap();
+
+        let proposed = propose(&mut *store, &dir, "Log request IDs").unwrap();
+        let mut changes = edits(&proposed.id);
+        changes.weight = Some(Weight::Must);
+        let edited = accept(&mut *store, &config, &changes).unwrap();
+        assert_ne!(edited.id, proposed.id);
+        assert_eq!((edited.weight, edited.origin), (Weight::Must, proposed.origin));
+        assert_eq!(store.decision_get(&proposed.id).unwrap().status, Status::Superseded);
+    }
+}
@@ -1,18 +1,28 @@
 use std::path::Path;
 
-use crate::db;
+use crate::db::{self, SearchQuery};
 use crate::error::Result;
 use crate::format::{self, OutputFormat};
+use crate::model::Status;
 
-pub fn run(path: &Path, query: &str, fmt: Option<String>, is_tty: bool) -> Result<()> {
+pub fn run(
+    path: &Path,
+    query: &str,
+    status: Option<String>,
+    fmt: Option<String>,
+    is_tty: bool,
+) -> Result<()> {
     let dictum_dir = path.join(".dictum");
     crate::cli::ensure_init(&dictum_dir)?;
 
+    let mut search = SearchQuery::parse(query)?;
+    search.status = status.map(|s| s.parse::<Status>()).transpose()?;
+
     let store = db::open(&dictum_dir)?;
-    let decisions = store.decision_search(query)?;
+    let hits = store.decision_search(&search)?;
 
     let format = OutputFormat::from_str_or_auto(fmt.as_deref(), is_tty);
-    let output = format::format_decision_list(&decisions, &format)?;
+    let output = format::format_search_hits(&hits, &format)?;
     print!("{}", output);
 
     Ok(())
@@ -9,24 +9,25 @@ use crate::error::Result;
 use crate::model::{Decision, Status};
 
 const REPORT_TEMPLATE: &str = include_str!("../assets/report.html");
-const DATA_PLACEHOLDER: &str = "/*__DICTUM_DATA__*/null";
 
-pub fn run(path: &Path, all: bool, output_file: Option<String>, template: Option<String>) -> Result<()> {
+pub fn run(
+    path: &Path,
+    all: bool,
+    output_file: Option<String>,
+    template: Option<String>,
+    as_of: Option<String>,
+) -> Result<()> {
     let dictum_dir = path.join(".dictum");
     crate::cli::ensure_init(&dictum_dir)?;
 
-    let store = db::open(&dictum_dir)?;
+    let store = db::open_as_of(&dictum_dir, path, as_of.as_deref())?;
 
     let decisions = if all {
         store.decision_get_all()?
     } else {
         store.decision_list(&ListFilter {
             status: Some(Status::Active),
-            level: None,
-            label: None,
-            kind: None,
-            weight: None,
-            scope: None,
+            ..Default::default()
         })?
     };
 
@@ -47,10 +48,7 @@ pub fn run(path: &Path, all: bool, output_file: Option<String>, template: Option
 
     let report_data = build_report_data(&*store, &decisions, &project_name)?;
     let json_str = serde_json::to_string(&report_data)?;
-    if !tmpl.contains(DATA_PLACEHOLDER) {
-        return Err(crate::error::DictumError::InvalidTemplate);
-    }
-    let html = tmpl.replacen(DATA_PLACEHOLDER, &json_str, 1);
+    let html = crate::format::template::fill(tmpl, template.as_deref().unwrap_or("report.html"), &json_str)?;
 
     let mut writer: Box<dyn Write> = match output_file {
         Some(ref f) => Box::new(std::fs::File::create(f)?),
@@ -0,0 +1,43 @@
+use std::path::Path;
+
+use crate::db::{self, ListFilter};
+use crate::error::Result;
+use crate::format::OutputFormat;
+use crate::model::scope::{self, ScopeNode};
+use crate::model::Status;
+
+pub fn run_list(path: &Path, all: bool, fmt: Option<String>, is_tty: bool) -> Result<()> {
+    let dictum_dir = path.join(".dictum");
+    crate::cli::ensure_init(&dictum_dir)?;
+
+    let store = db::open(&dictum_dir)?;
+    let decisions = store.decision_list(&ListFilter {
+        status: (!all).then_some(Status::Active),
+        ..Default::default()
+    })?;
+    let tree = scope::tree(decisions.iter().map(|d| d.scope.as_deref()));
+
+    match OutputFormat::from_str_or_auto(fmt.as_deref(), is_tty) {
+        OutputFormat::Text => {
+            println!("(global) {}", tree.global);
+            for node in &tree.scopes {
+                print_node(node, 0);
+            }
+        }
+        _ => println!("{}", serde_json::to_string_pretty(&tree)?),
+    }
+    Ok(())
+}
+
+/// `name direct` for leaves, `name direct/total` where descendants add to the count.
+fn print_node(node: &ScopeNode, depth: usize) {
+    let counts = if node.total == node.direct {
+        node.direct.to_string()
+    } else {
+        format!("{}/{}", node.direct, node.total)
+    };
+    println!("{}{} {}", "  ".repeat(depth), node.name, counts);
+    for child in &node.children {
+        print_node(child, depth + 1);
+    }
+}
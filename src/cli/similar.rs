@@ -0,0 +1,74 @@
+use std::path::Path;
+
+use serde_json::Value;
+
+use crate::db;
+use crate::error::{DictumError, Result};
+use crate::format::OutputFormat;
+use crate::model::{Decision, Status};
+use crate::similarity::SimilarityIndex;
+
+pub fn run(
+    path: &Path,
+    query: &str,
+    top: usize,
+    all: bool,
+    fmt: Option<String>,
+    is_tty: bool,
+) -> Result<()> {
+    let dictum_dir = path.join(".dictum");
+    crate::cli::ensure_init(&dictum_dir)?;
+
+    let store = db::open(&dictum_dir)?;
+    let decisions = store.decision_get_all()?;
+    let index = SimilarityIndex::load_or_build(&dictum_dir, &decisions)?;
+
+    let candidates: Vec<&str> = decisions
+        .iter()
+        .filter(|d| all || d.status == Status::Active)
+        .map(|d| d.id.as_str())
+        .collect();
+
+    // An existing ID compares that decision; anything else is free text
+    let results = match store.decision_get(query) {
+        Ok(_) => index.similar_to_id(query, &candidates, top),
+        Err(DictumError::DecisionNotFound(_)) => index.similar_to_text(query, &candidates, top),
+        Err(e) => return Err(e),
+    };
+
+    let hits: Vec<(&Decision, f64)> = results
+        .iter()
+        .filter_map(|s| Some((decisions.iter().find(|d| d.id == s.id)?, s.score)))
+        .collect();
+
+    let format = OutputFormat::from_str_or_auto(fmt.as_deref(), is_tty);
+    match format {
+        OutputFormat::Text => {
+            if hits.is_empty() {
+                println!("No similar decisions found.");
+            }
+            for (d, score) in &hits {
+                println!("{} | {:.2} | {:10} | {}", d.id, score, d.status, d.title);
+            }
+        }
+        OutputFormat::Jsonl => {
+            for hit in &hits {
+                println!("{}", serde_json::to_string(&hit_to_value(hit))?);
+            }
+        }
+        _ => {
+            let values: Vec<Value> = hits.iter().map(hit_to_value).collect();
+            println!("{}", serde_json::to_string_pretty(&values)?);
+        }
+    }
+
+    Ok(())
+}
+
+fn hit_to_value((decision, score): &(&Decision, f64)) -> Value {
+    let mut v = serde_json::to_value(decision).unwrap_or(Value::Null);
+    if let Value::Object(ref mut map) = v {
+        map.insert("score".to_string(), Value::from(*score));
+    }
+    v
+}
@@ -0,0 +1,155 @@
+use std::path::Path;
+
+use crate::config::{Config, View};
+use crate::db::{self, Sort};
+use crate::error::{DictumError, Result};
+use crate::format::OutputFormat;
+use crate::model::{Kind, Level, Status, Weight};
+
+/// Look up a saved view by name.
+pub fn load(dictum_dir: &Path, name: &str) -> Result<View> {
+    Config::load(dictum_dir)?.view(name).cloned()
+}
+
+/// Check that every value in a view parses, so a bad view fails at save time
+/// rather than every time it is used.
+pub fn validate(view: &View) -> Result<()> {
+    view.level.as_deref().map(str::parse::<Level>).transpose()?;
+    view.status.as_deref().map(str::parse::<Status>).transpose()?;
+    view.kind.as_deref().map(str::parse::<Kind>).transpose()?;
+    view.weight.as_deref().map(str::parse::<Weight>).transpose()?;
+    view.where_expr.as_deref().map(db::expr::parse).transpose()?;
+    view.sort.as_deref().map(str::parse::<Sort>).transpose()?;
+    view.fields.as_deref().map(crate::format::parse_fields).transpose()?;
+    Ok(())
+}
+
+/// Combine an explicit `--where` with the view's; both must hold.
+pub fn merge_where(explicit: Option<String>, view: &View) -> Option<String> {
+    match (explicit, view.where_expr.clone()) {
+        (Some(a), Some(b)) => Some(format!("({}) and ({})", b, a)),
+        (a, b) => a.or(b),
+    }
+}
+
+pub fn run_list(path: &Path, fmt: Option<String>, is_tty: bool) -> Result<()> {
+    let dictum_dir = path.join(".dictum");
+    crate::cli::ensure_init(&dictum_dir)?;
+
+    let config = Config::load(&dictum_dir)?;
+    match OutputFormat::from_str_or_auto(fmt.as_deref(), is_tty) {
+        OutputFormat::Text => {
+            if config.views.is_empty() {
+                println!("No saved views.");
+            }
+            for (name, view) in &config.views {
+                println!("{} | {}", name, describe(view));
+            }
+        }
+        OutputFormat::Jsonl => {
+            for (name, view) in &config.views {
+                let mut value = serde_json::to_value(view)?;
+                value["name"] = serde_json::Value::String(name.clone());
+                println!("{}", serde_json::to_string(&value)?);
+            }
+        }
+        _ => println!("{}", serde_json::to_string_pretty(&config.views)?),
+    }
+    Ok(())
+}
+
+pub fn run_save(path: &Path, name: &str, view: View) -> Result<()> {
+    let dictum_dir = path.join(".dictum");
+    crate::cli::ensure_init(&dictum_dir)?;
+
+    validate(&view)?;
+    let mut config = Config::load(&dictum_dir)?;
+    let replaced = config.views.insert(name.to_string(), view).is_some();
+    config.save(&dictum_dir)?;
+    if replaced {
+        println!("Updated view {}", name);
+    } else {
+        println!("Saved view {}", name);
+    }
+    Ok(())
+}
+
+pub fn run_delete(path: &Path, name: &str) -> Result<()> {
+    let dictum_dir = path.join(".dictum");
+    crate::cli::ensure_init(&dictum_dir)?;
+
+    let mut config = Config::load(&dictum_dir)?;
+    if config.views.remove(name).is_none() {
+        return Err(DictumError::ViewNotFound(name.to_string()));
+    }
+    config.save(&dictum_dir)?;
+    println!("Deleted view {}", name);
+    Ok(())
+}
+
+/// One-line summary of a view, in the same shape as the flags that define it.
+pub fn describe(view: &View) -> String {
+    let mut parts = Vec::new();
+    let fields = [
+        ("level", &view.level),
+        ("status", &view.status),
+        ("label", &view.label),
+        ("kind", &view.kind),
+        ("weight", &view.weight),
+        ("scope", &view.scope),
+        ("where", &view.where_expr),
+        ("sort", &view.sort),
+        ("fields", &view.fields),
+        ("format", &view.format),
+    ];
+    for (flag, value) in fields {
+        if let Some(v) = value {
+            parts.push(format!("--{} {}", flag, v));
+        }
+    }
+    if view.exact {
+        parts.push("--exact".to_string());
+    }
+    if view.tree {
+        parts.push("--tree".to_string());
+    }
+    if parts.is_empty() {
+        "(no filters)".to_string()
+    } else {
+        parts.join(" ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn views_round_trip_through_config() {
+        let view = View {
+            weight: Some("must".to_string()),
+            label: Some("security".to_string()),
+            where_expr: Some("not scope:legacy*".to_string()),
+            tree: true,
+            ..Default::default()
+        };
+        validate(&view).unwrap();
+
+        let mut config = Config::default();
+        config.views.insert("security-musts".to_string(), view.clone());
+        let toml = toml::to_string_pretty(&config).unwrap();
+        assert!(toml.contains("[views.security-musts]"));
+        assert!(toml.contains("where = "));
+        let back: Config = toml::from_str(&toml).unwrap();
+        assert_eq!(back.view("security-musts").unwrap(), &view);
+        assert!(back.view("missing").is_err());
+
+        assert_eq!(
+            merge_where(Some("kind:rule".to_string()), &view).as_deref(),
+            Some("(not scope:legacy*) and (kind:rule)")
+        );
+        assert!(validate(&View { weight: Some("often".to_string()), ..Default::default() }).is_err());
+        assert!(validate(&View { sort: Some("colour".to_string()), ..Default::default() }).is_err());
+        assert!(validate(&View { fields: Some("id,colour".to_string()), ..Default::default() }).is_err());
+    }
+}
@@ -1,7 +1,9 @@
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::Path;
 
 use crate::error::{DictumError, Result};
+use crate::model::LinkKind;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Config {
@@ -13,6 +15,80 @@ pub struct Config {
     pub default_format: String,
     #[serde(default = "default_backend")]
     pub backend: String,
+    /// Link kinds that must never form a cycle; checked on every link insert
+    #[serde(default = "default_acyclic_link_kinds")]
+    pub acyclic_link_kinds: Vec<LinkKind>,
+    /// Cosine score at which `decision add` flags a new decision as a near-duplicate
+    #[serde(default = "default_similarity_threshold")]
+    pub similarity_threshold: f64,
+    /// Named list presets, selectable with `--view <name>`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub views: BTreeMap<String, View>,
+    /// Agent instruction files kept up to date by `dictum sync-instructions`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub instructions: Vec<InstructionTarget>,
+    /// Where the project keeps its `dictum export`, relative to the project
+    /// root; `diff` reads it from a bare git revision
+    #[serde(default = "default_export")]
+    pub export: String,
+}
+
+/// A saved combination of list filters, sort order, output format and tree mode.
+/// Values are kept as strings, exactly as they would be passed on the command line.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct View {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub level: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kind: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub weight: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
+    /// Match `scope` exactly instead of inheriting enclosing scopes
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub exact: bool,
+    #[serde(rename = "where", default, skip_serializing_if = "Option::is_none")]
+    pub where_expr: Option<String>,
+    /// `field[:asc|desc]`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sort: Option<String>,
+    /// Comma-separated `--fields` selection
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fields: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub format: Option<String>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub tree: bool,
+}
+
+/// A file that gets the active decisions rendered between dictum's markers.
+/// Filters select decisions the same way `context` flags do.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct InstructionTarget {
+    /// Relative to the project root, e.g. `AGENTS.md`
+    pub path: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub view: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kind: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub weight: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
+    #[serde(rename = "where", default, skip_serializing_if = "Option::is_none")]
+    pub where_expr: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<usize>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub entailments: bool,
+    /// Template file containing the data placeholder, relative to the project root
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub template: Option<String>,
 }
 
 fn default_prefix() -> String {
@@ -27,6 +103,18 @@ fn default_backend() -> String {
     crate::db::compiled_backend().to_string()
 }
 
+pub fn default_acyclic_link_kinds() -> Vec<LinkKind> {
+    vec![LinkKind::Refines, LinkKind::Supersedes, LinkKind::Requires]
+}
+
+fn default_similarity_threshold() -> f64 {
+    0.8
+}
+
+fn default_export() -> String {
+    ".dictum/decisions.jsonl".to_string()
+}
+
 impl Default for Config {
     fn default() -> Self {
         Self {
@@ -34,6 +122,11 @@ impl Default for Config {
             default_author: None,
             default_format: default_format(),
             backend: default_backend(),
+            acyclic_link_kinds: default_acyclic_link_kinds(),
+            similarity_threshold: default_similarity_threshold(),
+            views: BTreeMap::new(),
+            instructions: Vec::new(),
+            export: default_export(),
         }
     }
 }
@@ -57,4 +150,10 @@ impl Config {
         std::fs::write(&config_path, content)?;
         Ok(())
     }
+
+    pub fn view(&self, name: &str) -> Result<&View> {
+        self.views
+            .get(name)
+            .ok_or_else(|| DictumError::ViewNotFound(name.to_string()))
+    }
 }
@@ -0,0 +1,606 @@
+//! `--where` filter expressions, e.g.
+//! `kind in (rule, constraint) and weight = must and label:security and not scope:legacy*`.
+//!
+//! Expressions are parsed once into an [`Expr`] tree and compiled by each backend
+//! (SQL for SQLite, a GQL `WHERE` clause for Grafeo).
+
+use std::fmt;
+
+use crate::error::{DictumError, Result};
+use chrono::{DateTime, NaiveDate, Utc};
+
+#[cfg(feature = "grafeo")]
+use crate::model::Decision;
+use crate::model::{Kind, Level, Status, Weight};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Field {
+    Id,
+    Title,
+    Body,
+    Level,
+    Status,
+    Kind,
+    Weight,
+    Scope,
+    Author,
+    Rebuttal,
+    Label,
+    Created,
+    Updated,
+}
+
+impl Field {
+    fn parse(s: &str) -> Option<Field> {
+        Some(match s.to_lowercase().as_str() {
+            "id" => Field::Id,
+            "title" => Field::Title,
+            "body" => Field::Body,
+            "level" => Field::Level,
+            "status" => Field::Status,
+            "kind" => Field::Kind,
+            "weight" => Field::Weight,
+            "scope" => Field::Scope,
+            "author" => Field::Author,
+            "rebuttal" => Field::Rebuttal,
+            "label" => Field::Label,
+            "created" | "created_at" => Field::Created,
+            "updated" | "updated_at" => Field::Updated,
+            _ => return None,
+        })
+    }
+
+    /// Property / column name on the decision record. `Label` lives in its own table/node.
+    pub fn column(&self) -> &'static str {
+        match self {
+            Field::Id => "id",
+            Field::Title => "title",
+            Field::Body => "body",
+            Field::Level => "level",
+            Field::Status => "status",
+            Field::Kind => "kind",
+            Field::Weight => "weight",
+            Field::Scope => "scope",
+            Field::Author => "author",
+            Field::Rebuttal => "rebuttal",
+            Field::Label => "label",
+            Field::Created => "created_at",
+            Field::Updated => "updated_at",
+        }
+    }
+
+    /// Whether the column may be NULL (compilers coalesce these to '').
+    pub fn is_nullable(&self) -> bool {
+        matches!(self, Field::Body | Field::Scope | Field::Rebuttal)
+    }
+
+    fn is_date(&self) -> bool {
+        matches!(self, Field::Created | Field::Updated)
+    }
+
+    fn validate(&self, value: &str) -> Result<()> {
+        match self {
+            Field::Level => value.parse::<Level>().map(|_| ()),
+            Field::Status => value.parse::<Status>().map(|_| ()),
+            Field::Kind => value.parse::<Kind>().map(|_| ()),
+            Field::Weight => value.parse::<Weight>().map(|_| ()),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CmpOp {
+    Eq,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl fmt::Display for CmpOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CmpOp::Eq => write!(f, "="),
+            CmpOp::Lt => write!(f, "<"),
+            CmpOp::Le => write!(f, "<="),
+            CmpOp::Gt => write!(f, ">"),
+            CmpOp::Ge => write!(f, ">="),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+    Cmp { field: Field, op: CmpOp, value: String },
+    /// `*` matches any run of characters; everything else is literal.
+    Glob { field: Field, pattern: String },
+    In { field: Field, values: Vec<String> },
+}
+
+#[cfg(feature = "grafeo")]
+impl Expr {
+    /// Evaluate the expression against `d` directly, for backends that cannot
+    /// express all of it in their query language.
+    pub fn matches(&self, d: &Decision) -> bool {
+        match self {
+            Expr::And(a, b) => a.matches(d) && b.matches(d),
+            Expr::Or(a, b) => a.matches(d) || b.matches(d),
+            Expr::Not(inner) => !inner.matches(d),
+            Expr::Cmp { field, op, value } => values_of(*field, d).iter().any(|x| match op {
+                CmpOp::Eq => x == value,
+                CmpOp::Lt => x < value,
+                CmpOp::Le => x <= value,
+                CmpOp::Gt => x > value,
+                CmpOp::Ge => x >= value,
+            }),
+            Expr::Glob { field, pattern } => values_of(*field, d).iter().any(|x| glob_matches(pattern, x)),
+            Expr::In { field, values } => values_of(*field, d).iter().any(|x| values.contains(x)),
+        }
+    }
+
+    /// Whether the expression has a glob with more than one `*`.
+    pub fn has_multi_star_glob(&self) -> bool {
+        match self {
+            Expr::And(a, b) | Expr::Or(a, b) => a.has_multi_star_glob() || b.has_multi_star_glob(),
+            Expr::Not(inner) => inner.has_multi_star_glob(),
+            Expr::Glob { pattern, .. } => pattern.matches('*').count() > 1,
+            Expr::Cmp { .. } | Expr::In { .. } => false,
+        }
+    }
+}
+
+/// The values a field holds on `d`: one per label for `Label`, otherwise one
+/// (unset optional fields read as '', as the compilers coalesce them).
+#[cfg(feature = "grafeo")]
+fn values_of(field: Field, d: &Decision) -> Vec<String> {
+    let value = match field {
+        Field::Label => return d.labels.clone(),
+        Field::Id => d.id.clone(),
+        Field::Title => d.title.clone(),
+        Field::Body => d.body.clone().unwrap_or_default(),
+        Field::Level => d.level.to_string(),
+        Field::Status => d.status.to_string(),
+        Field::Kind => d.kind.to_string(),
+        Field::Weight => d.weight.to_string(),
+        Field::Scope => d.scope.clone().unwrap_or_default(),
+        Field::Author => d.author.clone(),
+        Field::Rebuttal => d.rebuttal.clone().unwrap_or_default(),
+        Field::Created => d.created_at.clone(),
+        Field::Updated => d.updated_at.clone(),
+    };
+    vec![value]
+}
+
+/// Match `text` against a glob where `*` matches any run of characters and
+/// the literal segments between them must appear in order.
+#[cfg(feature = "grafeo")]
+pub fn glob_matches(pattern: &str, text: &str) -> bool {
+    let segments: Vec<&str> = pattern.split('*').collect();
+    if segments.len() == 1 {
+        return pattern == text;
+    }
+    let (first, last) = (segments[0], segments[segments.len() - 1]);
+    let Some(mut rest) = text.strip_prefix(first) else {
+        return false;
+    };
+    for middle in &segments[1..segments.len() - 1] {
+        match rest.find(middle) {
+            Some(at) => rest = &rest[at + middle.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(last)
+}
+
+pub fn parse(input: &str) -> Result<Expr> {
+    let tokens = tokenize(input)?;
+    let mut parser = Parser { tokens, pos: 0 };
+    let expr = parser.parse_or()?;
+    if let Some(tok) = parser.peek() {
+        return Err(invalid(format!("unexpected '{}'", tok)));
+    }
+    Ok(expr)
+}
+
+fn invalid(msg: String) -> DictumError {
+    DictumError::InvalidExpression(msg)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Word(String),
+    Quoted(String),
+    LParen,
+    RParen,
+    Comma,
+    Colon,
+    Op(&'static str),
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Word(w) => write!(f, "{}", w),
+            Token::Quoted(q) => write!(f, "\"{}\"", q),
+            Token::LParen => write!(f, "("),
+            Token::RParen => write!(f, ")"),
+            Token::Comma => write!(f, ","),
+            Token::Colon => write!(f, ":"),
+            Token::Op(op) => write!(f, "{}", op),
+        }
+    }
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            _ if c.is_whitespace() => i += 1,
+            '(' => {
+                tokens.push(Token::LParen);
+                i += 1;
+            }
+            ')' => {
+                tokens.push(Token::RParen);
+                i += 1;
+            }
+            ',' => {
+                tokens.push(Token::Comma);
+                i += 1;
+            }
+            ':' => {
+                tokens.push(Token::Colon);
+                i += 1;
+            }
+            '=' => {
+                tokens.push(Token::Op("="));
+                i += 1;
+            }
+            '!' | '<' | '>' => {
+                let two = chars.get(i + 1) == Some(&'=');
+                let op = match (c, two) {
+                    ('!', true) => "!=",
+                    ('<', true) => "<=",
+                    ('>', true) => ">=",
+                    ('<', false) => "<",
+                    ('>', false) => ">",
+                    _ => return Err(invalid("expected '=' after '!'".to_string())),
+                };
+                tokens.push(Token::Op(op));
+                i += if two { 2 } else { 1 };
+            }
+            '"' | '\'' => {
+                let quote = c;
+                let mut value = String::new();
+                i += 1;
+                loop {
+                    match chars.get(i) {
+                        None => return Err(invalid("unterminated string".to_string())),
+                        Some(&ch) if ch == quote => {
+                            i += 1;
+                            break;
+                        }
+                        Some(&ch) => {
+                            value.push(ch);
+                            i += 1;
+                        }
+                    }
+                }
+                tokens.push(Token::Quoted(value));
+            }
+            _ => {
+                let start = i;
+                while i < chars.len()
+                    && !chars[i].is_whitespace()
+                    && !matches!(chars[i], '(' | ')' | ',' | ':' | '=' | '!' | '<' | '>' | '"' | '\'')
+                {
+                    i += 1;
+                }
+                tokens.push(Token::Word(chars[start..i].iter().collect()));
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let tok = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        tok
+    }
+
+    fn peek_keyword(&self, kw: &str) -> bool {
+        matches!(self.peek(), Some(Token::Word(w)) if w.eq_ignore_ascii_case(kw))
+    }
+
+    fn parse_or(&mut self) -> Result<Expr> {
+        let mut left = self.parse_and()?;
+        while self.peek_keyword("or") {
+            self.pos += 1;
+            let right = self.parse_and()?;
+            left = Expr::Or(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn parse_and(&mut self) -> Result<Expr> {
+        let mut left = self.parse_unary()?;
+        while self.peek_keyword("and") {
+            self.pos += 1;
+            let right = self.parse_unary()?;
+            left = Expr::And(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn parse_unary(&mut self) -> Result<Expr> {
+        if self.peek_keyword("not") {
+            self.pos += 1;
+            return Ok(Expr::Not(Box::new(self.parse_unary()?)));
+        }
+        if self.peek() == Some(&Token::LParen) {
+            self.pos += 1;
+            let inner = self.parse_or()?;
+            if self.next() != Some(Token::RParen) {
+                return Err(invalid("expected ')'".to_string()));
+            }
+            return Ok(inner);
+        }
+        self.parse_predicate()
+    }
+
+    fn parse_predicate(&mut self) -> Result<Expr> {
+        let field = match self.next() {
+            Some(Token::Word(w)) => {
+                Field::parse(&w).ok_or_else(|| invalid(format!("unknown field '{}'", w)))?
+            }
+            Some(tok) => return Err(invalid(format!("expected a field, found '{}'", tok))),
+            None => return Err(invalid("expected a field".to_string())),
+        };
+
+        match self.next() {
+            Some(Token::Colon) | Some(Token::Op("=")) => {
+                let value = self.parse_value()?;
+                match_value(field, value)
+            }
+            Some(Token::Op("!=")) => {
+                let value = self.parse_value()?;
+                Ok(Expr::Not(Box::new(match_value(field, value)?)))
+            }
+            Some(Token::Op(op)) => {
+                if !field.is_date() {
+                    return Err(invalid(format!(
+                        "'{}' only applies to created/updated, not {}",
+                        op,
+                        field.column()
+                    )));
+                }
+                let op = match op {
+                    "<" => CmpOp::Lt,
+                    "<=" => CmpOp::Le,
+                    ">" => CmpOp::Gt,
+                    _ => CmpOp::Ge,
+                };
+                let value = self.parse_value()?;
+                let (op, value) = date_bound(op, &value)?;
+                Ok(Expr::Cmp { field, op, value })
+            }
+            Some(Token::Word(w)) if w.eq_ignore_ascii_case("in") => self.parse_in(field),
+            Some(Token::Word(w)) if w.eq_ignore_ascii_case("not") => {
+                if !self.peek_keyword("in") {
+                    return Err(invalid("expected 'in' after 'not'".to_string()));
+                }
+                self.pos += 1;
+                Ok(Expr::Not(Box::new(self.parse_in(field)?)))
+            }
+            Some(tok) => Err(invalid(format!(
+                "expected an operator after {}, found '{}'",
+                field.column(),
+                tok
+            ))),
+            None => Err(invalid(format!("expected an operator after {}", field.column()))),
+        }
+    }
+
+    fn parse_in(&mut self, field: Field) -> Result<Expr> {
+        if self.next() != Some(Token::LParen) {
+            return Err(invalid("expected '(' after 'in'".to_string()));
+        }
+        let mut values = Vec::new();
+        loop {
+            let value = self.parse_value()?;
+            field.validate(&value)?;
+            values.push(value);
+            match self.next() {
+                Some(Token::Comma) => continue,
+                Some(Token::RParen) => break,
+                _ => return Err(invalid("expected ',' or ')' in list".to_string())),
+            }
+        }
+        Ok(Expr::In { field, values })
+    }
+
+    fn parse_value(&mut self) -> Result<String> {
+        match self.next() {
+            Some(Token::Word(w)) | Some(Token::Quoted(w)) => Ok(w),
+            Some(tok) => Err(invalid(format!("expected a value, found '{}'", tok))),
+            None => Err(invalid("expected a value".to_string())),
+        }
+    }
+}
+
+/// Normalize the bound of a date comparison. A bare date covers the whole
+/// day, so `<= day` and `> day` compare against the start of the next one;
+/// timestamps are converted to UTC to compare with the stored ones.
+fn date_bound(op: CmpOp, value: &str) -> Result<(CmpOp, String)> {
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        let next_day = || date.succ_opt().unwrap_or(date).format("%Y-%m-%d").to_string();
+        return Ok(match op {
+            CmpOp::Le => (CmpOp::Lt, next_day()),
+            CmpOp::Gt => (CmpOp::Ge, next_day()),
+            _ => (op, date.format("%Y-%m-%d").to_string()),
+        });
+    }
+    match DateTime::parse_from_rfc3339(value) {
+        Ok(at) => Ok((op, at.with_timezone(&Utc).to_rfc3339())),
+        Err(_) => Err(invalid(format!(
+            "'{}' is not a date (YYYY-MM-DD) or an RFC 3339 timestamp",
+            value
+        ))),
+    }
+}
+
+/// `field:value` / `field = value`: globs match by pattern, dates by prefix, the rest exactly.
+fn match_value(field: Field, value: String) -> Result<Expr> {
+    if value.contains('*') {
+        return Ok(Expr::Glob {
+            field,
+            pattern: value,
+        });
+    }
+    if field.is_date() {
+        return Ok(Expr::Glob {
+            field,
+            pattern: format!("{}*", value),
+        });
+    }
+    field.validate(&value)?;
+    Ok(Expr::Cmp {
+        field,
+        op: CmpOp::Eq,
+        value,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::TestProject;
+
+    fn cmp(field: Field, value: &str) -> Expr {
+        Expr::Cmp {
+            field,
+            op: CmpOp::Eq,
+            value: value.to_string(),
+        }
+    }
+
+    #[test]
+    fn parses_full_example() {
+        let expr = parse(
+            "kind in (rule, constraint) and weight = must and label:security and not scope:legacy*",
+        )
+        .unwrap();
+        let expected = Expr::And(
+            Box::new(Expr::And(
+                Box::new(Expr::And(
+                    Box::new(Expr::In {
+                        field: Field::Kind,
+                        values: vec!["rule".to_string(), "constraint".to_string()],
+                    }),
+                    Box::new(cmp(Field::Weight, "must")),
+                )),
+                Box::new(cmp(Field::Label, "security")),
+            )),
+            Box::new(Expr::Not(Box::new(Expr::Glob {
+                field: Field::Scope,
+                pattern: "legacy*".to_string(),
+            }))),
+        );
+        assert_eq!(expr, expected);
+    }
+
+    #[test]
+    fn and_binds_tighter_than_or() {
+        let expr = parse("kind:rule or kind:goal and weight:must").unwrap();
+        assert!(matches!(expr, Expr::Or(_, ref right) if matches!(**right, Expr::And(_, _))));
+    }
+
+    #[test]
+    fn date_comparisons_and_prefixes() {
+        assert_eq!(
+            parse("created >= 2025-01-01").unwrap(),
+            Expr::Cmp {
+                field: Field::Created,
+                op: CmpOp::Ge,
+                value: "2025-01-01".to_string()
+            }
+        );
+        assert_eq!(
+            parse("updated:2025-03").unwrap(),
+            Expr::Glob {
+                field: Field::Updated,
+                pattern: "2025-03*".to_string()
+            }
+        );
+        assert!(parse("title > x").is_err());
+    }
+
+    #[test]
+    fn rejects_invalid_input() {
+        assert!(parse("kind = banana").is_err());
+        assert!(parse("colour = red").is_err());
+        assert!(parse("(kind:rule").is_err());
+        assert!(parse("kind:rule weight:must").is_err());
+    }
+
+    #[test]
+    fn rejects_dates_that_do_not_parse() {
+        assert!(parse("created < yesterday").is_err());
+        assert!(parse("updated >= 2025-13-01").is_err());
+        assert_eq!(
+            parse("created <= 2025-01-31").unwrap(),
+            Expr::Cmp {
+                field: Field::Created,
+                op: CmpOp::Lt,
+                value: "2025-02-01".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn backends_agree_on_ordered_globs_and_whole_day_bounds() {
+        let project = TestProject::new();
+        let mut store = project.open_cache();
+        for (id, scope, created) in [
+            ("d-1", "cli", "2026-02-27T09:00:00+00:00"),
+            ("d-2", "collaboration", "2026-02-27T18:00:00+00:00"),
+            ("d-3", "local/cache", "2026-02-28T00:00:00+00:00"),
+        ] {
+            let d = crate::model::Decision::test(id).with_scope(scope).with_created(created);
+            store.decision_insert(&d).unwrap();
+        }
+
+        let ids = |input: &str| -> Vec<String> {
+            let filter = crate::db::ListFilter {
+                expr: Some(parse(input).unwrap()),
+                sort: Some("id".parse().unwrap()),
+                ..Default::default()
+            };
+            store.decision_list(&filter).unwrap().into_iter().map(|d| d.id).collect()
+        };
+        assert_eq!(ids("scope:*l*c*"), ["d-3"]);
+        assert_eq!(ids("not scope:*l*c*"), ["d-1", "d-2"]);
+        assert_eq!(ids("created:2026-02-27"), ["d-1", "d-2"]);
+        assert_eq!(ids("created <= 2026-02-27"), ["d-1", "d-2"]);
+        assert_eq!(ids("created > 2026-02-27"), ["d-3"]);
+        assert_eq!(ids("created < \"2026-02-27T12:00:00Z\""), ["d-1"]);
+    }
+}
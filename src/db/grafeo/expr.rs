@@ -0,0 +1,78 @@
+use crate::db::expr::{Expr, Field};
+
+/// Compile a `--where` expression to a GQL predicate over `(d:Decision)`.
+///
+/// Values are inlined as escaped string literals: Grafeo does not yet accept
+/// parameters inside `EXISTS { ... }` filters, which label predicates need.
+/// Globs with a single `*` are exact; with several, the inner segments are
+/// only checked with `CONTAINS` (their order is not enforced), so
+/// `decision_list` evaluates such expressions with [`Expr::matches`] instead.
+pub fn to_gql(expr: &Expr) -> String {
+    let mut counter = 0;
+    compile(expr, &mut counter)
+}
+
+fn compile(expr: &Expr, counter: &mut usize) -> String {
+    match expr {
+        Expr::And(a, b) => format!("({} AND {})", compile(a, counter), compile(b, counter)),
+        Expr::Or(a, b) => format!("({} OR {})", compile(a, counter), compile(b, counter)),
+        Expr::Not(inner) => format!("NOT ({})", compile(inner, counter)),
+        Expr::Cmp { field, op, value } => {
+            predicate(*field, counter, |x| format!("{} {} {}", x, op, literal(value)))
+        }
+        Expr::Glob { field, pattern } => predicate(*field, counter, |x| glob(x, pattern)),
+        Expr::In { field, values } => {
+            let list: Vec<String> = values.iter().map(|v| literal(v)).collect();
+            predicate(*field, counter, |x| format!("{} IN [{}]", x, list.join(", ")))
+        }
+    }
+}
+
+fn predicate(field: Field, counter: &mut usize, test: impl Fn(&str) -> String) -> String {
+    match field {
+        Field::Label => {
+            *counter += 1;
+            let var = format!("wl{}", counter);
+            format!(
+                "EXISTS {{ MATCH (d)-[:HAS_LABEL]->({}:Label) WHERE {} }}",
+                var,
+                test(&format!("{}.name", var))
+            )
+        }
+        _ if field.is_nullable() => test(&format!("coalesce(d.{}, '')", field.column())),
+        _ => test(&format!("d.{}", field.column())),
+    }
+}
+
+fn glob(x: &str, pattern: &str) -> String {
+    let segments: Vec<&str> = pattern.split('*').collect();
+    if segments.len() == 1 {
+        return format!("{} = {}", x, literal(pattern));
+    }
+    let min_len: usize = segments.iter().map(|s| s.chars().count()).sum();
+    let mut tests = Vec::new();
+    if min_len > 0 {
+        tests.push(format!("char_length({}) >= {}", x, min_len));
+    }
+    let (first, last) = (segments[0], segments[segments.len() - 1]);
+    if !first.is_empty() {
+        tests.push(format!("{} STARTS WITH {}", x, literal(first)));
+    }
+    if !last.is_empty() {
+        tests.push(format!("{} ENDS WITH {}", x, literal(last)));
+    }
+    for middle in &segments[1..segments.len() - 1] {
+        if !middle.is_empty() {
+            tests.push(format!("{} CONTAINS {}", x, literal(middle)));
+        }
+    }
+    if tests.is_empty() {
+        // Pattern is all wildcards
+        return "true".to_string();
+    }
+    format!("({})", tests.join(" AND "))
+}
+
+fn literal(value: &str) -> String {
+    format!("'{}'", value.replace('\\', "\\\\").replace('\'', "\\'"))
+}
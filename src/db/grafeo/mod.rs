@@ -1,14 +1,26 @@
+mod expr;
+
+use std::collections::hash_map::Entry;
 use std::collections::{HashMap, HashSet, VecDeque};
 use std::path::Path;
 
 use grafeo::{Config, GrafeoDB, Value};
 
+use crate::db::search::{self, SearchField, SearchHit, SearchQuery};
+use crate::db::sort::Sort;
 use crate::db::store::{ListFilter, Neighborhood, Store};
 use crate::error::{DictumError, Result};
-use crate::model::{Decision, Kind, Level, Link, LinkKind, Status, Weight};
+use crate::model::{scope, Decision, Kind, Level, Link, LinkKind, Origin, Status, Transition, Weight};
+
+/// Columns and rows of a raw GQL query, with values converted to JSON.
+pub struct RawResult {
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<serde_json::Value>>,
+}
 
 pub struct GrafeoStore {
     db: GrafeoDB,
+    acyclic_kinds: Vec<LinkKind>,
 }
 
 impl GrafeoStore {
@@ -16,18 +28,30 @@ impl GrafeoStore {
         let db_path = dictum_dir.join("dictum.grafeo");
         let config = Config::persistent(&db_path);
         let db = GrafeoDB::with_config(config)?;
-        let store = GrafeoStore { db };
+        let store = GrafeoStore {
+            db,
+            acyclic_kinds: crate::config::default_acyclic_link_kinds(),
+        };
         store.ensure_text_indexes();
         Ok(store)
     }
 
+    #[allow(dead_code)]
     pub fn in_memory() -> Result<Self> {
         let db = GrafeoDB::new_in_memory();
-        let store = GrafeoStore { db };
+        let store = GrafeoStore {
+            db,
+            acyclic_kinds: crate::config::default_acyclic_link_kinds(),
+        };
         store.ensure_text_indexes();
         Ok(store)
     }
 
+    pub fn with_acyclic_kinds(mut self, kinds: Vec<LinkKind>) -> Self {
+        self.acyclic_kinds = kinds;
+        self
+    }
+
     fn ensure_text_indexes(&self) {
         // Best-effort: create text indexes for decision_search
         let _ = self.db.create_text_index("Decision", "title");
@@ -36,6 +60,39 @@ impl GrafeoStore {
         let _ = self.db.create_text_index("Decision", "scope");
     }
 
+    /// Run raw GQL. Without `write`, mutating statements are rejected up front and
+    /// the query runs in a read-only session. The keyword check is what protects
+    /// parameterized queries: `execute_with_params` skips the session's read-only check.
+    pub fn query_raw(
+        &self,
+        query: &str,
+        query_params: HashMap<String, Value>,
+        write: bool,
+    ) -> Result<RawResult> {
+        if !write && is_mutating(query) {
+            return Err(DictumError::ReadOnlyQuery);
+        }
+        let session = if write {
+            self.db.session()
+        } else {
+            self.db.session_read_only()
+        };
+        let result = session
+            .execute_with_params(query, query_params)
+            .map_err(|e| match e.error_code().as_str() {
+                "GRAFEO-T003" => DictumError::ReadOnlyQuery,
+                _ => DictumError::Grafeo(e),
+            })?;
+        Ok(RawResult {
+            columns: result.columns.clone(),
+            rows: result
+                .rows
+                .iter()
+                .map(|row| row.iter().map(value_to_json).collect())
+                .collect(),
+        })
+    }
+
     fn session(&self) -> grafeo::Session {
         self.db.session()
     }
@@ -55,6 +112,27 @@ impl GrafeoStore {
         Ok(labels)
     }
 
+    /// Internal edge ID of the `kind` link from `source_id` to `target_id`, if any.
+    ///
+    /// Filters on `kind` in Rust: on persistent stores a `WHERE r.kind = ...`
+    /// predicate is confused by the `kind` property on Decision nodes and
+    /// matches nothing.
+    fn find_link(&self, source_id: &str, kind: &LinkKind, target_id: &str) -> Result<Option<Value>> {
+        let result = self.session().execute_with_params(
+            "MATCH (:Decision {id: $src})-[r:LINK]->(:Decision {id: $tgt}) RETURN id(r), r.kind",
+            params(&[
+                ("src", Value::from(source_id)),
+                ("tgt", Value::from(target_id)),
+            ]),
+        )?;
+        let kind = kind.to_string();
+        let edge_id = result
+            .iter()
+            .find(|row| row[1].as_str() == Some(kind.as_str()))
+            .map(|row| row[0].clone());
+        Ok(edge_id)
+    }
+
     fn row_to_decision(&self, row: &[Value]) -> Result<Decision> {
         let id = row[0].as_str().unwrap_or("").to_string();
         let labels = self.load_labels(&id)?;
@@ -88,6 +166,7 @@ impl GrafeoStore {
                 .unwrap_or(Weight::Should),
             rebuttal: row[11].as_str().map(|s| s.to_string()),
             scope: row[12].as_str().map(|s| s.to_string()),
+            origin: row[13].as_str().and_then(|s| serde_json::from_str(s).ok()),
             labels,
         })
     }
@@ -95,7 +174,7 @@ impl GrafeoStore {
 
 const DECISION_COLS: &str =
     "d.id, d.title, d.body, d.level, d.status, d.superseded_by, \
-     d.author, d.created_at, d.updated_at, d.kind, d.weight, d.rebuttal, d.scope";
+     d.author, d.created_at, d.updated_at, d.kind, d.weight, d.rebuttal, d.scope, d.origin";
 
 impl Store for GrafeoStore {
     fn decision_insert(&mut self, decision: &Decision) -> Result<()> {
@@ -115,7 +194,8 @@ impl Store for GrafeoStore {
                 id: $id, title: $title, body: $body, level: $level,
                 status: $status, superseded_by: $superseded_by, author: $author,
                 created_at: $created_at, updated_at: $updated_at,
-                kind: $kind, weight: $weight, rebuttal: $rebuttal, scope: $scope
+                kind: $kind, weight: $weight, rebuttal: $rebuttal, scope: $scope,
+                origin: $origin
             })",
             params(&[
                 ("id", Value::from(decision.id.as_str())),
@@ -131,6 +211,7 @@ impl Store for GrafeoStore {
                 ("weight", Value::from(decision.weight.to_string().as_str())),
                 ("rebuttal", opt_value(&decision.rebuttal)),
                 ("scope", opt_value(&decision.scope)),
+                ("origin", opt_value(&decision.origin.as_ref().map(serde_json::to_string).transpose()?)),
             ]),
         )?;
         Ok(())
@@ -172,8 +253,26 @@ impl Store for GrafeoStore {
             param_pairs.push(("f_weight".to_string(), Value::from(weight.to_string().as_str())));
         }
         if let Some(ref scope) = filter.scope {
-            conditions.push("d.scope = $f_scope".to_string());
-            param_pairs.push(("f_scope".to_string(), Value::from(scope.as_str())));
+            if filter.scope_exact {
+                conditions.push("d.scope = $f_scope".to_string());
+                let exact = scope::normalize(scope).unwrap_or_default();
+                param_pairs.push(("f_scope".to_string(), Value::from(exact.as_str())));
+            } else {
+                conditions.push("(d.scope IS NULL OR d.scope IN $f_scopes)".to_string());
+                let ancestors: Vec<Value> = scope::ancestors(scope)
+                    .iter()
+                    .map(|s| Value::from(s.as_str()))
+                    .collect();
+                param_pairs.push(("f_scopes".to_string(), Value::List(ancestors.into())));
+            }
+        }
+        // GQL cannot check that glob segments appear in order; such
+        // expressions are evaluated here, before paging.
+        let post_filter = filter.expr.as_ref().filter(|expr| expr.has_multi_star_glob());
+        if let Some(ref expr) = filter.expr {
+            if post_filter.is_none() {
+                conditions.push(expr::to_gql(expr));
+            }
         }
 
         let match_clause = if let Some(ref label) = filter.label {
@@ -189,10 +288,22 @@ impl Store for GrafeoStore {
             format!(" WHERE {}", conditions.join(" AND "))
         };
 
-        let query = format!(
-            "{}{} RETURN {} ORDER BY d.created_at DESC",
-            match_clause, where_clause, DECISION_COLS,
+        let sort = filter.sort.unwrap_or(Sort::DEFAULT);
+        let mut query = format!(
+            "{}{} RETURN {} ORDER BY {}",
+            match_clause,
+            where_clause,
+            DECISION_COLS,
+            sort.order_by("d"),
         );
+        if post_filter.is_none() {
+            if filter.offset > 0 {
+                query.push_str(&format!(" SKIP {}", filter.offset));
+            }
+            if let Some(limit) = filter.limit {
+                query.push_str(&format!(" LIMIT {}", limit));
+            }
+        }
 
         let param_map: HashMap<String, Value> = param_pairs.into_iter().collect();
         let result = session.execute_with_params(&query, param_map)?;
@@ -201,6 +312,14 @@ impl Store for GrafeoStore {
         for row in result.iter() {
             decisions.push(self.row_to_decision(row)?);
         }
+        if let Some(expr) = post_filter {
+            decisions = decisions
+                .into_iter()
+                .filter(|d| expr.matches(d))
+                .skip(filter.offset)
+                .take(filter.limit.unwrap_or(usize::MAX))
+                .collect();
+        }
         Ok(decisions)
     }
 
@@ -210,17 +329,10 @@ impl Store for GrafeoStore {
         status: &Status,
         superseded_by: Option<&str>,
     ) -> Result<()> {
+        // Also verifies existence
+        let old = self.decision_get(id)?;
         let session = self.session();
 
-        // Verify existence
-        let check = session.execute_with_params(
-            "MATCH (d:Decision {id: $id}) RETURN d.id",
-            params(&[("id", Value::from(id))]),
-        )?;
-        if check.row_count() == 0 {
-            return Err(DictumError::DecisionNotFound(id.to_string()));
-        }
-
         let now = chrono::Utc::now().to_rfc3339();
         let superseded_val = match superseded_by {
             Some(s) => Value::from(s),
@@ -237,57 +349,166 @@ impl Store for GrafeoStore {
                 ("updated_at", Value::from(now.as_str())),
             ]),
         )?;
+        self.transition_insert(&Transition {
+            decision_id: id.to_string(),
+            from_status: old.status,
+            to_status: status.clone(),
+            from_superseded_by: old.superseded_by,
+            from_fields: Default::default(),
+            at: now,
+        })
+    }
+
+    fn decision_reject(&mut self, id: &str, origin: &Origin) -> Result<()> {
+        let old = self.decision_get(id)?;
+        let now = chrono::Utc::now().to_rfc3339();
+        self.session().execute_with_params(
+            "MATCH (d:Decision {id: $id}) \
+             SET d.status = $status, d.origin = $origin, d.updated_at = $updated_at",
+            params(&[
+                ("id", Value::from(id)),
+                ("status", Value::from(Status::Rejected.to_string().as_str())),
+                ("origin", Value::from(serde_json::to_string(origin)?.as_str())),
+                ("updated_at", Value::from(now.as_str())),
+            ]),
+        )?;
+        self.transition_insert(&Transition {
+            decision_id: id.to_string(),
+            from_status: old.status,
+            to_status: Status::Rejected,
+            from_superseded_by: old.superseded_by,
+            from_fields: Default::default(),
+            at: now,
+        })
+    }
+
+    fn decision_replace(&mut self, decision: &Decision) -> Result<()> {
+        self.decision_get(&decision.id)?;
+        self.session().execute_with_params(
+            "MATCH (d:Decision {id: $id}) \
+             SET d.title = $title, d.body = $body, d.level = $level, d.status = $status, \
+             d.superseded_by = $superseded_by, d.author = $author, d.created_at = $created_at, \
+             d.updated_at = $updated_at, d.kind = $kind, d.weight = $weight, \
+             d.rebuttal = $rebuttal, d.scope = $scope, d.origin = $origin",
+            params(&[
+                ("id", Value::from(decision.id.as_str())),
+                ("title", Value::from(decision.title.as_str())),
+                ("body", opt_value(&decision.body)),
+                ("level", Value::from(decision.level.to_string().as_str())),
+                ("status", Value::from(decision.status.to_string().as_str())),
+                ("superseded_by", opt_value(&decision.superseded_by)),
+                ("author", Value::from(decision.author.as_str())),
+                ("created_at", Value::from(decision.created_at.as_str())),
+                ("updated_at", Value::from(decision.updated_at.as_str())),
+                ("kind", Value::from(decision.kind.to_string().as_str())),
+                ("weight", Value::from(decision.weight.to_string().as_str())),
+                ("rebuttal", opt_value(&decision.rebuttal)),
+                ("scope", opt_value(&decision.scope)),
+                ("origin", opt_value(&decision.origin.as_ref().map(serde_json::to_string).transpose()?)),
+            ]),
+        )?;
         Ok(())
     }
 
-    fn decision_search(&self, query: &str) -> Result<Vec<Decision>> {
-        // Use text_search API across indexed properties, merge results by ID
-        let mut seen_ids: HashSet<String> = HashSet::new();
-        let mut decisions = Vec::new();
+    fn decision_search(&self, query: &SearchQuery) -> Result<Vec<SearchHit>> {
+        // Every term must match, which the per-property text indexes cannot express,
+        // so candidates are checked in Rust and the indexes only provide BM25 scores.
+        let candidates: Vec<Decision> = self
+            .decision_list(&ListFilter {
+                status: query.status.clone(),
+                ..Default::default()
+            })?
+            .into_iter()
+            .filter(|d| query.evaluate(d).is_some())
+            .collect();
+        if candidates.is_empty() {
+            return Ok(Vec::new());
+        }
 
-        for property in &["title", "body", "rebuttal", "scope"] {
-            if let Ok(hits) = self.db.text_search("Decision", property, query, 1000) {
-                for (node_id, _score) in hits {
-                    // Resolve the node to get its id property
-                    let session = self.session();
-                    let result = session.execute_with_params(
-                        &format!("MATCH (d:Decision) WHERE id(d) = $nid RETURN {}", DECISION_COLS),
-                        params(&[("nid", Value::from(node_id.0 as i64))]),
-                    );
-                    if let Ok(r) = result {
-                        for row in r.iter() {
-                            if let Some(id) = row[0].as_str() {
-                                if seen_ids.insert(id.to_string()) {
-                                    if let Ok(d) = self.row_to_decision(row) {
-                                        decisions.push(d);
-                                    }
-                                }
-                            }
-                        }
+        let result = self
+            .session()
+            .execute("MATCH (d:Decision) RETURN id(d), d.id")?;
+        let node_ids: HashMap<i64, String> = result
+            .iter()
+            .filter_map(|row| Some((row[0].as_int64()?, row[1].as_str()?.to_string())))
+            .collect();
+
+        let mut scores: HashMap<String, f64> = HashMap::new();
+        for field in SearchField::ALL {
+            let vocabulary: Vec<&str> = candidates.iter().filter_map(|d| field.text(d)).collect();
+            let words = query.index_words(field, &vocabulary);
+            if words.is_empty() {
+                continue;
+            }
+            // Best-effort: a missing index only costs ranking, not results
+            if let Ok(hits) = self.db.text_search("Decision", field.name(), &words.join(" "), 1000) {
+                for (node_id, score) in hits {
+                    if let Some(id) = node_ids.get(&(node_id.0 as i64)) {
+                        *scores.entry(id.clone()).or_default() += score;
                     }
                 }
             }
         }
 
-        // Fallback if no text indexes exist yet or no results: property scan
-        if decisions.is_empty() {
-            let session = self.session();
-            let result = session.execute_with_params(
-                &format!(
-                    "MATCH (d:Decision) \
-                     WHERE d.title CONTAINS $q OR d.body CONTAINS $q \
-                     OR d.rebuttal CONTAINS $q OR d.scope CONTAINS $q \
-                     RETURN {} ORDER BY d.created_at DESC",
-                    DECISION_COLS
-                ),
-                params(&[("q", Value::from(query))]),
-            )?;
-            for row in result.iter() {
-                decisions.push(self.row_to_decision(row)?);
-            }
-        }
+        let scored = candidates
+            .into_iter()
+            .map(|d| {
+                let score = scores.get(&d.id).copied().unwrap_or(0.0);
+                (d, score)
+            })
+            .collect();
+        Ok(search::rank(query, scored))
+    }
 
-        Ok(decisions)
+    fn transition_insert(&mut self, transition: &Transition) -> Result<()> {
+        let from_fields = if transition.from_fields.is_empty() {
+            None
+        } else {
+            Some(serde_json::to_string(&transition.from_fields)?)
+        };
+        self.session().execute_with_params(
+            "INSERT (:Transition {decision_id: $decision_id, from_status: $from_status, \
+             to_status: $to_status, from_superseded_by: $from_superseded_by, \
+             from_fields: $from_fields, at: $at})",
+            params(&[
+                ("decision_id", Value::from(transition.decision_id.as_str())),
+                ("from_status", Value::from(transition.from_status.to_string().as_str())),
+                ("to_status", Value::from(transition.to_status.to_string().as_str())),
+                ("from_superseded_by", opt_value(&transition.from_superseded_by)),
+                ("from_fields", opt_value(&from_fields)),
+                ("at", Value::from(transition.at.as_str())),
+            ]),
+        )?;
+        Ok(())
+    }
+
+    fn transitions(&self, decision_id: Option<&str>) -> Result<Vec<Transition>> {
+        let session = self.session();
+        let cols = "t.decision_id, t.from_status, t.to_status, t.from_superseded_by, t.at, t.from_fields";
+        let result = match decision_id {
+            Some(id) => session.execute_with_params(
+                &format!("MATCH (t:Transition {{decision_id: $id}}) RETURN {} ORDER BY t.at", cols),
+                params(&[("id", Value::from(id))]),
+            )?,
+            None => session.execute(&format!("MATCH (t:Transition) RETURN {} ORDER BY t.at", cols))?,
+        };
+        let status = |v: &Value| -> Status {
+            v.as_str().and_then(|s| s.parse().ok()).unwrap_or(Status::Active)
+        };
+        Ok(result
+            .iter()
+            .map(|row| Transition {
+                decision_id: row[0].as_str().unwrap_or_default().to_string(),
+                from_status: status(&row[1]),
+                to_status: status(&row[2]),
+                from_superseded_by: row[3].as_str().map(|s| s.to_string()),
+                from_fields: row[5]
+                    .as_str()
+                    .and_then(|s| serde_json::from_str(s).ok())
+                    .unwrap_or_default(),
+                at: row[4].as_str().unwrap_or_default().to_string(),
+            })
+            .collect())
     }
 
     fn label_add(&mut self, decision_id: &str, label: &str) -> Result<()> {
@@ -332,18 +553,13 @@ impl Store for GrafeoStore {
         let session = self.session();
 
         // Check for duplicate
-        let check = session.execute_with_params(
-            "MATCH (:Decision {id: $src})-[r:LINK]->(:Decision {id: $tgt}) \
-             WHERE r.kind = $kind RETURN 1",
-            params(&[
-                ("src", Value::from(link.source_id.as_str())),
-                ("tgt", Value::from(link.target_id.as_str())),
-                ("kind", Value::from(link.kind.to_string().as_str())),
-            ]),
-        )?;
-        if check.row_count() > 0 {
+        if self.find_link(link.source_id.as_str(), &link.kind, link.target_id.as_str())?.is_some() {
             return Err(DictumError::LinkAlreadyExists);
         }
+        if self.acyclic_kinds.contains(&link.kind) {
+            let edges = self.links_of_kind(&link.kind)?;
+            crate::db::check_link_cycle(link, &self.acyclic_kinds, &edges)?;
+        }
 
         session.execute_with_params(
             "MATCH (s:Decision {id: $src}), (t:Decision {id: $tgt}) \
@@ -360,29 +576,13 @@ impl Store for GrafeoStore {
     }
 
     fn link_delete(&mut self, source_id: &str, kind: &LinkKind, target_id: &str) -> Result<()> {
-        let session = self.session();
+        let edge_id = self
+            .find_link(source_id, kind, target_id)?
+            .ok_or(DictumError::LinkNotFound)?;
 
-        let check = session.execute_with_params(
-            "MATCH (:Decision {id: $src})-[r:LINK]->(:Decision {id: $tgt}) \
-             WHERE r.kind = $kind RETURN 1",
-            params(&[
-                ("src", Value::from(source_id)),
-                ("tgt", Value::from(target_id)),
-                ("kind", Value::from(kind.to_string().as_str())),
-            ]),
-        )?;
-        if check.row_count() == 0 {
-            return Err(DictumError::LinkNotFound);
-        }
-
-        session.execute_with_params(
-            "MATCH (:Decision {id: $src})-[r:LINK]->(:Decision {id: $tgt}) \
-             WHERE r.kind = $kind DELETE r",
-            params(&[
-                ("src", Value::from(source_id)),
-                ("tgt", Value::from(target_id)),
-                ("kind", Value::from(kind.to_string().as_str())),
-            ]),
+        self.session().execute_with_params(
+            "MATCH (:Decision)-[r:LINK]->(:Decision) WHERE id(r) = $rid DELETE r",
+            params(&[("rid", edge_id)]),
         )?;
         Ok(())
     }
@@ -419,14 +619,13 @@ impl Store for GrafeoStore {
 
     fn links_of_kind(&self, kind: &LinkKind) -> Result<Vec<(String, String)>> {
         let session = self.session();
-        let result = session.execute_with_params(
+        let result = session.execute(
             "MATCH (s:Decision)-[r:LINK]->(t:Decision) \
-             WHERE r.kind = $kind \
-             RETURN s.id, t.id ORDER BY r.created_at",
-            params(&[("kind", Value::from(kind.to_string().as_str()))]),
+             RETURN s.id, t.id, r.kind ORDER BY r.created_at",
         )?;
+        let kind = kind.to_string();
         let mut out = Vec::new();
-        for row in result.iter() {
+        for row in result.iter().filter(|row| row[2].as_str() == Some(kind.as_str())) {
             out.push((
                 row[0].as_str().unwrap_or("").to_string(),
                 row[1].as_str().unwrap_or("").to_string(),
@@ -437,9 +636,9 @@ impl Store for GrafeoStore {
 
     fn neighborhood(&self, id: &str, depth: u32) -> Result<Neighborhood> {
         // BFS — same algorithm as SQLite backend, just using trait methods
-        let mut visited_ids: HashSet<String> = HashSet::new();
+        let mut hops: HashMap<String, u32> = HashMap::new();
         let mut queue: VecDeque<(String, u32)> = VecDeque::new();
-        visited_ids.insert(id.to_string());
+        hops.insert(id.to_string(), 0);
         queue.push_back((id.to_string(), 0));
 
         let mut all_links: Vec<Link> = Vec::new();
@@ -455,7 +654,8 @@ impl Store for GrafeoStore {
                 } else {
                     &link.source_id
                 };
-                if visited_ids.insert(neighbor.clone()) {
+                if let Entry::Vacant(slot) = hops.entry(neighbor.clone()) {
+                    slot.insert(current_depth + 1);
                     queue.push_back((neighbor.clone(), current_depth + 1));
                 }
             }
@@ -467,13 +667,19 @@ impl Store for GrafeoStore {
             seen.insert((l.source_id.clone(), l.target_id.clone(), l.kind.to_string()))
         });
 
+        let mut node_ids: Vec<&String> = hops.keys().collect();
+        node_ids.sort_by(|a, b| hops[*a].cmp(&hops[*b]).then_with(|| a.cmp(b)));
+
         let mut decisions = Vec::new();
-        for node_id in &visited_ids {
+        for node_id in node_ids {
             decisions.push(self.decision_get(node_id)?);
         }
         Ok(Neighborhood {
+            root: id.to_string(),
+            depth,
             decisions,
             links: all_links,
+            hops,
         })
     }
 
@@ -511,6 +717,85 @@ fn opt_value(opt: &Option<String>) -> Value {
     }
 }
 
+/// Whether a GQL statement contains a mutating keyword outside string literals,
+/// backtick-quoted names and property/label positions (`d.set`, `:Delete`).
+fn is_mutating(query: &str) -> bool {
+    const MUTATING: &[&str] = &[
+        "INSERT", "CREATE", "MERGE", "SET", "REMOVE", "DELETE", "DETACH", "DROP", "ALTER",
+    ];
+    let chars: Vec<char> = query.chars().collect();
+    let mut i = 0;
+    let mut prev = ' ';
+    while i < chars.len() {
+        let c = chars[i];
+        if c == '\'' || c == '"' || c == '`' {
+            i += 1;
+            while i < chars.len() && chars[i] != c {
+                if chars[i] == '\\' {
+                    i += 1;
+                }
+                i += 1;
+            }
+            prev = c;
+            i += 1;
+            continue;
+        }
+        if c.is_alphabetic() || c == '_' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            let word: String = chars[start..i].iter().collect();
+            if !matches!(prev, '.' | ':' | '$')
+                && MUTATING.iter().any(|kw| kw.eq_ignore_ascii_case(&word))
+            {
+                return true;
+            }
+            prev = 'a';
+            continue;
+        }
+        if !c.is_whitespace() {
+            prev = c;
+        }
+        i += 1;
+    }
+    false
+}
+
+fn value_to_json(value: &Value) -> serde_json::Value {
+    match value {
+        Value::Null => serde_json::Value::Null,
+        Value::Bool(b) => serde_json::Value::from(*b),
+        Value::Int64(i) => serde_json::Value::from(*i),
+        Value::Float64(f) => serde_json::Value::from(*f),
+        Value::String(s) => serde_json::Value::from(s.as_str()),
+        Value::List(items) => items.iter().map(value_to_json).collect(),
+        Value::Map(map) => map
+            .iter()
+            .map(|(k, v)| (k.as_str().to_string(), value_to_json(v)))
+            .collect::<serde_json::Map<_, _>>()
+            .into(),
+        other => serde_json::Value::from(format!("{:?}", other)),
+    }
+}
+
+/// Convert a JSON value (e.g. a `--param`) into a GQL parameter value.
+pub fn json_to_value(value: &serde_json::Value) -> Value {
+    match value {
+        serde_json::Value::Null => Value::Null,
+        serde_json::Value::Bool(b) => Value::from(*b),
+        serde_json::Value::Number(n) => match n.as_i64() {
+            Some(i) => Value::from(i),
+            None => Value::from(n.as_f64().unwrap_or(0.0)),
+        },
+        serde_json::Value::String(s) => Value::from(s.as_str()),
+        serde_json::Value::Array(items) => {
+            Value::from(items.iter().map(json_to_value).collect::<Vec<_>>())
+        }
+        serde_json::Value::Object(_) => Value::from(value.to_string().as_str()),
+    }
+}
+
 fn params(pairs: &[(&str, Value)]) -> HashMap<String, Value> {
     pairs
         .iter()
@@ -536,27 +821,17 @@ fn row_to_link(row: &[Value]) -> Link {
 mod tests {
     use super::*;
     use crate::db::store::Store;
+    use crate::model::TestProject;
 
     fn make_store() -> GrafeoStore {
         GrafeoStore::in_memory().unwrap()
     }
 
     fn make_decision(id: &str, kind: Kind, weight: Weight, scope: Option<&str>) -> Decision {
-        Decision {
-            id: id.to_string(),
-            title: format!("Decision {}", id),
-            body: None,
-            level: Level::Tactical,
-            status: Status::Active,
-            superseded_by: None,
-            author: "test".to_string(),
-            created_at: "2025-01-01T00:00:00Z".to_string(),
-            updated_at: "2025-01-01T00:00:00Z".to_string(),
-            labels: Vec::new(),
-            kind,
-            weight,
-            rebuttal: None,
-            scope: scope.map(|s| s.to_string()),
+        let d = Decision::test(id).with_kind(kind).with_weight(weight);
+        match scope {
+            Some(scope) => d.with_scope(scope),
+            None => d,
         }
     }
 
@@ -615,12 +890,112 @@ mod tests {
         store.decision_insert(&make_decision("d-1", Kind::Rule, Weight::Must, Some("auth"))).unwrap();
         store.decision_insert(&make_decision("d-2", Kind::Rule, Weight::Must, Some("logging"))).unwrap();
         store.decision_insert(&make_decision("d-3", Kind::Rule, Weight::Must, None)).unwrap();
+        store.decision_insert(&make_decision("d-4", Kind::Rule, Weight::Must, Some("auth/oauth"))).unwrap();
+
+        let ids = |scope: &str, scope_exact: bool| -> Vec<String> {
+            let mut ids: Vec<String> = store.decision_list(&ListFilter {
+                scope: Some(scope.to_string()), scope_exact, ..Default::default()
+            }).unwrap().into_iter().map(|d| d.id).collect();
+            ids.sort();
+            ids
+        };
 
-        let results = store.decision_list(&ListFilter {
-            scope: Some("auth".to_string()), ..Default::default()
-        }).unwrap();
-        assert_eq!(results.len(), 1);
-        assert_eq!(results[0].id, "d-1");
+        // Enclosing and unscoped decisions are inherited; narrower ones are not
+        assert_eq!(ids("auth", false), vec!["d-1", "d-3"]);
+        assert_eq!(ids("auth/oauth/", false), vec!["d-1", "d-3", "d-4"]);
+        assert_eq!(ids("auth", true), vec!["d-1"]);
+    }
+
+    #[test]
+    fn sort_and_paginate() {
+        let mut store = make_store();
+        store.decision_insert(&make_decision("d-1", Kind::Rule, Weight::May, None)).unwrap();
+        store.decision_insert(&make_decision("d-2", Kind::Rule, Weight::Must, None)).unwrap();
+        store.decision_insert(&make_decision("d-3", Kind::Rule, Weight::Should, None)).unwrap();
+        store.decision_insert(&make_decision("d-4", Kind::Rule, Weight::Must, None)).unwrap();
+
+        let ids = |filter: ListFilter| -> Vec<String> {
+            store.decision_list(&filter).unwrap().into_iter().map(|d| d.id).collect()
+        };
+        assert_eq!(
+            ids(ListFilter { sort: Some("weight".parse().unwrap()), ..Default::default() }),
+            vec!["d-2", "d-4", "d-3", "d-1"]
+        );
+        assert_eq!(
+            ids(ListFilter {
+                sort: Some("weight:desc".parse().unwrap()),
+                limit: Some(2),
+                offset: 1,
+                ..Default::default()
+            }),
+            vec!["d-3", "d-2"]
+        );
+        assert_eq!(ids(ListFilter { offset: 3, ..Default::default() }).len(), 1);
+    }
+
+    #[test]
+    fn filter_by_where_expression() {
+        let mut store = make_store();
+        store.decision_insert(&make_decision("d-1", Kind::Rule, Weight::Must, Some("legacy-api"))).unwrap();
+        store.decision_insert(&make_decision("d-2", Kind::Constraint, Weight::Must, None)).unwrap();
+        store.decision_insert(&make_decision("d-3", Kind::Rule, Weight::Must, Some("auth"))).unwrap();
+        store.decision_insert(&make_decision("d-4", Kind::Choice, Weight::Must, None)).unwrap();
+        for id in ["d-1", "d-2", "d-4"] {
+            store.label_add(id, "security").unwrap();
+        }
+
+        let filter = |input: &str| ListFilter {
+            expr: Some(crate::db::expr::parse(input).unwrap()),
+            ..Default::default()
+        };
+        let ids = |results: Vec<Decision>| -> Vec<String> {
+            let mut ids: Vec<String> = results.into_iter().map(|d| d.id).collect();
+            ids.sort();
+            ids
+        };
+
+        let results = store.decision_list(&filter(
+            "kind in (rule, constraint) and weight = must and label:security and not scope:legacy*",
+        )).unwrap();
+        assert_eq!(ids(results), vec!["d-2"]);
+
+        let results = store.decision_list(&filter("scope:*a*th or label != security")).unwrap();
+        assert_eq!(ids(results), vec!["d-3"]);
+
+        let results = store.decision_list(&filter("created >= 2025-01-01 and id:d-?")).unwrap();
+        assert!(results.is_empty());
+    }
+
+    #[test]
+    fn raw_queries_are_read_only_by_default() {
+        let mut store = make_store();
+        store.decision_insert(&make_decision("d-1", Kind::Rule, Weight::Must, None)).unwrap();
+
+        let result = store
+            .query_raw(
+                "MATCH (d:Decision) WHERE d.kind = $k RETURN d.id AS id",
+                params(&[("k", Value::from("rule"))]),
+                false,
+            )
+            .unwrap();
+        assert_eq!(result.columns, vec!["id"]);
+        assert_eq!(result.rows, vec![vec![serde_json::json!("d-1")]]);
+
+        let update = "MATCH (d:Decision {id: $id}) SET d.title = 'changed'";
+        let err = store.query_raw(update, params(&[("id", Value::from("d-1"))]), false);
+        assert!(matches!(err, Err(DictumError::ReadOnlyQuery)));
+        assert_eq!(store.decision_get("d-1").unwrap().title, "Decision d-1");
+
+        store.query_raw(update, params(&[("id", Value::from("d-1"))]), true).unwrap();
+        assert_eq!(store.decision_get("d-1").unwrap().title, "changed");
+    }
+
+    #[test]
+    fn mutation_guard_ignores_strings_and_properties() {
+        assert!(is_mutating("MATCH (d) detach delete d"));
+        assert!(is_mutating("INSERT (:Decision {id: 'x'})"));
+        assert!(!is_mutating("MATCH (d:Decision) WHERE d.title = 'set up CREATE' RETURN d.set"));
+        assert!(!is_mutating("MATCH (d:Decision) RETURN d.`delete`, $remove"));
     }
 
     #[test]
@@ -733,6 +1108,91 @@ mod tests {
         assert!(links.is_empty());
     }
 
+    #[test]
+    fn neighborhood_groups_by_hop() {
+        let mut store = make_store();
+        for id in ["d-1", "d-2", "d-3", "d-4"] {
+            store.decision_insert(&make_decision(id, Kind::Rule, Weight::Must, None)).unwrap();
+        }
+        for (src, tgt) in [("d-2", "d-1"), ("d-3", "d-2"), ("d-4", "d-3")] {
+            store.link_insert(&Link {
+                source_id: src.to_string(), target_id: tgt.to_string(),
+                kind: LinkKind::Refines, created_at: "2025-01-01T00:00:00Z".to_string(),
+                reason: None,
+            }).unwrap();
+        }
+
+        let nb = store.neighborhood("d-1", 2).unwrap();
+        let ids: Vec<&str> = nb.decisions.iter().map(|d| d.id.as_str()).collect();
+        assert_eq!(ids, vec!["d-1", "d-2", "d-3"]);
+        assert_eq!(nb.hop_of("d-3"), 2);
+        assert_eq!(nb.links.len(), 2);
+    }
+
+    #[test]
+    fn links_of_kind_on_persistent_store() {
+        let project = TestProject::empty();
+
+        let mut store = GrafeoStore::open(&project.root).unwrap();
+        store.decision_insert(&make_decision("d-1", Kind::Rule, Weight::Must, None)).unwrap();
+        store.decision_insert(&make_decision("d-2", Kind::Choice, Weight::Should, None)).unwrap();
+        let link = Link {
+            source_id: "d-1".to_string(), target_id: "d-2".to_string(),
+            kind: LinkKind::Requires, created_at: "2025-01-01T00:00:00Z".to_string(),
+            reason: None,
+        };
+        store.link_insert(&link).unwrap();
+
+        assert_eq!(store.links_of_kind(&LinkKind::Requires).unwrap().len(), 1);
+        assert!(matches!(store.link_insert(&link), Err(DictumError::LinkAlreadyExists)));
+        store.link_delete("d-1", &LinkKind::Requires, "d-2").unwrap();
+        assert!(store.links_of_kind(&LinkKind::Requires).unwrap().is_empty());
+    }
+
+    #[test]
+    fn status_changes_record_transitions() {
+        let mut store = make_store();
+        store.decision_insert(&make_decision("d-1", Kind::Rule, Weight::Must, None)).unwrap();
+        store.decision_insert(&make_decision("d-2", Kind::Rule, Weight::Must, None)).unwrap();
+        store.decision_update_status("d-1", &Status::Superseded, Some("d-2")).unwrap();
+        store.decision_update_status("d-1", &Status::Deprecated, None).unwrap();
+
+        let transitions = store.transitions(Some("d-1")).unwrap();
+        assert_eq!(transitions.len(), 2);
+        assert_eq!(transitions[0].from_status, Status::Active);
+        assert_eq!(transitions[1].from_status, Status::Superseded);
+        assert_eq!(transitions[1].from_superseded_by.as_deref(), Some("d-2"));
+        assert!(store.transitions(Some("d-2")).unwrap().is_empty());
+        assert_eq!(store.transitions(None).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn link_cycle_rejected_for_acyclic_kind() {
+        let mut store = make_store();
+        for id in ["d-1", "d-2", "d-3"] {
+            store.decision_insert(&make_decision(id, Kind::Rule, Weight::Must, None)).unwrap();
+        }
+        let link = |src: &str, tgt: &str, kind: LinkKind| Link {
+            source_id: src.to_string(), target_id: tgt.to_string(),
+            kind, created_at: "2025-01-01T00:00:00Z".to_string(), reason: None,
+        };
+        store.link_insert(&link("d-1", "d-2", LinkKind::Refines)).unwrap();
+        store.link_insert(&link("d-2", "d-3", LinkKind::Refines)).unwrap();
+
+        match store.link_insert(&link("d-3", "d-1", LinkKind::Refines)) {
+            Err(DictumError::LinkCycle { kind, cycle }) => {
+                assert_eq!(kind, "refines");
+                assert_eq!(cycle, "d-3 -> d-1 -> d-2 -> d-3");
+            }
+            other => panic!("expected LinkCycle, got {:?}", other.map(|_| ())),
+        }
+
+        // Cycles are allowed for kinds outside the acyclic set
+        store.link_insert(&link("d-3", "d-1", LinkKind::Supports)).unwrap();
+        let mut store = store.with_acyclic_kinds(Vec::new());
+        store.link_insert(&link("d-3", "d-1", LinkKind::Refines)).unwrap();
+    }
+
     #[test]
     fn search_finds_by_title() {
         let mut store = make_store();
@@ -745,8 +1205,43 @@ mod tests {
             ..make_decision("d-2", Kind::Choice, Weight::Should, None)
         }).unwrap();
 
-        let results = store.decision_search("graph").unwrap();
+        let results = store.decision_search(&SearchQuery::parse("graph").unwrap()).unwrap();
         assert_eq!(results.len(), 1);
-        assert_eq!(results[0].id, "d-1");
+        assert_eq!(results[0].decision.id, "d-1");
+    }
+
+    #[test]
+    fn search_ranks_by_text_index_score() {
+        let mut store = make_store();
+        store.decision_insert(&Decision {
+            title: "Cache sessions".to_string(),
+            body: Some("Cache everything in the session cache".to_string()),
+            ..make_decision("d-1", Kind::Choice, Weight::Should, None)
+        }).unwrap();
+        store.decision_insert(&Decision {
+            title: "Use Postgres".to_string(),
+            rebuttal: Some("unless the cache layer is enough".to_string()),
+            ..make_decision("d-2", Kind::Choice, Weight::Should, None)
+        }).unwrap();
+        store.decision_insert(&Decision {
+            title: "Old caching rule".to_string(),
+            ..make_decision("d-3", Kind::Rule, Weight::Must, None)
+        }).unwrap();
+        store.decision_update_status("d-3", &Status::Deprecated, None).unwrap();
+
+        let hits = store.decision_search(&SearchQuery::parse("cache").unwrap()).unwrap();
+        let ids: Vec<&str> = hits.iter().map(|h| h.decision.id.as_str()).collect();
+        assert_eq!(ids, vec!["d-1", "d-2"]);
+        assert!(hits[0].score > hits[1].score);
+        assert_eq!(hits[1].field, SearchField::Rebuttal);
+
+        let mut query = SearchQuery::parse("cach*").unwrap();
+        assert_eq!(store.decision_search(&query).unwrap().len(), 3);
+        query.status = Some(Status::Active);
+        assert_eq!(store.decision_search(&query).unwrap().len(), 2);
+
+        let hits = store.decision_search(&SearchQuery::parse("\"session cache\"").unwrap()).unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].snippet, "Cache everything in the **session cache**");
     }
 }
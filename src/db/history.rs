@@ -0,0 +1,596 @@
+//! Point-in-time reconstruction for `--as-of`. Amending creates a new
+//! decision, so a decision's fields only change in place when an import
+//! rewrites them, and each such rewrite is recorded as a transition holding
+//! the values it replaced. The state at an instant is then: the decisions
+//! created by then, each with the status it had before the first transition
+//! recorded after that instant and with later field edits undone, and the
+//! links created by then between decisions that existed. Removed links are
+//! not recorded and do not reappear. [`AsOfStore`] serves that state through
+//! the `Store` trait, so read-only commands work unchanged.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::Path;
+use std::process::Command;
+
+use chrono::{DateTime, NaiveDate, Utc};
+
+use super::search::{SearchHit, SearchQuery};
+use super::store::{ListFilter, Neighborhood, Store};
+use super::Sort;
+use crate::error::{DictumError, Result};
+use crate::model::{Decision, Link, LinkKind, Origin, Status, Transition};
+
+/// Resolve an `--as-of` value: an RFC3339 timestamp, a `YYYY-MM-DD` date (the
+/// end of that day, UTC), or a git revision resolved to its commit date in the
+/// repository at `repo`.
+pub fn resolve(spec: &str, repo: &Path) -> Result<DateTime<Utc>> {
+    if let Some(t) = parse_time(spec) {
+        return Ok(t);
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(spec, "%Y-%m-%d") {
+        let end = date.and_hms_opt(23, 59, 59).expect("valid time of day");
+        return Ok(end.and_utc());
+    }
+    if spec.starts_with('-') {
+        return Err(DictumError::InvalidAsOf(spec.to_string()));
+    }
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(repo)
+        .args(["show", "-s", "--format=%cI"])
+        .arg(format!("{}^{{commit}}", spec))
+        .output()
+        .map_err(|e| DictumError::InvalidAsOf(format!("{} (git: {})", spec, e)))?;
+    if !output.status.success() {
+        return Err(DictumError::InvalidAsOf(spec.to_string()));
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    parse_time(stdout.trim()).ok_or_else(|| DictumError::InvalidAsOf(spec.to_string()))
+}
+
+fn parse_time(s: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(s)
+        .ok()
+        .map(|t| t.with_timezone(&Utc))
+}
+
+/// Whether a stored timestamp is at or before `at`, to the second, since git
+/// commit times carry no fractions. Unparseable timestamps count as before,
+/// so malformed data is shown rather than hidden.
+fn not_after(timestamp: &str, at: &DateTime<Utc>) -> bool {
+    parse_time(timestamp).is_none_or(|t| t.timestamp() <= at.timestamp())
+}
+
+pub struct Snapshot {
+    at: DateTime<Utc>,
+    created: HashMap<String, String>,
+    transitions: HashMap<String, Vec<Transition>>,
+}
+
+impl Snapshot {
+    pub fn load(store: &dyn Store, at: DateTime<Utc>) -> Result<Self> {
+        let created = store
+            .decision_get_all()?
+            .into_iter()
+            .map(|d| (d.id, d.created_at))
+            .collect();
+        let mut transitions: HashMap<String, Vec<Transition>> = HashMap::new();
+        for t in store.transitions(None)? {
+            transitions.entry(t.decision_id.clone()).or_default().push(t);
+        }
+        Ok(Snapshot {
+            at,
+            created,
+            transitions,
+        })
+    }
+
+    fn existed(&self, id: &str) -> bool {
+        self.created
+            .get(id)
+            .is_some_and(|created| not_after(created, &self.at))
+    }
+
+    /// The decision as it was at the snapshot time, or `None` if it did not exist yet.
+    pub fn decision(&self, mut d: Decision) -> Option<Decision> {
+        if !not_after(&d.created_at, &self.at) {
+            return None;
+        }
+        let recorded = self.transitions.get(&d.id).map(Vec::as_slice).unwrap_or(&[]);
+        // Undo field edits made since, latest first, so the earliest one's
+        // previous values are what remains
+        for t in recorded.iter().rev().take_while(|t| !not_after(&t.at, &self.at)) {
+            d = t.revert_fields(d);
+        }
+        if let Some(next) = recorded.iter().find(|t| !not_after(&t.at, &self.at)) {
+            d.status = next.from_status.clone();
+            d.superseded_by = next.from_superseded_by.clone();
+            d.updated_at = recorded
+                .iter()
+                .take_while(|t| not_after(&t.at, &self.at))
+                .last()
+                .map_or(d.created_at.clone(), |t| t.at.clone());
+        } else if recorded.is_empty()
+            && !not_after(&d.updated_at, &self.at)
+            && matches!(d.status, Status::Superseded | Status::Deprecated)
+        {
+            // Changed before transitions were recorded: it was active until then
+            d.status = Status::Active;
+            d.superseded_by = None;
+            d.updated_at = d.created_at.clone();
+        }
+        Some(d)
+    }
+
+    /// Whether a link existed at the snapshot time.
+    pub fn link(&self, link: &Link) -> bool {
+        not_after(&link.created_at, &self.at)
+            && self.existed(&link.source_id)
+            && self.existed(&link.target_id)
+    }
+
+    /// `Store::decision_list` at the snapshot time. Status and order depend on
+    /// reconstructed values, so those (and paging) are applied here; the other
+    /// filters are pushed down.
+    pub fn list(&self, store: &dyn Store, filter: &ListFilter) -> Result<Vec<Decision>> {
+        let pushed = ListFilter {
+            status: None,
+            sort: None,
+            limit: None,
+            offset: 0,
+            ..filter.clone()
+        };
+        let mut decisions: Vec<Decision> = store
+            .decision_list(&pushed)?
+            .into_iter()
+            .filter_map(|d| self.decision(d))
+            .filter(|d| filter.status.as_ref().is_none_or(|s| &d.status == s))
+            .collect();
+        let sort = filter.sort.unwrap_or(Sort::DEFAULT);
+        decisions.sort_by(|a, b| sort.compare(a, b));
+        Ok(decisions
+            .into_iter()
+            .skip(filter.offset)
+            .take(filter.limit.unwrap_or(usize::MAX))
+            .collect())
+    }
+}
+
+const TOKEN_PREFIX: &str = "ctx-";
+
+/// A continuation token for `context --since`. Timestamps are compared to
+/// the second, so the token points one second back: a change made in the
+/// same second as this call is reported again next time rather than missed.
+pub fn continuation_token(now: DateTime<Utc>) -> String {
+    format!("{}{}", TOKEN_PREFIX, now.timestamp() - 1)
+}
+
+/// Resolve a `--since` value: a token from an earlier call, or anything
+/// [`resolve`] accepts. A bare date means the start of that day, so changes
+/// made during it are reported; changes are those after the returned time,
+/// which is therefore the last second of the day before.
+pub fn resolve_since(spec: &str, repo: &Path) -> Result<DateTime<Utc>> {
+    if let Ok(date) = NaiveDate::parse_from_str(spec, "%Y-%m-%d") {
+        let start = date.and_hms_opt(0, 0, 0).expect("valid time of day");
+        return Ok(start.and_utc() - chrono::Duration::seconds(1));
+    }
+    match spec.strip_prefix(TOKEN_PREFIX) {
+        Some(secs) => secs
+            .parse::<i64>()
+            .ok()
+            .and_then(|s| DateTime::from_timestamp(s, 0))
+            .ok_or_else(|| DictumError::InvalidSince(spec.to_string())),
+        None => resolve(spec, repo).map_err(|_| DictumError::InvalidSince(spec.to_string())),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Change {
+    /// Created since, and not replacing anything
+    Added,
+    /// Created since, superseding a decision that was active
+    Amended,
+    /// Existed but was not active, and is now
+    Activated,
+    /// Unchanged itself, but with links created since
+    Relinked,
+}
+
+impl Change {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Change::Added => "added",
+            Change::Amended => "amended",
+            Change::Activated => "activated",
+            Change::Relinked => "relinked",
+        }
+    }
+}
+
+/// What happened to the decisions matching a filter between two instants.
+#[derive(Debug)]
+pub struct Changes {
+    pub since: DateTime<Utc>,
+    /// Active decisions that are new or changed, and how
+    pub changed: HashMap<String, Change>,
+    /// `(old, successor)` for decisions superseded since
+    pub superseded: Vec<(String, String)>,
+    /// Decisions deprecated since
+    pub deprecated: Vec<String>,
+}
+
+/// Compare the decisions matching `filter` (whatever their status) now and at `since`.
+pub fn changes_since(store: &dyn Store, filter: &ListFilter, since: DateTime<Utc>) -> Result<Changes> {
+    let snapshot = Snapshot::load(store, since)?;
+    let current = store.decision_list(&ListFilter {
+        status: None,
+        sort: Some(Sort {
+            field: super::sort::SortField::Created,
+            descending: false,
+        }),
+        limit: None,
+        offset: 0,
+        ..filter.clone()
+    })?;
+
+    let mut changes = Changes {
+        since,
+        changed: HashMap::new(),
+        superseded: Vec::new(),
+        deprecated: Vec::new(),
+    };
+    let mut successors = HashSet::new();
+    for d in &current {
+        let was_active = snapshot
+            .decision(d.clone())
+            .is_some_and(|then| then.status == Status::Active);
+        match (was_active, &d.status) {
+            (true, Status::Superseded) => {
+                let successor = d.superseded_by.clone().unwrap_or_default();
+                successors.insert(successor.clone());
+                changes.superseded.push((d.id.clone(), successor));
+            }
+            (true, Status::Deprecated) => changes.deprecated.push(d.id.clone()),
+            _ => {}
+        }
+    }
+    for d in current.iter().filter(|d| d.status == Status::Active) {
+        let change = if !snapshot.existed(&d.id) {
+            if successors.contains(&d.id) {
+                Change::Amended
+            } else {
+                Change::Added
+            }
+        } else if snapshot.decision(d.clone()).is_some_and(|then| then.status != Status::Active) {
+            Change::Activated
+        } else if store
+            .links_for_decision(&d.id)?
+            .iter()
+            .any(|l| !not_after(&l.created_at, &since))
+        {
+            Change::Relinked
+        } else {
+            continue;
+        };
+        changes.changed.insert(d.id.clone(), change);
+    }
+    Ok(changes)
+}
+
+/// A read-only view of a store as it was at one instant.
+pub struct AsOfStore {
+    inner: Box<dyn Store>,
+    snapshot: Snapshot,
+}
+
+impl AsOfStore {
+    pub fn new(inner: Box<dyn Store>, at: DateTime<Utc>) -> Result<Self> {
+        let snapshot = Snapshot::load(&*inner, at)?;
+        Ok(AsOfStore { inner, snapshot })
+    }
+
+    fn existing_ids(&self) -> impl Iterator<Item = &String> {
+        self.snapshot
+            .created
+            .iter()
+            .filter(|(_, created)| not_after(created, &self.snapshot.at))
+            .map(|(id, _)| id)
+    }
+}
+
+impl Store for AsOfStore {
+    fn decision_insert(&mut self, _decision: &Decision) -> Result<()> {
+        Err(DictumError::AsOfWrite)
+    }
+
+    fn decision_get(&self, id: &str) -> Result<Decision> {
+        let decision = self.inner.decision_get(id)?;
+        self.snapshot
+            .decision(decision)
+            .ok_or_else(|| DictumError::DecisionNotFound(id.to_string()))
+    }
+
+    fn decision_list(&self, filter: &ListFilter) -> Result<Vec<Decision>> {
+        self.snapshot.list(&*self.inner, filter)
+    }
+
+    fn decision_update_status(
+        &mut self,
+        _id: &str,
+        _status: &Status,
+        _superseded_by: Option<&str>,
+    ) -> Result<()> {
+        Err(DictumError::AsOfWrite)
+    }
+
+    fn decision_reject(&mut self, _id: &str, _origin: &Origin) -> Result<()> {
+        Err(DictumError::AsOfWrite)
+    }
+
+    fn decision_replace(&mut self, _decision: &Decision) -> Result<()> {
+        Err(DictumError::AsOfWrite)
+    }
+
+    fn decision_search(&self, query: &SearchQuery) -> Result<Vec<SearchHit>> {
+        let unfiltered = SearchQuery {
+            status: None,
+            ..query.clone()
+        };
+        Ok(self
+            .inner
+            .decision_search(&unfiltered)?
+            .into_iter()
+            .filter_map(|hit| {
+                let decision = self.snapshot.decision(hit.decision.clone())?;
+                query
+                    .status
+                    .as_ref()
+                    .is_none_or(|s| &decision.status == s)
+                    .then_some(SearchHit { decision, ..hit })
+            })
+            .collect())
+    }
+
+    fn label_add(&mut self, _decision_id: &str, _label: &str) -> Result<()> {
+        Err(DictumError::AsOfWrite)
+    }
+
+    fn transition_insert(&mut self, _transition: &Transition) -> Result<()> {
+        Err(DictumError::AsOfWrite)
+    }
+
+    fn transitions(&self, decision_id: Option<&str>) -> Result<Vec<Transition>> {
+        let mut transitions = self.inner.transitions(decision_id)?;
+        transitions.retain(|t| not_after(&t.at, &self.snapshot.at));
+        Ok(transitions)
+    }
+
+    fn link_insert(&mut self, _link: &Link) -> Result<()> {
+        Err(DictumError::AsOfWrite)
+    }
+
+    fn link_delete(&mut self, _source_id: &str, _kind: &LinkKind, _target_id: &str) -> Result<()> {
+        Err(DictumError::AsOfWrite)
+    }
+
+    fn links_for_decision(&self, decision_id: &str) -> Result<Vec<Link>> {
+        let mut links = self.inner.links_for_decision(decision_id)?;
+        links.retain(|l| self.snapshot.link(l));
+        Ok(links)
+    }
+
+    fn links_of_kind(&self, kind: &LinkKind) -> Result<Vec<(String, String)>> {
+        // Pairs carry no timestamps, so collect each existing decision's outgoing links
+        let mut ids: Vec<&String> = self.existing_ids().collect();
+        ids.sort();
+        let mut pairs = Vec::new();
+        for id in ids {
+            for link in self.links_for_decision(id)? {
+                if &link.source_id == id && &link.kind == kind {
+                    pairs.push((link.source_id, link.target_id));
+                }
+            }
+        }
+        Ok(pairs)
+    }
+
+    fn neighborhood(&self, id: &str, depth: u32) -> Result<Neighborhood> {
+        self.decision_get(id)?;
+        // The current neighborhood is a superset; recompute hops over the links that existed
+        let current = self.inner.neighborhood(id, depth)?;
+        let links: Vec<Link> = current
+            .links
+            .into_iter()
+            .filter(|l| self.snapshot.link(l))
+            .collect();
+
+        let mut hops: HashMap<String, u32> = HashMap::from([(id.to_string(), 0)]);
+        let mut queue = VecDeque::from([(id.to_string(), 0)]);
+        while let Some((current_id, hop)) = queue.pop_front() {
+            if hop >= depth {
+                continue;
+            }
+            for link in &links {
+                let neighbor = if link.source_id == current_id {
+                    &link.target_id
+                } else if link.target_id == current_id {
+                    &link.source_id
+                } else {
+                    continue;
+                };
+                if !hops.contains_key(neighbor) {
+                    hops.insert(neighbor.clone(), hop + 1);
+                    queue.push_back((neighbor.clone(), hop + 1));
+                }
+            }
+        }
+
+        let mut decisions: Vec<Decision> = current
+            .decisions
+            .into_iter()
+            .filter(|d| hops.contains_key(&d.id))
+            .filter_map(|d| self.snapshot.decision(d))
+            .collect();
+        decisions.sort_by(|a, b| hops[&a.id].cmp(&hops[&b.id]).then_with(|| a.id.cmp(&b.id)));
+        Ok(Neighborhood {
+            root: id.to_string(),
+            depth,
+            decisions,
+            links: links
+                .into_iter()
+                .filter(|l| hops.contains_key(&l.source_id) && hops.contains_key(&l.target_id))
+                .collect(),
+            hops,
+        })
+    }
+
+    fn reachable(&self, id: &str, kinds: &[LinkKind]) -> Result<Vec<String>> {
+        let mut edges = Vec::new();
+        for kind in kinds {
+            edges.extend(self.links_of_kind(kind)?);
+        }
+        let mut seen: HashSet<String> = HashSet::from([id.to_string()]);
+        let mut queue = VecDeque::from([id.to_string()]);
+        let mut reached = Vec::new();
+        while let Some(current) = queue.pop_front() {
+            for (src, tgt) in &edges {
+                if src == &current && seen.insert(tgt.clone()) {
+                    reached.push(tgt.clone());
+                    queue.push_back(tgt.clone());
+                }
+            }
+        }
+        Ok(reached)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(at: &str, transitions: Vec<Transition>) -> Snapshot {
+        let mut by_id: HashMap<String, Vec<Transition>> = HashMap::new();
+        for t in transitions {
+            by_id.entry(t.decision_id.clone()).or_default().push(t);
+        }
+        Snapshot {
+            at: parse_time(at).unwrap(),
+            created: HashMap::from([
+                ("d-1".to_string(), "2025-01-01T00:00:00Z".to_string()),
+                ("d-2".to_string(), "2025-03-01T00:00:00Z".to_string()),
+            ]),
+            transitions: by_id,
+        }
+    }
+
+    /// d-1 as it is now, superseded by d-2 on 2025-03-01.
+    fn superseded() -> (Decision, Transition) {
+        let mut d1 = Decision::test("d-1").with_status(Status::Superseded);
+        d1.superseded_by = Some("d-2".to_string());
+        d1.updated_at = "2025-03-01T00:00:00Z".to_string();
+        let transition = Transition {
+            decision_id: "d-1".to_string(),
+            from_status: Status::Active,
+            to_status: Status::Superseded,
+            from_superseded_by: None,
+            from_fields: Default::default(),
+            at: "2025-03-01T00:00:00Z".to_string(),
+        };
+        (d1, transition)
+    }
+
+    #[test]
+    fn rewinds_status_changes_made_after_the_snapshot() {
+        let (d1, transition) = superseded();
+        let past = snapshot("2025-02-01T00:00:00Z", vec![transition.clone()]).decision(d1.clone()).unwrap();
+        assert_eq!(past.status, Status::Active);
+        assert_eq!(past.superseded_by, None);
+        assert_eq!(past.updated_at, "2025-01-01T00:00:00Z");
+
+        let after = snapshot("2025-03-02T00:00:00Z", vec![transition]);
+        assert_eq!(after.decision(d1).unwrap().status, Status::Superseded);
+    }
+
+    #[test]
+    fn infers_status_from_updated_at_without_recorded_transitions() {
+        let (d1, _) = superseded();
+        let legacy = snapshot("2025-02-01T00:00:00Z", Vec::new());
+        assert_eq!(legacy.decision(d1).unwrap().status, Status::Active);
+    }
+
+    #[test]
+    fn hides_decisions_and_links_created_after_the_snapshot() {
+        let d2 = Decision::test("d-2").with_created("2025-03-01T00:00:00Z");
+        let before = snapshot("2025-02-01T00:00:00Z", Vec::new());
+        let after = snapshot("2025-03-02T00:00:00Z", Vec::new());
+        assert!(before.decision(d2).is_none());
+
+        let link = Link {
+            source_id: "d-2".to_string(),
+            target_id: "d-1".to_string(),
+            kind: crate::model::LinkKind::Supersedes,
+            created_at: "2025-03-01T00:00:00Z".to_string(),
+            reason: None,
+        };
+        assert!(!before.link(&link));
+        assert!(after.link(&link));
+    }
+
+    #[test]
+    fn reverts_field_edits_made_after_the_snapshot() {
+        let original = Decision::test("d-1");
+        let renamed = original.clone().with_title("Renamed").with_scope("api");
+        let edit = Transition::between(&original, &renamed, "2025-02-01T00:00:00Z".to_string()).unwrap();
+        assert_eq!((edit.from_status.clone(), edit.to_status.clone()), (Status::Active, Status::Active));
+        let reworded = renamed.clone().with_title("Renamed again");
+        let second = Transition::between(&renamed, &reworded, "2025-03-01T00:00:00Z".to_string()).unwrap();
+        let history = vec![edit, second];
+
+        let before = snapshot("2025-01-15T00:00:00Z", history.clone()).decision(reworded.clone()).unwrap();
+        assert_eq!((before.title.as_str(), before.scope), ("Decision d-1", None));
+        let between = snapshot("2025-02-15T00:00:00Z", history.clone()).decision(reworded.clone()).unwrap();
+        assert_eq!((between.title.as_str(), between.scope.as_deref()), ("Renamed", Some("api")));
+        assert_eq!(between.updated_at, "2025-02-01T00:00:00Z");
+        let after = snapshot("2025-03-15T00:00:00Z", history).decision(reworded.clone()).unwrap();
+        assert_eq!(after, reworded);
+    }
+
+    #[test]
+    fn unchanged_decisions_need_no_transition() {
+        let d = Decision::test("d-1");
+        assert!(Transition::between(&d, &d, "2025-04-01T00:00:00Z".to_string()).is_none());
+    }
+
+    #[test]
+    fn resolves_timestamps_and_dates() {
+        let repo = Path::new(".");
+        assert_eq!(
+            resolve("2025-03-01T12:00:00+02:00", repo).unwrap().to_rfc3339(),
+            "2025-03-01T10:00:00+00:00"
+        );
+        assert_eq!(
+            resolve("2025-03-01", repo).unwrap().to_rfc3339(),
+            "2025-03-01T23:59:59+00:00"
+        );
+        assert!(resolve("--upload-pack=x", repo).is_err());
+    }
+
+    #[test]
+    fn continuation_tokens_resume_a_second_early() {
+        let repo = Path::new(".");
+        let now = resolve("2025-03-01T12:00:00.5Z", repo).unwrap();
+        let token = continuation_token(now);
+        assert_eq!(token, "ctx-1740830399");
+        assert_eq!(resolve_since(&token, repo).unwrap().to_rfc3339(), "2025-03-01T11:59:59+00:00");
+        assert!(resolve_since("ctx-soon", repo).is_err());
+    }
+
+    #[test]
+    fn since_a_date_means_from_the_start_of_it() {
+        let repo = Path::new(".");
+        let since = resolve_since("2025-03-01", repo).unwrap();
+        assert_eq!(since.to_rfc3339(), "2025-02-28T23:59:59+00:00");
+        // A decision created that day counts as added since it
+        assert!(!snapshot(&since.to_rfc3339(), Vec::new()).existed("d-2"));
+    }
+}
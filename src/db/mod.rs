@@ -1,3 +1,8 @@
+pub mod expr;
+pub mod history;
+pub mod oplog;
+pub mod search;
+pub mod sort;
 pub mod store;
 
 #[cfg(feature = "sqlite")]
@@ -6,26 +11,68 @@ pub mod sqlite;
 #[cfg(feature = "grafeo")]
 pub mod grafeo;
 
-pub use store::{ListFilter, Store};
-#[allow(unused_imports)]
-pub use store::Neighborhood;
+pub use search::{SearchHit, SearchQuery};
+pub use sort::Sort;
+pub use store::{ListFilter, Neighborhood, Store};
 
 use std::path::Path;
 
 use crate::config::Config;
 use crate::error::{DictumError, Result};
+use crate::model::{Link, LinkKind};
 
+/// Open the store, logging every change to the operation log. The database
+/// is rebuilt from the log first when it is missing (a fresh clone) or has
+/// not applied everything in it (after a pull or merge).
 pub fn open(dictum_dir: &Path) -> Result<Box<dyn Store>> {
     check_backend_marker(dictum_dir)?;
+    let database = dictum_dir.join(compiled_backend_files()[0]);
+    let ops = oplog::read(dictum_dir)?;
+    if !ops.is_empty() && (!database.exists() || oplog::is_stale(dictum_dir, &ops)?) {
+        let rebuilt = oplog::rebuild(dictum_dir)?;
+        if !rebuilt.skipped.is_empty() {
+            eprintln!(
+                "Warning: {} operation(s) in the log no longer apply; see `dictum rebuild`",
+                rebuilt.skipped.len()
+            );
+        }
+    }
+    Ok(Box::new(oplog::LoggedStore::new(open_cache(dictum_dir)?, dictum_dir)))
+}
+
+/// Open the database itself, without logging: for replaying the log into it.
+#[allow(clippy::needless_return)]
+pub fn open_cache(dictum_dir: &Path) -> Result<Box<dyn Store>> {
+    let config = check_backend_marker(dictum_dir)?;
 
     #[cfg(feature = "sqlite")]
-    { return Ok(Box::new(sqlite::SqliteStore::open(dictum_dir)?)); }
+    { return Ok(Box::new(sqlite::SqliteStore::open(dictum_dir)?.with_acyclic_kinds(config.acyclic_link_kinds))); }
 
     #[cfg(feature = "grafeo")]
-    { return Ok(Box::new(grafeo::GrafeoStore::open(dictum_dir)?)); }
+    { return Ok(Box::new(grafeo::GrafeoStore::open(dictum_dir)?.with_acyclic_kinds(config.acyclic_link_kinds))); }
+}
+
+/// Open the store, or a read-only view of it at `as_of` (see [`history::resolve`]).
+/// Git revisions are resolved in `repo`.
+pub fn open_as_of(dictum_dir: &Path, repo: &Path, as_of: Option<&str>) -> Result<Box<dyn Store>> {
+    let store = open(dictum_dir)?;
+    match as_of {
+        Some(spec) => {
+            let at = history::resolve(spec, repo)?;
+            Ok(Box::new(history::AsOfStore::new(store, at)?))
+        }
+        None => Ok(store),
+    }
 }
 
-fn check_backend_marker(dictum_dir: &Path) -> Result<()> {
+/// Open the Grafeo store directly, for commands that need more than the `Store` trait.
+#[cfg(feature = "grafeo")]
+pub fn open_grafeo(dictum_dir: &Path) -> Result<grafeo::GrafeoStore> {
+    let config = check_backend_marker(dictum_dir)?;
+    Ok(grafeo::GrafeoStore::open(dictum_dir)?.with_acyclic_kinds(config.acyclic_link_kinds))
+}
+
+fn check_backend_marker(dictum_dir: &Path) -> Result<Config> {
     let config = Config::load(dictum_dir)?;
     let expected = compiled_backend();
     if config.backend != expected {
@@ -34,6 +81,27 @@ fn check_backend_marker(dictum_dir: &Path) -> Result<()> {
             expected: expected.to_string(),
         });
     }
+    Ok(config)
+}
+
+/// Reject `link` if its kind is acyclic and its target already reaches its source.
+/// `edges` are the existing links of the same kind.
+pub fn check_link_cycle(
+    link: &Link,
+    acyclic_kinds: &[LinkKind],
+    edges: &[(String, String)],
+) -> Result<()> {
+    if !acyclic_kinds.contains(&link.kind) {
+        return Ok(());
+    }
+    if let Some(path) = crate::reasoning::shortest_path(edges, &link.target_id, &link.source_id) {
+        let mut cycle = vec![link.source_id.clone()];
+        cycle.extend(path);
+        return Err(DictumError::LinkCycle {
+            kind: link.kind.to_string(),
+            cycle: cycle.join(" -> "),
+        });
+    }
     Ok(())
 }
 
@@ -44,9 +112,77 @@ pub fn compiled_backend() -> &'static str {
     { "grafeo" }
 }
 
+/// The database files under `.dictum/`, main file first.
+pub fn compiled_backend_files() -> &'static [&'static str] {
+    #[cfg(feature = "sqlite")]
+    { &["dictum.db", "dictum.db-wal", "dictum.db-shm"] }
+    #[cfg(feature = "grafeo")]
+    { &["dictum.grafeo"] }
+}
+
 pub fn compiled_backend_gitignore() -> &'static str {
     #[cfg(feature = "sqlite")]
-    { "dictum.db\ndictum.db-wal\ndictum.db-shm\n" }
+    { "dictum.db\ndictum.db-wal\ndictum.db-shm\nsimilarity.json\nrebuild.tmp/\nops.applied\n" }
     #[cfg(feature = "grafeo")]
-    { "dictum.grafeo/\n" }
+    { "dictum.grafeo\nsimilarity.json\nrebuild.tmp/\nops.applied\n" }
+}
+
+/// Append the entries of [`compiled_backend_gitignore`] missing from
+/// `.dictum/.gitignore`, so repositories set up before an entry was added
+/// stop listing it as untracked. Other lines are left alone. Run by
+/// `rebuild` and `init --git`, never by commands that only read.
+pub fn update_gitignore(dictum_dir: &Path) -> Result<()> {
+    let path = dictum_dir.join(".gitignore");
+    let current = match std::fs::read_to_string(&path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(e.into()),
+    };
+    let present: Vec<&str> = current.lines().map(str::trim).collect();
+    let missing: Vec<&str> = compiled_backend_gitignore()
+        .lines()
+        .filter(|entry| !present.contains(entry))
+        .collect();
+    if missing.is_empty() {
+        return Ok(());
+    }
+    let mut updated = current.clone();
+    if !updated.is_empty() && !updated.ends_with('\n') {
+        updated.push('\n');
+    }
+    for entry in missing {
+        updated.push_str(entry);
+        updated.push('\n');
+    }
+    std::fs::write(path, updated)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::TestProject;
+
+    #[test]
+    fn an_old_gitignore_is_brought_up_to_date_only_when_asked() {
+        let project = TestProject::new();
+        let dir = project.dictum_dir();
+        let gitignore = dir.join(".gitignore");
+        let first = compiled_backend_gitignore().lines().next().unwrap();
+        let old = format!("{}\nnotes/", first);
+        std::fs::write(&gitignore, &old).unwrap();
+
+        // Reading leaves the committed file alone
+        drop(open(&dir).unwrap());
+        assert_eq!(std::fs::read_to_string(&gitignore).unwrap(), old);
+
+        update_gitignore(&dir).unwrap();
+        let text = std::fs::read_to_string(&gitignore).unwrap();
+        assert!(text.starts_with(&format!("{}\nnotes/\n", first)));
+        for entry in compiled_backend_gitignore().lines() {
+            assert_eq!(text.lines().filter(|l| *l == entry).count(), 1, "{}", entry);
+        }
+        update_gitignore(&dir).unwrap();
+        assert_eq!(std::fs::read_to_string(&gitignore).unwrap(), text);
+    }
 }
@@ -0,0 +1,803 @@
+//! The operation log: every mutation appended to `.dictum/ops.jsonl`, which
+//! is tracked in git. The database is a cache of it. Each op has a
+//! content-derived ID and names the ops it was made after (its causal
+//! parents), so logs from two branches can be concatenated, deduplicated and
+//! replayed in one deterministic order to reach the same state.
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use super::search::{SearchHit, SearchQuery};
+use super::store::{ListFilter, Neighborhood, Store};
+use crate::error::{DictumError, Result};
+use crate::model::{Decision, Link, LinkKind, Origin, Status, Transition};
+
+pub const LOG_FILE: &str = "ops.jsonl";
+
+/// The log heads the database reflects, one per line. It sits beside the
+/// database and is not tracked, so a log changed by a pull or merge shows up
+/// as heads the database has not applied.
+pub const APPLIED_FILE: &str = "ops.applied";
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Op {
+    pub id: String,
+    /// The heads of the log when this op was appended
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub parents: Vec<String>,
+    pub at: String,
+    #[serde(flatten)]
+    pub change: Change,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum Change {
+    Insert {
+        decision: Decision,
+    },
+    Status {
+        decision_id: String,
+        status: Status,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        superseded_by: Option<String>,
+    },
+    Reject {
+        decision_id: String,
+        origin: Origin,
+    },
+    Replace {
+        decision: Decision,
+    },
+    LabelAdd {
+        decision_id: String,
+        label: String,
+    },
+    LinkAdd {
+        link: Link,
+    },
+    LinkRemove {
+        source_id: String,
+        kind: LinkKind,
+        target_id: String,
+    },
+    Transition {
+        transition: Transition,
+    },
+}
+
+impl Op {
+    pub fn new(parents: Vec<String>, at: String, change: Change) -> Result<Self> {
+        let content = serde_json::to_string(&(&parents, &at, &change))?;
+        let digest = format!("{:x}", Sha256::digest(content.as_bytes()));
+        Ok(Op {
+            id: digest[..16].to_string(),
+            parents,
+            at,
+            change,
+        })
+    }
+}
+
+pub fn log_path(dictum_dir: &Path) -> PathBuf {
+    dictum_dir.join(LOG_FILE)
+}
+
+/// Every op in the log, first occurrence of each ID only, so a log that is
+/// the union of two branches reads cleanly.
+pub fn read(dictum_dir: &Path) -> Result<Vec<Op>> {
+    let path = log_path(dictum_dir);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let mut seen = HashSet::new();
+    let mut ops = Vec::new();
+    for (n, line) in std::fs::read_to_string(&path)?.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let op: Op = serde_json::from_str(line)
+            .map_err(|e| DictumError::InvalidOpLog(format!("line {}: {}", n + 1, e)))?;
+        if seen.insert(op.id.clone()) {
+            ops.push(op);
+        }
+    }
+    Ok(ops)
+}
+
+/// The heads recorded as applied to the database, if any were.
+pub fn applied(dictum_dir: &Path) -> Result<Option<Vec<String>>> {
+    let path = dictum_dir.join(APPLIED_FILE);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let mut heads: Vec<String> = std::fs::read_to_string(path)?
+        .lines()
+        .filter(|l| !l.trim().is_empty())
+        .map(|l| l.trim().to_string())
+        .collect();
+    heads.sort();
+    Ok(Some(heads))
+}
+
+fn record_applied(dictum_dir: &Path, heads: &[String]) -> Result<()> {
+    let lines: String = heads.iter().map(|h| format!("{}\n", h)).collect();
+    std::fs::write(dictum_dir.join(APPLIED_FILE), lines)?;
+    Ok(())
+}
+
+/// Whether the database lags the log: ops were added to the log (by a pull
+/// or a merge) that it has not applied.
+pub fn is_stale(dictum_dir: &Path, ops: &[Op]) -> Result<bool> {
+    Ok(!ops.is_empty() && applied(dictum_dir)?.as_deref() != Some(heads(ops).as_slice()))
+}
+
+/// Ops no other op names as a parent, sorted.
+pub fn heads(ops: &[Op]) -> Vec<String> {
+    let referenced: HashSet<&str> = ops
+        .iter()
+        .flat_map(|op| op.parents.iter().map(String::as_str))
+        .collect();
+    let mut heads: Vec<String> = ops
+        .iter()
+        .filter(|op| !referenced.contains(op.id.as_str()))
+        .map(|op| op.id.clone())
+        .collect();
+    heads.sort();
+    heads
+}
+
+/// Parents before children; among ops that are ready together, earliest
+/// `at` first, then by ID. Parents missing from the log are ignored.
+pub fn order(ops: Vec<Op>) -> Vec<Op> {
+    let ids: HashSet<String> = ops.iter().map(|op| op.id.clone()).collect();
+    let mut waiting: HashMap<String, usize> = HashMap::new();
+    let mut children: HashMap<String, Vec<String>> = HashMap::new();
+    for op in &ops {
+        let known: BTreeSet<&String> = op.parents.iter().filter(|p| ids.contains(*p)).collect();
+        waiting.insert(op.id.clone(), known.len());
+        for parent in known {
+            children.entry(parent.clone()).or_default().push(op.id.clone());
+        }
+    }
+
+    let mut by_id: HashMap<String, Op> = ops.into_iter().map(|op| (op.id.clone(), op)).collect();
+    let mut ready: BTreeSet<(String, String)> = by_id
+        .values()
+        .filter(|op| waiting[&op.id] == 0)
+        .map(|op| (op.at.clone(), op.id.clone()))
+        .collect();
+    let mut ordered = Vec::with_capacity(by_id.len());
+    while let Some(next) = ready.pop_first() {
+        let op = by_id.remove(&next.1).expect("ready ops are unvisited");
+        for child in children.get(&op.id).into_iter().flatten() {
+            let count = waiting.get_mut(child).expect("children are known ops");
+            *count -= 1;
+            if *count == 0 {
+                ready.insert((by_id[child].at.clone(), child.clone()));
+            }
+        }
+        ordered.push(op);
+    }
+    ordered
+}
+
+/// Ops recreating what `store` holds, for a store that predates the log.
+/// They depend only on the data, so two clones seeding the same state write
+/// the same ops.
+pub fn snapshot(store: &dyn Store) -> Result<Vec<Op>> {
+    let mut changes: Vec<(String, Change)> = Vec::new();
+    let mut links = Vec::new();
+    let mut seen_links = HashSet::new();
+    for d in store.decision_get_all()? {
+        for link in store.links_for_decision(&d.id)? {
+            if seen_links.insert((link.source_id.clone(), link.kind.to_string(), link.target_id.clone())) {
+                links.push(link);
+            }
+        }
+        changes.push((d.created_at.clone(), Change::Insert { decision: d }));
+    }
+    changes.extend(links.into_iter().map(|link| (link.created_at.clone(), Change::LinkAdd { link })));
+    changes.extend(
+        store
+            .transitions(None)?
+            .into_iter()
+            .map(|t| (t.at.clone(), Change::Transition { transition: t })),
+    );
+    changes.sort_by(|a, b| {
+        a.0.cmp(&b.0).then_with(|| {
+            let key = |c: &Change| serde_json::to_string(c).unwrap_or_default();
+            key(&a.1).cmp(&key(&b.1))
+        })
+    });
+
+    // Chained, so the first op made afterwards has one parent rather than all of them
+    let mut ops: Vec<Op> = Vec::with_capacity(changes.len());
+    for (at, change) in changes {
+        let parents = ops.last().map(|p| vec![p.id.clone()]).unwrap_or_default();
+        ops.push(Op::new(parents, at, change)?);
+    }
+    Ok(ops)
+}
+
+/// An op that could not be applied during a replay, with the reason.
+#[derive(Debug, Serialize)]
+pub struct Skipped {
+    pub op: String,
+    pub reason: String,
+}
+
+/// Apply `ops`, already in replay order, to an empty store. Ops that no
+/// longer apply (e.g. a link that would close a cycle once two branches are
+/// combined) are skipped the same way on every replay.
+pub fn replay(store: &mut dyn Store, ops: &[Op]) -> Result<Vec<Skipped>> {
+    let mut skipped = Vec::new();
+    for op in ops {
+        match apply(store, op) {
+            Ok(()) => {}
+            Err(
+                e @ (DictumError::DecisionNotFound(_)
+                | DictumError::DecisionAlreadyExists
+                | DictumError::LinkAlreadyExists
+                | DictumError::LinkNotFound
+                | DictumError::SelfLink
+                | DictumError::LinkCycle { .. }),
+            ) => skipped.push(Skipped {
+                op: op.id.clone(),
+                reason: e.to_string(),
+            }),
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(skipped)
+}
+
+fn apply(store: &mut dyn Store, op: &Op) -> Result<()> {
+    match &op.change {
+        Change::Insert { decision } => {
+            store.decision_insert(decision)?;
+            for label in &decision.labels {
+                store.label_add(&decision.id, label)?;
+            }
+        }
+        // Written directly rather than through decision_update_status, so the
+        // timestamps are the op's and not the time of the replay
+        Change::Status {
+            decision_id,
+            status,
+            superseded_by,
+        } => {
+            let old = store.decision_get(decision_id)?;
+            store.decision_replace(&Decision {
+                status: status.clone(),
+                superseded_by: superseded_by.clone(),
+                updated_at: op.at.clone(),
+                ..old.clone()
+            })?;
+            store.transition_insert(&Transition {
+                decision_id: decision_id.clone(),
+                from_status: old.status,
+                to_status: status.clone(),
+                from_superseded_by: old.superseded_by,
+                from_fields: Default::default(),
+                at: op.at.clone(),
+            })?;
+        }
+        Change::Reject { decision_id, origin } => {
+            let old = store.decision_get(decision_id)?;
+            store.decision_replace(&Decision {
+                status: Status::Rejected,
+                origin: Some(origin.clone()),
+                updated_at: op.at.clone(),
+                ..old.clone()
+            })?;
+            store.transition_insert(&Transition {
+                decision_id: decision_id.clone(),
+                from_status: old.status,
+                to_status: Status::Rejected,
+                from_superseded_by: old.superseded_by,
+                from_fields: Default::default(),
+                at: op.at.clone(),
+            })?;
+        }
+        Change::Replace { decision } => store.decision_replace(decision)?,
+        Change::LabelAdd { decision_id, label } => {
+            store.decision_get(decision_id)?;
+            store.label_add(decision_id, label)?;
+        }
+        Change::LinkAdd { link } => store.link_insert(link)?,
+        Change::LinkRemove {
+            source_id,
+            kind,
+            target_id,
+        } => store.link_delete(source_id, kind, target_id)?,
+        Change::Transition { transition } => store.transition_insert(transition)?,
+    }
+    Ok(())
+}
+
+/// Everything the log can express about a store, compared before and after a
+/// write made around the `Store` trait (`gql --write`) to log what it did.
+#[cfg(feature = "grafeo")]
+pub struct Contents {
+    decisions: std::collections::BTreeMap<String, Decision>,
+    links: std::collections::BTreeMap<(String, String, String), Link>,
+    transitions: Vec<Transition>,
+}
+
+#[cfg(feature = "grafeo")]
+impl Contents {
+    pub fn of(store: &dyn Store) -> Result<Self> {
+        let mut contents = Contents {
+            decisions: Default::default(),
+            links: Default::default(),
+            transitions: store.transitions(None)?,
+        };
+        for d in store.decision_get_all()? {
+            for link in store.links_for_decision(&d.id)? {
+                let key = (link.source_id.clone(), link.kind.to_string(), link.target_id.clone());
+                contents.links.insert(key, link);
+            }
+            contents.decisions.insert(d.id.clone(), d);
+        }
+        Ok(contents)
+    }
+
+    /// The changes that turn `self` into `after`, stamped `at`. `Err` names
+    /// the first change the log has no op for.
+    pub fn changes_to(&self, after: &Contents, at: &str) -> std::result::Result<Vec<Change>, String> {
+        if let Some(id) = self.decisions.keys().find(|id| !after.decisions.contains_key(*id)) {
+            return Err(format!("decision {} removed", id));
+        }
+        if let Some(t) = self.transitions.iter().find(|t| !after.transitions.contains(t)) {
+            return Err(format!("a transition of {} removed", t.decision_id));
+        }
+
+        let mut changes = Vec::new();
+        let mut labels = Vec::new();
+        for (id, new) in &after.decisions {
+            let Some(old) = self.decisions.get(id) else {
+                changes.push(Change::Insert { decision: new.clone() });
+                continue;
+            };
+            if let Some(label) = old.labels.iter().find(|l| !new.labels.contains(l)) {
+                return Err(format!("label {} removed from {}", label, id));
+            }
+            labels.extend(new.labels.iter().filter(|l| !old.labels.contains(l)).map(|label| Change::LabelAdd {
+                decision_id: id.clone(),
+                label: label.clone(),
+            }));
+            let unlabeled = |d: &Decision| Decision {
+                labels: Vec::new(),
+                ..d.clone()
+            };
+            if unlabeled(old) != unlabeled(new) {
+                changes.push(Change::Replace {
+                    decision: unlabeled(new),
+                });
+                if let Some(transition) = Transition::between(old, new, at.to_string()) {
+                    changes.push(Change::Transition { transition });
+                }
+            }
+        }
+        changes.extend(labels);
+        changes.extend(
+            after
+                .transitions
+                .iter()
+                .filter(|t| !self.transitions.contains(t))
+                .map(|t| Change::Transition { transition: t.clone() }),
+        );
+        for (key, link) in &self.links {
+            if after.links.get(key) != Some(link) {
+                changes.push(Change::LinkRemove {
+                    source_id: link.source_id.clone(),
+                    kind: link.kind.clone(),
+                    target_id: link.target_id.clone(),
+                });
+            }
+        }
+        for (key, link) in &after.links {
+            if self.links.get(key) != Some(link) {
+                changes.push(Change::LinkAdd { link: link.clone() });
+            }
+        }
+        Ok(changes)
+    }
+}
+
+/// The log's heads, writing `store` into the log as it stands first when
+/// there is no log yet.
+pub fn seed(dictum_dir: &Path, store: &dyn Store) -> Result<Vec<String>> {
+    let path = log_path(dictum_dir);
+    if path.exists() {
+        return Ok(heads(&read(dictum_dir)?));
+    }
+    let seed = snapshot(store)?;
+    write_ops(&path, &seed)?;
+    record_applied(dictum_dir, &heads(&seed))?;
+    Ok(heads(&seed))
+}
+
+/// Log changes made to the database directly, after the ops already logged.
+#[cfg(feature = "grafeo")]
+pub fn append(dictum_dir: &Path, at: &str, changes: Vec<Change>) -> Result<()> {
+    let mut parents = heads(&read(dictum_dir)?);
+    let mut ops = Vec::with_capacity(changes.len());
+    for change in changes {
+        let op = Op::new(parents, at.to_string(), change)?;
+        parents = vec![op.id.clone()];
+        ops.push(op);
+    }
+    write_ops(&log_path(dictum_dir), &ops)?;
+    record_applied(dictum_dir, &parents)
+}
+
+fn write_ops(path: &Path, ops: &[Op]) -> Result<()> {
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    let mut lines = String::new();
+    for op in ops {
+        lines.push_str(&serde_json::to_string(op)?);
+        lines.push('\n');
+    }
+    file.write_all(lines.as_bytes())?;
+    Ok(())
+}
+
+/// A store that appends each successful mutation to the operation log.
+pub struct LoggedStore {
+    inner: Box<dyn Store>,
+    dictum_dir: PathBuf,
+    path: PathBuf,
+    /// Current heads, read from the log on the first append
+    heads: Option<Vec<String>>,
+}
+
+impl LoggedStore {
+    pub fn new(inner: Box<dyn Store>, dictum_dir: &Path) -> Self {
+        LoggedStore {
+            inner,
+            dictum_dir: dictum_dir.to_path_buf(),
+            path: log_path(dictum_dir),
+            heads: None,
+        }
+    }
+
+    /// Seed a missing log from the store's current contents. Runs before the
+    /// mutation it precedes, so the seed does not already contain it.
+    fn ensure_log(&mut self) -> Result<()> {
+        if self.heads.is_some() {
+            return Ok(());
+        }
+        self.heads = Some(seed(&self.dictum_dir, &*self.inner)?);
+        Ok(())
+    }
+
+    fn append(&mut self, at: String, change: Change) -> Result<()> {
+        let parents = self.heads.take().unwrap_or_default();
+        let op = Op::new(parents, at, change)?;
+        self.write(std::slice::from_ref(&op))?;
+        record_applied(&self.dictum_dir, std::slice::from_ref(&op.id))?;
+        self.heads = Some(vec![op.id]);
+        Ok(())
+    }
+
+    fn write(&self, ops: &[Op]) -> Result<()> {
+        write_ops(&self.path, ops)
+    }
+}
+
+pub fn now() -> String {
+    chrono::Utc::now().to_rfc3339()
+}
+
+impl Store for LoggedStore {
+    fn decision_insert(&mut self, decision: &Decision) -> Result<()> {
+        self.ensure_log()?;
+        self.inner.decision_insert(decision)?;
+        self.append(
+            now(),
+            Change::Insert {
+                decision: decision.clone(),
+            },
+        )
+    }
+
+    fn decision_get(&self, id: &str) -> Result<Decision> {
+        self.inner.decision_get(id)
+    }
+
+    fn decision_list(&self, filter: &ListFilter) -> Result<Vec<Decision>> {
+        self.inner.decision_list(filter)
+    }
+
+    fn decision_update_status(
+        &mut self,
+        id: &str,
+        status: &Status,
+        superseded_by: Option<&str>,
+    ) -> Result<()> {
+        self.ensure_log()?;
+        self.inner.decision_update_status(id, status, superseded_by)?;
+        // The op takes the stored timestamp, so a replay reproduces it exactly
+        let at = self.inner.decision_get(id)?.updated_at;
+        self.append(
+            at,
+            Change::Status {
+                decision_id: id.to_string(),
+                status: status.clone(),
+                superseded_by: superseded_by.map(str::to_string),
+            },
+        )
+    }
+
+    fn decision_reject(&mut self, id: &str, origin: &Origin) -> Result<()> {
+        self.ensure_log()?;
+        self.inner.decision_reject(id, origin)?;
+        let at = self.inner.decision_get(id)?.updated_at;
+        self.append(
+            at,
+            Change::Reject {
+                decision_id: id.to_string(),
+                origin: origin.clone(),
+            },
+        )
+    }
+
+    fn decision_replace(&mut self, decision: &Decision) -> Result<()> {
+        self.ensure_log()?;
+        self.inner.decision_replace(decision)?;
+        self.append(
+            now(),
+            Change::Replace {
+                decision: decision.clone(),
+            },
+        )
+    }
+
+    fn decision_search(&self, query: &SearchQuery) -> Result<Vec<SearchHit>> {
+        self.inner.decision_search(query)
+    }
+
+    fn label_add(&mut self, decision_id: &str, label: &str) -> Result<()> {
+        self.ensure_log()?;
+        self.inner.label_add(decision_id, label)?;
+        self.append(
+            now(),
+            Change::LabelAdd {
+                decision_id: decision_id.to_string(),
+                label: label.to_string(),
+            },
+        )
+    }
+
+    fn transition_insert(&mut self, transition: &Transition) -> Result<()> {
+        self.ensure_log()?;
+        self.inner.transition_insert(transition)?;
+        self.append(
+            now(),
+            Change::Transition {
+                transition: transition.clone(),
+            },
+        )
+    }
+
+    fn transitions(&self, decision_id: Option<&str>) -> Result<Vec<Transition>> {
+        self.inner.transitions(decision_id)
+    }
+
+    fn link_insert(&mut self, link: &Link) -> Result<()> {
+        self.ensure_log()?;
+        self.inner.link_insert(link)?;
+        self.append(now(), Change::LinkAdd { link: link.clone() })
+    }
+
+    fn link_delete(&mut self, source_id: &str, kind: &LinkKind, target_id: &str) -> Result<()> {
+        self.ensure_log()?;
+        self.inner.link_delete(source_id, kind, target_id)?;
+        self.append(
+            now(),
+            Change::LinkRemove {
+                source_id: source_id.to_string(),
+                kind: kind.clone(),
+                target_id: target_id.to_string(),
+            },
+        )
+    }
+
+    fn links_for_decision(&self, decision_id: &str) -> Result<Vec<Link>> {
+        self.inner.links_for_decision(decision_id)
+    }
+
+    fn links_of_kind(&self, kind: &LinkKind) -> Result<Vec<(String, String)>> {
+        self.inner.links_of_kind(kind)
+    }
+
+    fn neighborhood(&self, id: &str, depth: u32) -> Result<Neighborhood> {
+        self.inner.neighborhood(id, depth)
+    }
+
+    fn reachable(&self, id: &str, kinds: &[LinkKind]) -> Result<Vec<String>> {
+        self.inner.reachable(id, kinds)
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct Rebuilt {
+    pub ops: usize,
+    pub decisions: usize,
+    pub skipped: Vec<Skipped>,
+}
+
+/// Replace the database with a replay of the log. The new database is built
+/// beside the old one and only swapped in once the replay has succeeded.
+pub fn rebuild(dictum_dir: &Path) -> Result<Rebuilt> {
+    let ops = order(read(dictum_dir)?);
+    let applied = heads(&ops);
+
+    let staging = dictum_dir.join("rebuild.tmp");
+    if staging.exists() {
+        std::fs::remove_dir_all(&staging)?;
+    }
+    std::fs::create_dir_all(&staging)?;
+    std::fs::copy(dictum_dir.join("config.toml"), staging.join("config.toml"))?;
+
+    let (skipped, decisions) = {
+        let mut store = super::open_cache(&staging)?;
+        let skipped = replay(&mut *store, &ops)?;
+        (skipped, store.decision_get_all()?.len())
+    };
+
+    for name in super::compiled_backend_files() {
+        let (old, new) = (dictum_dir.join(name), staging.join(name));
+        if old.is_dir() {
+            std::fs::remove_dir_all(&old)?;
+        } else if old.exists() {
+            std::fs::remove_file(&old)?;
+        }
+        if new.exists() {
+            std::fs::rename(&new, &old)?;
+        }
+    }
+    std::fs::remove_dir_all(&staging)?;
+    record_applied(dictum_dir, &applied)?;
+
+    Ok(Rebuilt {
+        ops: ops.len(),
+        decisions,
+        skipped,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::TestProject;
+
+    /// A base op, two branches off it and the op that merges them.
+    fn branches() -> [Op; 4] {
+        let base = Op::new(Vec::new(), "2025-01-01T00:00:00Z".to_string(), Change::Insert { decision: Decision::test("d-1") }).unwrap();
+        let ours = Op::new(
+            vec![base.id.clone()],
+            "2025-01-03T00:00:00Z".to_string(),
+            Change::Status {
+                decision_id: "d-1".to_string(),
+                status: Status::Deprecated,
+                superseded_by: None,
+            },
+        )
+        .unwrap();
+        let theirs = Op::new(vec![base.id.clone()], "2025-01-02T00:00:00Z".to_string(), Change::Insert { decision: Decision::test("d-2") }).unwrap();
+        let merge = Op::new(
+            vec![ours.id.clone(), theirs.id.clone()],
+            "2025-01-04T00:00:00Z".to_string(),
+            Change::LabelAdd {
+                decision_id: "d-2".to_string(),
+                label: "api".to_string(),
+            },
+        )
+        .unwrap();
+        [base, ours, theirs, merge]
+    }
+
+    #[test]
+    fn branches_union_into_one_replay_order() {
+        let [base, ours, theirs, merge] = branches();
+        // Either branch's lines first, as a union merge of the log leaves them
+        let ids = |ops: Vec<Op>| order(ops).into_iter().map(|op| op.id).collect::<Vec<_>>();
+        let a = ids(vec![base.clone(), ours.clone(), theirs.clone(), merge.clone()]);
+        let b = ids(vec![merge.clone(), theirs.clone(), base.clone(), ours.clone()]);
+        assert_eq!(a, b);
+        assert_eq!(a, [base.id.as_str(), theirs.id.as_str(), ours.id.as_str(), merge.id.as_str()]);
+    }
+
+    #[test]
+    fn heads_are_the_ops_nothing_follows() {
+        let [base, ours, theirs, merge] = branches();
+        let mut branch_heads = vec![ours.id.clone(), theirs.id.clone()];
+        branch_heads.sort();
+        assert_eq!(heads(&[base.clone(), ours.clone(), theirs.clone()]), branch_heads);
+        assert_eq!(heads(&[base, ours, theirs, merge.clone()]), [merge.id]);
+    }
+
+    #[test]
+    fn ops_round_trip_through_json_with_the_op_name_inline() {
+        let [_, ours, _, _] = branches();
+        let line = serde_json::to_string(&ours).unwrap();
+        assert!(line.contains(r#""op":"status""#));
+        assert_eq!(serde_json::from_str::<Op>(&line).unwrap(), ours);
+    }
+
+    #[test]
+    fn reopening_replays_ops_the_database_has_not_applied() {
+        let project = TestProject::new();
+        let dir = project.dictum_dir();
+
+        let mut store = crate::db::open(&dir).unwrap();
+        store.decision_insert(&Decision::test("d-1")).unwrap();
+        drop(store);
+        let ops = read(&dir).unwrap();
+        assert!(!is_stale(&dir, &ops).unwrap());
+
+        // Another clone's op arrives in the log, as a pull or union merge leaves it
+        let theirs = Op::new(heads(&ops), "2025-01-02T00:00:00Z".to_string(), Change::Insert { decision: Decision::test("d-2") }).unwrap();
+        let mut log = std::fs::read_to_string(log_path(&dir)).unwrap();
+        log.push_str(&format!("{}\n", serde_json::to_string(&theirs).unwrap()));
+        std::fs::write(log_path(&dir), log).unwrap();
+        assert!(is_stale(&dir, &read(&dir).unwrap()).unwrap());
+
+        let store = crate::db::open(&dir).unwrap();
+        assert_eq!(store.decision_get_all().unwrap().len(), 2);
+        assert_eq!(applied(&dir).unwrap(), Some(vec![theirs.id]));
+    }
+
+    #[cfg(feature = "grafeo")]
+    #[test]
+    fn direct_writes_are_logged_or_refused() {
+        let project = TestProject::new();
+        let dir = project.dictum_dir();
+
+        let mut store = crate::db::open(&dir).unwrap();
+        store.decision_insert(&Decision::test("d-1")).unwrap();
+        store.decision_insert(&Decision::test("d-2")).unwrap();
+        drop(store);
+
+        // Written around the log, as `gql --write` does
+        let mut cache = crate::db::open_cache(&dir).unwrap();
+        let before = Contents::of(&*cache).unwrap();
+        let renamed = Decision::test("d-1").with_title("Renamed");
+        cache.decision_replace(&renamed).unwrap();
+        cache.label_add("d-1", "api").unwrap();
+        cache
+            .link_insert(&Link {
+                source_id: "d-2".to_string(),
+                target_id: "d-1".to_string(),
+                kind: LinkKind::Requires,
+                created_at: "2025-01-02T00:00:00Z".to_string(),
+                reason: None,
+            })
+            .unwrap();
+        let mut after = Contents::of(&*cache).unwrap();
+        drop(cache);
+
+        let changes = before.changes_to(&after, "2025-01-02T00:00:00Z").unwrap();
+        append(&dir, "2025-01-02T00:00:00Z", changes).unwrap();
+        rebuild(&dir).unwrap();
+        let store = crate::db::open(&dir).unwrap();
+        let d = store.decision_get("d-1").unwrap();
+        assert_eq!((d.title.as_str(), d.labels.clone()), ("Renamed", vec!["api".to_string()]));
+        assert_eq!(store.links_for_decision("d-2").unwrap().len(), 1);
+        assert_eq!(store.transitions(Some("d-1")).unwrap()[0].from_fields["title"], "Decision d-1");
+        drop(store);
+
+        // The log has no op for a deletion
+        after.decisions.remove("d-2");
+        let err = Contents::of(&*crate::db::open_cache(&dir).unwrap()).unwrap().changes_to(&after, "2025-01-03T00:00:00Z");
+        assert_eq!(err.unwrap_err(), "decision d-2 removed");
+    }
+}
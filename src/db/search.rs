@@ -0,0 +1,394 @@
+//! Full-text search queries for `decision query`.
+//!
+//! Syntax: bare words, `"exact phrases"`, `prefix*`, each optionally scoped to a
+//! field (`title:cache`, `rebuttal:"high latency"`). Every term must match.
+//! Backends rank candidates (FTS5 `bm25()` for SQLite, `text_search` for Grafeo);
+//! [`SearchQuery::evaluate`] decides the matched field and builds the snippet.
+
+use std::fmt;
+
+use crate::error::{DictumError, Result};
+use crate::model::{Decision, Status};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SearchField {
+    Title,
+    Body,
+    Rebuttal,
+    Scope,
+}
+
+impl SearchField {
+    pub const ALL: [SearchField; 4] = [
+        SearchField::Title,
+        SearchField::Body,
+        SearchField::Rebuttal,
+        SearchField::Scope,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            SearchField::Title => "title",
+            SearchField::Body => "body",
+            SearchField::Rebuttal => "rebuttal",
+            SearchField::Scope => "scope",
+        }
+    }
+
+    fn parse(s: &str) -> Option<SearchField> {
+        SearchField::ALL
+            .into_iter()
+            .find(|f| f.name().eq_ignore_ascii_case(s))
+    }
+
+    pub fn text<'a>(&self, decision: &'a Decision) -> Option<&'a str> {
+        match self {
+            SearchField::Title => Some(decision.title.as_str()),
+            SearchField::Body => decision.body.as_deref(),
+            SearchField::Rebuttal => decision.rebuttal.as_deref(),
+            SearchField::Scope => decision.scope.as_deref(),
+        }
+    }
+}
+
+impl fmt::Display for SearchField {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// One query term: a single word or a phrase, lowercased and tokenized.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Term {
+    pub field: Option<SearchField>,
+    pub words: Vec<String>,
+    /// The last word matches as a prefix (`cach*`).
+    pub prefix: bool,
+}
+
+impl Term {
+    pub fn applies_to(&self, field: SearchField) -> bool {
+        self.field.is_none() || self.field == Some(field)
+    }
+
+    /// Start positions in `tokens` where this term matches.
+    fn positions(&self, tokens: &[Token]) -> Vec<usize> {
+        let n = self.words.len();
+        if tokens.len() < n {
+            return Vec::new();
+        }
+        (0..=tokens.len() - n)
+            .filter(|&start| {
+                self.words.iter().enumerate().all(|(i, word)| {
+                    let token = &tokens[start + i].text;
+                    if self.prefix && i == n - 1 {
+                        token.starts_with(word.as_str())
+                    } else {
+                        token == word
+                    }
+                })
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SearchQuery {
+    pub terms: Vec<Term>,
+    pub status: Option<Status>,
+}
+
+#[derive(Debug, Clone)]
+pub struct SearchHit {
+    pub decision: Decision,
+    pub score: f64,
+    pub field: SearchField,
+    pub snippet: String,
+}
+
+/// Where a decision matched, as computed by [`SearchQuery::evaluate`].
+pub struct Match {
+    pub field: SearchField,
+    pub snippet: String,
+}
+
+impl SearchQuery {
+    pub fn parse(input: &str) -> Result<SearchQuery> {
+        let chars: Vec<char> = input.chars().collect();
+        let mut terms = Vec::new();
+        let mut i = 0;
+
+        while i < chars.len() {
+            if chars[i].is_whitespace() {
+                i += 1;
+                continue;
+            }
+
+            // Optional `field:` scope; any other `word:` is searched as text
+            let mut field = None;
+            let word_end = (i..chars.len())
+                .find(|&j| !chars[j].is_alphabetic())
+                .unwrap_or(chars.len());
+            if word_end > i && chars.get(word_end) == Some(&':') {
+                let name: String = chars[i..word_end].iter().collect();
+                field = SearchField::parse(&name);
+                if field.is_some() {
+                    i = word_end + 1;
+                }
+            }
+
+            let raw: String = if chars.get(i) == Some(&'"') {
+                let end = (i + 1..chars.len())
+                    .find(|&j| chars[j] == '"')
+                    .ok_or_else(|| DictumError::InvalidQuery("unterminated phrase".to_string()))?;
+                let phrase = chars[i + 1..end].iter().collect();
+                i = end + 1;
+                phrase
+            } else {
+                let start = i;
+                while i < chars.len() && !chars[i].is_whitespace() {
+                    i += 1;
+                }
+                chars[start..i].iter().collect()
+            };
+            let mut prefix = raw.ends_with('*');
+            if chars.get(i) == Some(&'*') {
+                prefix = true;
+                i += 1;
+            }
+
+            let words: Vec<String> = tokenize(&raw).into_iter().map(|t| t.text).collect();
+            if !words.is_empty() {
+                terms.push(Term {
+                    field,
+                    words,
+                    prefix,
+                });
+            }
+        }
+
+        if terms.is_empty() {
+            return Err(DictumError::InvalidQuery("empty query".to_string()));
+        }
+        Ok(SearchQuery {
+            terms,
+            status: None,
+        })
+    }
+
+    /// Check every term against `decision`. Returns the field with the most matching
+    /// terms (earlier fields win ties) and a highlighted snippet from it.
+    pub fn evaluate(&self, decision: &Decision) -> Option<Match> {
+        let fields: Vec<(SearchField, &str, Vec<Token>)> = SearchField::ALL
+            .into_iter()
+            .filter_map(|f| f.text(decision).map(|text| (f, text, tokenize(text))))
+            .collect();
+
+        let matches_in = |term: &Term, field: SearchField, tokens: &[Token]| {
+            term.applies_to(field) && !term.positions(tokens).is_empty()
+        };
+        let all_match = self
+            .terms
+            .iter()
+            .all(|term| fields.iter().any(|(f, _, tokens)| matches_in(term, *f, tokens)));
+        if !all_match {
+            return None;
+        }
+
+        let mut best: Option<(usize, SearchField, &str, &[Token])> = None;
+        for (field, text, tokens) in &fields {
+            let count = self
+                .terms
+                .iter()
+                .filter(|term| matches_in(term, *field, tokens))
+                .count();
+            if count > best.map_or(0, |b| b.0) {
+                best = Some((count, *field, text, tokens));
+            }
+        }
+        let (_, field, text, tokens) = best?;
+        Some(Match {
+            field,
+            snippet: self.snippet(field, text, tokens),
+        })
+    }
+
+    /// Words to look up in a per-field text index. Prefix terms are expanded to the
+    /// matching words found in `vocabulary`.
+    #[cfg(feature = "grafeo")]
+    pub fn index_words(&self, field: SearchField, vocabulary: &[&str]) -> Vec<String> {
+        let mut words = Vec::new();
+        for term in self.terms.iter().filter(|t| t.applies_to(field)) {
+            let (last, rest) = term.words.split_last().expect("terms are never empty");
+            words.extend(rest.iter().cloned());
+            if term.prefix {
+                for text in vocabulary {
+                    for token in tokenize(text) {
+                        if token.text.starts_with(last.as_str()) && !words.contains(&token.text) {
+                            words.push(token.text);
+                        }
+                    }
+                }
+            } else {
+                words.push(last.clone());
+            }
+        }
+        words
+    }
+
+    fn snippet(&self, field: SearchField, text: &str, tokens: &[Token]) -> String {
+        const BEFORE: usize = 4;
+        const WINDOW: usize = 16;
+
+        let mut highlighted = vec![false; tokens.len()];
+        for term in self.terms.iter().filter(|t| t.applies_to(field)) {
+            for start in term.positions(tokens) {
+                for h in &mut highlighted[start..start + term.words.len()] {
+                    *h = true;
+                }
+            }
+        }
+
+        let first = highlighted.iter().position(|&h| h).unwrap_or(0);
+        let start = first.saturating_sub(BEFORE);
+        let end = (start + WINDOW).min(tokens.len());
+
+        let mut out = String::new();
+        let mut pos = if start == 0 { 0 } else { tokens[start].start };
+        if start > 0 {
+            out.push('…');
+        }
+        // Adjacent highlighted tokens (phrases) share one pair of markers
+        for i in start..end {
+            let token = &tokens[i];
+            out.push_str(&text[pos..token.start]);
+            if highlighted[i] && (i == start || !highlighted[i - 1]) {
+                out.push_str("**");
+            }
+            out.push_str(&text[token.start..token.end]);
+            if highlighted[i] && (i + 1 == end || !highlighted[i + 1]) {
+                out.push_str("**");
+            }
+            pos = token.end;
+        }
+        if end < tokens.len() {
+            out.push('…');
+        } else {
+            out.push_str(&text[pos..]);
+        }
+        out.split_whitespace().collect::<Vec<_>>().join(" ")
+    }
+}
+
+/// Rank candidates that a backend has already scored: drop those that do not
+/// satisfy every term, then sort by descending score (stable, so ties keep the
+/// backend's order).
+pub fn rank(query: &SearchQuery, scored: Vec<(Decision, f64)>) -> Vec<SearchHit> {
+    let mut hits: Vec<SearchHit> = scored
+        .into_iter()
+        .filter_map(|(decision, score)| {
+            let m = query.evaluate(&decision)?;
+            Some(SearchHit {
+                decision,
+                score,
+                field: m.field,
+                snippet: m.snippet,
+            })
+        })
+        .collect();
+    hits.sort_by(|a, b| b.score.total_cmp(&a.score));
+    hits
+}
+
+struct Token {
+    start: usize,
+    end: usize,
+    text: String,
+}
+
+/// Split on non-alphanumeric characters and lowercase, like FTS5's `unicode61`.
+fn tokenize(text: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut start = None;
+    for (i, c) in text.char_indices() {
+        match (c.is_alphanumeric(), start) {
+            (true, None) => start = Some(i),
+            (false, Some(s)) => {
+                tokens.push(Token {
+                    start: s,
+                    end: i,
+                    text: text[s..i].to_lowercase(),
+                });
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(s) = start {
+        tokens.push(Token {
+            start: s,
+            end: text.len(),
+            text: text[s..].to_lowercase(),
+        });
+    }
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decision() -> Decision {
+        Decision::test("d-1")
+            .with_title("Use a read-through cache")
+            .with_body("Cache user sessions in Redis.")
+            .with_rebuttal("unless latency budget is exceeded")
+    }
+
+    #[test]
+    fn parses_fields_phrases_and_prefixes() {
+        let q = SearchQuery::parse(r#"title:cache "read through" rebuttal:laten*"#).unwrap();
+        assert_eq!(q.terms.len(), 3);
+        assert_eq!(q.terms[0].field, Some(SearchField::Title));
+        assert_eq!(q.terms[1].words, vec!["read", "through"]);
+        assert!(q.terms[2].prefix);
+        assert_eq!(q.terms[2].field, Some(SearchField::Rebuttal));
+    }
+
+    #[test]
+    fn rejects_open_quotes_and_blank_queries() {
+        assert!(SearchQuery::parse("\"open").is_err());
+        assert!(SearchQuery::parse("  ").is_err());
+    }
+
+    #[test]
+    fn other_words_before_a_colon_are_searched_as_text() {
+        let q = SearchQuery::parse("redis:6379 http://example.com").unwrap();
+        assert_eq!(q.terms.len(), 2);
+        assert_eq!((q.terms[0].field, &q.terms[0].words), (None, &vec!["redis".to_string(), "6379".to_string()]));
+        assert_eq!(q.terms[1].field, None);
+        let d = decision().with_body("Point sessions at redis:6379.");
+        assert_eq!(SearchQuery::parse("redis:6379").unwrap().evaluate(&d).unwrap().field, SearchField::Body);
+    }
+
+    #[test]
+    fn evaluate_requires_every_term() {
+        let m = SearchQuery::parse("cache redis").unwrap().evaluate(&decision()).unwrap();
+        assert_eq!(m.field, SearchField::Body);
+        assert_eq!(m.snippet, "**Cache** user sessions in **Redis**.");
+        assert!(SearchQuery::parse("title:redis").unwrap().evaluate(&decision()).is_none());
+    }
+
+    #[test]
+    fn prefixes_match_within_their_field() {
+        let m = SearchQuery::parse("rebuttal:laten*").unwrap().evaluate(&decision()).unwrap();
+        assert_eq!(m.field, SearchField::Rebuttal);
+        assert_eq!(m.snippet, "unless **latency** budget is exceeded");
+    }
+
+    #[test]
+    fn phrases_match_in_order() {
+        assert!(SearchQuery::parse("\"through read\"").unwrap().evaluate(&decision()).is_none());
+        assert!(SearchQuery::parse("\"read through\"").unwrap().evaluate(&decision()).is_some());
+    }
+}
@@ -0,0 +1,209 @@
+//! `--sort field[:asc|desc]` for decision listings. Backends translate a
+//! [`Sort`] into their own ORDER BY; levels and weights order by rank
+//! (strategic before operational, must before may), everything else by value.
+
+use std::cmp::Ordering;
+use std::str::FromStr;
+
+use crate::error::DictumError;
+use crate::model::Decision;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SortField {
+    Id,
+    Title,
+    Level,
+    Status,
+    Kind,
+    Weight,
+    Scope,
+    Author,
+    Created,
+    Updated,
+}
+
+impl SortField {
+    pub fn column(&self) -> &'static str {
+        match self {
+            SortField::Id => "id",
+            SortField::Title => "title",
+            SortField::Level => "level",
+            SortField::Status => "status",
+            SortField::Kind => "kind",
+            SortField::Weight => "weight",
+            SortField::Scope => "scope",
+            SortField::Author => "author",
+            SortField::Created => "created_at",
+            SortField::Updated => "updated_at",
+        }
+    }
+
+    /// Stored values in rank order, for fields that do not sort alphabetically.
+    pub fn ranks(&self) -> Option<&'static [&'static str]> {
+        match self {
+            SortField::Level => Some(&["strategic", "tactical", "operational"]),
+            SortField::Weight => Some(&["must", "should", "may"]),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Sort {
+    pub field: SortField,
+    pub descending: bool,
+}
+
+impl Sort {
+    /// The listing order when no `--sort` is given: newest first.
+    pub const DEFAULT: Sort = Sort {
+        field: SortField::Created,
+        descending: true,
+    };
+
+    pub fn direction(&self) -> &'static str {
+        if self.descending {
+            "DESC"
+        } else {
+            "ASC"
+        }
+    }
+
+    /// Build an ORDER BY clause body for `alias` (e.g. `d`). Ties break on
+    /// `id` so that `--limit/--offset` pages are stable. Unscoped decisions
+    /// sort as an empty scope, first in ascending order, on every backend.
+    pub fn order_by(&self, alias: &str) -> String {
+        let column = match self.field {
+            SortField::Scope => format!("coalesce({}.scope, '')", alias),
+            _ => format!("{}.{}", alias, self.field.column()),
+        };
+        let key = match self.field.ranks() {
+            Some(ranks) => {
+                let arms: Vec<String> = ranks
+                    .iter()
+                    .enumerate()
+                    .map(|(i, v)| format!("WHEN '{}' THEN {}", v, i))
+                    .collect();
+                format!("CASE {} {} ELSE {} END", column, arms.join(" "), ranks.len())
+            }
+            None => column,
+        };
+        if self.field == SortField::Id {
+            format!("{} {}", key, self.direction())
+        } else {
+            format!("{} {}, {}.id ASC", key, self.direction(), alias)
+        }
+    }
+
+    /// The same order as [`Sort::order_by`], for decisions already in memory.
+    pub fn compare(&self, a: &Decision, b: &Decision) -> Ordering {
+        let key = |d: &Decision| -> String {
+            match self.field {
+                SortField::Id => d.id.clone(),
+                SortField::Title => d.title.clone(),
+                SortField::Level => d.level.to_string(),
+                SortField::Status => d.status.to_string(),
+                SortField::Kind => d.kind.to_string(),
+                SortField::Weight => d.weight.to_string(),
+                SortField::Scope => d.scope.clone().unwrap_or_default(),
+                SortField::Author => d.author.clone(),
+                SortField::Created => d.created_at.clone(),
+                SortField::Updated => d.updated_at.clone(),
+            }
+        };
+        let (ka, kb) = (key(a), key(b));
+        let ord = match self.field.ranks() {
+            Some(ranks) => {
+                let rank = |v: &str| ranks.iter().position(|r| *r == v).unwrap_or(ranks.len());
+                rank(&ka).cmp(&rank(&kb))
+            }
+            None => ka.cmp(&kb),
+        };
+        let ord = if self.descending { ord.reverse() } else { ord };
+        ord.then_with(|| a.id.cmp(&b.id))
+    }
+}
+
+impl FromStr for Sort {
+    type Err = DictumError;
+
+    fn from_str(spec: &str) -> Result<Self, Self::Err> {
+        let invalid = || DictumError::InvalidSort(spec.to_string());
+        let (field, dir) = spec.split_once(':').unwrap_or((spec, "asc"));
+        let field = match field.trim() {
+            "id" => SortField::Id,
+            "title" => SortField::Title,
+            "level" => SortField::Level,
+            "status" => SortField::Status,
+            "kind" => SortField::Kind,
+            "weight" => SortField::Weight,
+            "scope" => SortField::Scope,
+            "author" => SortField::Author,
+            "created" | "created_at" => SortField::Created,
+            "updated" | "updated_at" => SortField::Updated,
+            _ => return Err(invalid()),
+        };
+        let descending = match dir.trim() {
+            "asc" => false,
+            "desc" => true,
+            _ => return Err(invalid()),
+        };
+        Ok(Sort { field, descending })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::TestProject;
+
+    #[test]
+    fn parses_field_and_direction() {
+        let sort: Sort = "weight:desc".parse().unwrap();
+        assert_eq!(sort.field, SortField::Weight);
+        assert!(sort.descending);
+        assert_eq!("author".parse::<Sort>().unwrap().field, SortField::Author);
+    }
+
+    #[test]
+    fn rejects_unknown_fields_and_directions() {
+        assert!("colour".parse::<Sort>().is_err());
+        assert!("title:sideways".parse::<Sort>().is_err());
+    }
+
+    #[test]
+    fn builds_order_by_with_id_as_tiebreak() {
+        let sort: Sort = "weight:desc".parse().unwrap();
+        assert_eq!(
+            sort.order_by("d"),
+            "CASE d.weight WHEN 'must' THEN 0 WHEN 'should' THEN 1 WHEN 'may' THEN 2 ELSE 3 END DESC, d.id ASC"
+        );
+        assert_eq!("created".parse::<Sort>().unwrap().order_by("d"), "d.created_at ASC, d.id ASC");
+        assert_eq!("id:desc".parse::<Sort>().unwrap().order_by("d"), "d.id DESC");
+
+        assert_eq!(
+            "scope:desc".parse::<Sort>().unwrap().order_by("d"),
+            "coalesce(d.scope, '') DESC, d.id ASC"
+        );
+    }
+
+    #[test]
+    fn backends_sort_by_scope_and_author() {
+        let project = TestProject::new();
+        let mut store = project.open_cache();
+        store.decision_insert(&Decision::test("d-1").with_scope("billing").with_author("carol")).unwrap();
+        store.decision_insert(&Decision::test("d-2").with_author("alice")).unwrap();
+        store.decision_insert(&Decision::test("d-3").with_scope("api").with_author("bob")).unwrap();
+
+        let ids = |spec: &str| -> Vec<String> {
+            let filter = crate::db::ListFilter {
+                sort: Some(spec.parse().unwrap()),
+                ..Default::default()
+            };
+            store.decision_list(&filter).unwrap().into_iter().map(|d| d.id).collect()
+        };
+        assert_eq!(ids("scope"), ["d-2", "d-3", "d-1"]);
+        assert_eq!(ids("scope:desc"), ["d-1", "d-3", "d-2"]);
+        assert_eq!(ids("author"), ["d-2", "d-3", "d-1"]);
+    }
+}
@@ -1,8 +1,10 @@
 use rusqlite::{params, Connection, Row};
 
+use crate::db::search::{SearchHit, SearchQuery};
+use crate::db::sort::Sort;
 use crate::db::store::ListFilter;
 use crate::error::{DictumError, Result};
-use crate::model::{Decision, Kind, Level, Status, Weight};
+use crate::model::{scope, Decision, Kind, Level, Origin, Status, Transition, Weight};
 
 fn decision_from_row(row: &Row) -> rusqlite::Result<Decision> {
     Ok(Decision {
@@ -32,15 +34,18 @@ fn decision_from_row(row: &Row) -> rusqlite::Result<Decision> {
             .unwrap_or(Weight::Should),
         rebuttal: row.get(11)?,
         scope: row.get(12)?,
+        origin: row
+            .get::<_, Option<String>>(13)?
+            .and_then(|s| serde_json::from_str(&s).ok()),
     })
 }
 
-const SELECT_COLS: &str = "id, title, body, level, status, superseded_by, author, created_at, updated_at, kind, weight, rebuttal, scope";
+const SELECT_COLS: &str = "id, title, body, level, status, superseded_by, author, created_at, updated_at, kind, weight, rebuttal, scope, origin";
 
 pub fn insert(conn: &Connection, decision: &Decision) -> Result<()> {
     conn.execute(
-        "INSERT INTO decisions (id, title, body, level, status, superseded_by, author, created_at, updated_at, kind, weight, rebuttal, scope)
-         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
+        "INSERT INTO decisions (id, title, body, level, status, superseded_by, author, created_at, updated_at, kind, weight, rebuttal, scope, origin)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)",
         params![
             decision.id,
             decision.title,
@@ -55,6 +60,7 @@ pub fn insert(conn: &Connection, decision: &Decision) -> Result<()> {
             decision.weight.to_string(),
             decision.rebuttal,
             decision.scope,
+            decision.origin.as_ref().map(serde_json::to_string).transpose()?,
         ],
     )
     .map_err(|e| match e {
@@ -116,8 +122,23 @@ pub fn list(conn: &Connection, filter: &ListFilter) -> Result<Vec<Decision>> {
         conditions.push(format!("d.weight = ?{}", param_values.len()));
     }
     if let Some(ref scope) = filter.scope {
-        param_values.push(scope.clone());
-        conditions.push(format!("d.scope = ?{}", param_values.len()));
+        if filter.scope_exact {
+            param_values.push(scope::normalize(scope).unwrap_or_default());
+            conditions.push(format!("d.scope = ?{}", param_values.len()));
+        } else {
+            let mut placeholders = Vec::new();
+            for ancestor in scope::ancestors(scope) {
+                param_values.push(ancestor);
+                placeholders.push(format!("?{}", param_values.len()));
+            }
+            conditions.push(format!(
+                "(d.scope IS NULL OR d.scope IN ({}))",
+                placeholders.join(", ")
+            ));
+        }
+    }
+    if let Some(ref expr) = filter.expr {
+        conditions.push(super::expr::to_sql(expr, &mut param_values));
     }
 
     if !conditions.is_empty() {
@@ -125,7 +146,13 @@ pub fn list(conn: &Connection, filter: &ListFilter) -> Result<Vec<Decision>> {
         sql.push_str(&conditions.join(" AND "));
     }
 
-    sql.push_str(" ORDER BY d.created_at DESC");
+    let sort = filter.sort.unwrap_or(Sort::DEFAULT);
+    sql.push_str(&format!(" ORDER BY {}", sort.order_by("d")));
+    if filter.limit.is_some() || filter.offset > 0 {
+        // SQLite needs a LIMIT for OFFSET; -1 means no limit
+        let limit = filter.limit.map_or(-1, |l| l as i64);
+        sql.push_str(&format!(" LIMIT {} OFFSET {}", limit, filter.offset));
+    }
 
     let mut stmt = conn.prepare(&sql)?;
     let params: Vec<&dyn rusqlite::types::ToSql> = param_values
@@ -151,34 +178,112 @@ pub fn update_status(
     status: &Status,
     superseded_by: Option<&str>,
 ) -> Result<()> {
+    let old = get(conn, id)?;
     let now = chrono::Utc::now().to_rfc3339();
-    let rows = conn.execute(
+    conn.execute(
         "UPDATE decisions SET status = ?1, superseded_by = ?2, updated_at = ?3 WHERE id = ?4",
         params![status.to_string(), superseded_by, now, id],
     )?;
-    if rows == 0 {
-        return Err(DictumError::DecisionNotFound(id.to_string()));
-    }
+    super::transitions::insert(conn, &Transition {
+        decision_id: id.to_string(),
+        from_status: old.status,
+        to_status: status.clone(),
+        from_superseded_by: old.superseded_by,
+        from_fields: Default::default(),
+        at: now,
+    })
+}
+
+pub fn reject(conn: &Connection, id: &str, origin: &Origin) -> Result<()> {
+    let old = get(conn, id)?;
+    let now = chrono::Utc::now().to_rfc3339();
+    conn.execute(
+        "UPDATE decisions SET status = ?1, origin = ?2, updated_at = ?3 WHERE id = ?4",
+        params![Status::Rejected.to_string(), serde_json::to_string(origin)?, now, id],
+    )?;
+    super::transitions::insert(conn, &Transition {
+        decision_id: id.to_string(),
+        from_status: old.status,
+        to_status: Status::Rejected,
+        from_superseded_by: old.superseded_by,
+        from_fields: Default::default(),
+        at: now,
+    })
+}
+
+pub fn replace(conn: &Connection, decision: &Decision) -> Result<()> {
+    get(conn, &decision.id)?;
+    conn.execute(
+        "UPDATE decisions SET title = ?2, body = ?3, level = ?4, status = ?5, superseded_by = ?6,
+         author = ?7, created_at = ?8, updated_at = ?9, kind = ?10, weight = ?11, rebuttal = ?12,
+         scope = ?13, origin = ?14
+         WHERE id = ?1",
+        params![
+            decision.id,
+            decision.title,
+            decision.body,
+            decision.level.to_string(),
+            decision.status.to_string(),
+            decision.superseded_by,
+            decision.author,
+            decision.created_at,
+            decision.updated_at,
+            decision.kind.to_string(),
+            decision.weight.to_string(),
+            decision.rebuttal,
+            decision.scope,
+            decision.origin.as_ref().map(serde_json::to_string).transpose()?,
+        ],
+    )?;
     Ok(())
 }
 
-pub fn search(conn: &Connection, query: &str) -> Result<Vec<Decision>> {
-    let pattern = format!("%{}%", query);
-    let sql = format!(
-        "SELECT {} FROM decisions WHERE title LIKE ?1 OR body LIKE ?1 OR rebuttal LIKE ?1 OR scope LIKE ?1 ORDER BY created_at DESC",
-        SELECT_COLS
+pub fn search(conn: &Connection, query: &SearchQuery) -> Result<Vec<SearchHit>> {
+    let mut sql = format!(
+        "SELECT d.{}, bm25(decisions_fts) FROM decisions_fts JOIN decisions d ON d.id = decisions_fts.id \
+         WHERE decisions_fts MATCH ?1",
+        SELECT_COLS.replace(", ", ", d.")
     );
+    let mut param_values = vec![fts_match(query)];
+    if let Some(ref status) = query.status {
+        param_values.push(status.to_string());
+        sql.push_str(" AND d.status = ?2");
+    }
+    sql.push_str(" ORDER BY bm25(decisions_fts), d.created_at DESC");
+
     let mut stmt = conn.prepare(&sql)?;
-    let decisions = stmt
-        .query_map(params![pattern], decision_from_row)?
+    let score_col = SELECT_COLS.split(", ").count();
+    let scored = stmt
+        .query_map(rusqlite::params_from_iter(&param_values), |row| {
+            // bm25() is lower-is-better; flip it so higher scores rank first
+            Ok((decision_from_row(row)?, -row.get::<_, f64>(score_col)?))
+        })?
         .collect::<std::result::Result<Vec<_>, _>>()?;
 
     let mut result = Vec::new();
-    for d in decisions {
+    for (d, score) in scored {
         let labels = super::labels::get_for_decision(conn, &d.id)?;
-        result.push(Decision { labels, ..d });
+        result.push((Decision { labels, ..d }, score));
     }
-    Ok(result)
+    Ok(crate::db::search::rank(query, result))
+}
+
+/// FTS5 MATCH expression for a parsed query. Every word is quoted, so user input
+/// cannot inject FTS5 operators.
+fn fts_match(query: &SearchQuery) -> String {
+    query
+        .terms
+        .iter()
+        .map(|term| {
+            let phrase = format!("\"{}\"", term.words.join(" "));
+            let phrase = if term.prefix { format!("{}*", phrase) } else { phrase };
+            match term.field {
+                Some(field) => format!("{} : {}", field.name(), phrase),
+                None => phrase,
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
 }
 
 #[cfg(test)]
@@ -194,21 +299,10 @@ mod tests {
     }
 
     fn make_decision(id: &str, kind: Kind, weight: Weight, scope: Option<&str>) -> Decision {
-        Decision {
-            id: id.to_string(),
-            title: format!("Decision {}", id),
-            body: None,
-            level: Level::Tactical,
-            status: Status::Active,
-            superseded_by: None,
-            author: "test".to_string(),
-            created_at: "2025-01-01T00:00:00Z".to_string(),
-            updated_at: "2025-01-01T00:00:00Z".to_string(),
-            labels: Vec::new(),
-            kind,
-            weight,
-            rebuttal: None,
-            scope: scope.map(|s| s.to_string()),
+        let d = Decision::test(id).with_kind(kind).with_weight(weight);
+        match scope {
+            Some(scope) => d.with_scope(scope),
+            None => d,
         }
     }
 
@@ -221,7 +315,7 @@ mod tests {
 
         let results = list(&conn, &ListFilter {
             kind: Some(Kind::Rule),
-            level: None, status: None, label: None, weight: None, scope: None,
+            ..Default::default()
         }).unwrap();
 
         assert_eq!(results.len(), 2);
@@ -236,7 +330,7 @@ mod tests {
 
         let results = list(&conn, &ListFilter {
             weight: Some(Weight::Must),
-            level: None, status: None, label: None, kind: None, scope: None,
+            ..Default::default()
         }).unwrap();
 
         assert_eq!(results.len(), 1);
@@ -249,14 +343,22 @@ mod tests {
         insert(&conn, &make_decision("d-1", Kind::Rule, Weight::Must, Some("auth"))).unwrap();
         insert(&conn, &make_decision("d-2", Kind::Rule, Weight::Must, Some("logging"))).unwrap();
         insert(&conn, &make_decision("d-3", Kind::Rule, Weight::Must, None)).unwrap();
+        insert(&conn, &make_decision("d-4", Kind::Rule, Weight::Must, Some("auth/oauth"))).unwrap();
+
+        let ids = |scope: &str, scope_exact: bool| -> Vec<String> {
+            let mut ids: Vec<String> = list(&conn, &ListFilter {
+                scope: Some(scope.to_string()),
+                scope_exact,
+                ..Default::default()
+            }).unwrap().into_iter().map(|d| d.id).collect();
+            ids.sort();
+            ids
+        };
 
-        let results = list(&conn, &ListFilter {
-            scope: Some("auth".to_string()),
-            level: None, status: None, label: None, kind: None, weight: None,
-        }).unwrap();
-
-        assert_eq!(results.len(), 1);
-        assert_eq!(results[0].id, "d-1");
+        // Enclosing and unscoped decisions are inherited; narrower ones are not
+        assert_eq!(ids("auth", false), vec!["d-1", "d-3"]);
+        assert_eq!(ids("auth/oauth/", false), vec!["d-1", "d-3", "d-4"]);
+        assert_eq!(ids("auth", true), vec!["d-1"]);
     }
 
     #[test]
@@ -269,13 +371,99 @@ mod tests {
         let results = list(&conn, &ListFilter {
             kind: Some(Kind::Rule),
             weight: Some(Weight::Must),
-            level: None, status: None, label: None, scope: None,
+            ..Default::default()
         }).unwrap();
 
         assert_eq!(results.len(), 1);
         assert_eq!(results[0].id, "d-1");
     }
 
+    #[test]
+    fn filter_by_where_expression() {
+        let conn = test_db();
+        insert(&conn, &make_decision("d-1", Kind::Rule, Weight::Must, Some("legacy-api"))).unwrap();
+        insert(&conn, &make_decision("d-2", Kind::Constraint, Weight::Must, None)).unwrap();
+        insert(&conn, &make_decision("d-3", Kind::Rule, Weight::Must, Some("auth"))).unwrap();
+        insert(&conn, &make_decision("d-4", Kind::Choice, Weight::Must, None)).unwrap();
+        for id in ["d-1", "d-2", "d-4"] {
+            super::super::labels::add(&conn, id, "security").unwrap();
+        }
+
+        let expr = crate::db::expr::parse(
+            "kind in (rule, constraint) and weight = must and label:security and not scope:legacy*",
+        )
+        .unwrap();
+        let results = list(&conn, &ListFilter {
+            expr: Some(expr),
+            ..Default::default()
+        }).unwrap();
+
+        let ids: Vec<&str> = results.iter().map(|d| d.id.as_str()).collect();
+        assert_eq!(ids, vec!["d-2"]);
+    }
+
+    #[test]
+    fn sort_and_paginate() {
+        let conn = test_db();
+        insert(&conn, &make_decision("d-1", Kind::Rule, Weight::May, None)).unwrap();
+        insert(&conn, &make_decision("d-2", Kind::Rule, Weight::Must, None)).unwrap();
+        insert(&conn, &make_decision("d-3", Kind::Rule, Weight::Should, None)).unwrap();
+        insert(&conn, &make_decision("d-4", Kind::Rule, Weight::Must, None)).unwrap();
+
+        let ids = |filter: ListFilter| -> Vec<String> {
+            list(&conn, &filter).unwrap().into_iter().map(|d| d.id).collect()
+        };
+        assert_eq!(
+            ids(ListFilter { sort: Some("weight".parse().unwrap()), ..Default::default() }),
+            vec!["d-2", "d-4", "d-3", "d-1"]
+        );
+        assert_eq!(
+            ids(ListFilter {
+                sort: Some("weight:desc".parse().unwrap()),
+                limit: Some(2),
+                offset: 1,
+                ..Default::default()
+            }),
+            vec!["d-3", "d-2"]
+        );
+        assert_eq!(ids(ListFilter { offset: 3, ..Default::default() }).len(), 1);
+    }
+
+    #[test]
+    fn search_ranks_with_fts_and_filters_status() {
+        let conn = test_db();
+        insert(&conn, &Decision {
+            title: "Cache sessions".to_string(),
+            body: Some("Cache everything in the session cache".to_string()),
+            ..make_decision("d-1", Kind::Choice, Weight::Should, None)
+        }).unwrap();
+        insert(&conn, &Decision {
+            title: "Use Postgres".to_string(),
+            rebuttal: Some("unless the cache layer is enough".to_string()),
+            ..make_decision("d-2", Kind::Choice, Weight::Should, None)
+        }).unwrap();
+        insert(&conn, &Decision {
+            title: "Old caching rule".to_string(),
+            ..make_decision("d-3", Kind::Rule, Weight::Must, None)
+        }).unwrap();
+        update_status(&conn, "d-3", &Status::Deprecated, None).unwrap();
+
+        let hits = search(&conn, &SearchQuery::parse("cache").unwrap()).unwrap();
+        let ids: Vec<&str> = hits.iter().map(|h| h.decision.id.as_str()).collect();
+        assert_eq!(ids, vec!["d-1", "d-2"]);
+        assert!(hits[0].score > hits[1].score);
+        assert_eq!(hits[1].field.name(), "rebuttal");
+
+        let mut query = SearchQuery::parse("cach*").unwrap();
+        assert_eq!(search(&conn, &query).unwrap().len(), 3);
+        query.status = Some(Status::Active);
+        assert_eq!(search(&conn, &query).unwrap().len(), 2);
+
+        let hits = search(&conn, &SearchQuery::parse("rebuttal:cache").unwrap()).unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].snippet, "unless the **cache** layer is enough");
+    }
+
     #[test]
     fn new_fields_roundtrip_through_db() {
         let conn = test_db();
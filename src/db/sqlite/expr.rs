@@ -0,0 +1,53 @@
+use crate::db::expr::{Expr, Field};
+
+/// Compile a `--where` expression to a SQL condition over `decisions d`,
+/// appending bound values to `params` (placeholders are `?N`, 1-based).
+pub fn to_sql(expr: &Expr, params: &mut Vec<String>) -> String {
+    match expr {
+        Expr::And(a, b) => format!("({} AND {})", to_sql(a, params), to_sql(b, params)),
+        Expr::Or(a, b) => format!("({} OR {})", to_sql(a, params), to_sql(b, params)),
+        Expr::Not(inner) => format!("NOT {}", to_sql(inner, params)),
+        Expr::Cmp { field, op, value } => {
+            params.push(value.clone());
+            predicate(*field, &format!("{} ?{}", op, params.len()))
+        }
+        Expr::Glob { field, pattern } => {
+            params.push(glob_escape(pattern));
+            predicate(*field, &format!("GLOB ?{}", params.len()))
+        }
+        Expr::In { field, values } => {
+            let placeholders: Vec<String> = values
+                .iter()
+                .map(|v| {
+                    params.push(v.clone());
+                    format!("?{}", params.len())
+                })
+                .collect();
+            predicate(*field, &format!("IN ({})", placeholders.join(", ")))
+        }
+    }
+}
+
+fn predicate(field: Field, test: &str) -> String {
+    match field {
+        Field::Label => format!(
+            "EXISTS (SELECT 1 FROM labels wl WHERE wl.decision_id = d.id AND wl.label {})",
+            test
+        ),
+        _ if field.is_nullable() => format!("COALESCE(d.{}, '') {}", field.column(), test),
+        _ => format!("d.{} {}", field.column(), test),
+    }
+}
+
+/// Only `*` is a wildcard in `--where` globs; quote SQLite's other GLOB metacharacters.
+fn glob_escape(pattern: &str) -> String {
+    let mut out = String::new();
+    for c in pattern.chars() {
+        match c {
+            '?' => out.push_str("[?]"),
+            '[' => out.push_str("[[]"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
@@ -3,10 +3,14 @@ use rusqlite::{params, Connection};
 use crate::error::{DictumError, Result};
 use crate::model::{Link, LinkKind};
 
-pub fn insert(conn: &Connection, link: &Link) -> Result<()> {
+pub fn insert(conn: &Connection, link: &Link, acyclic_kinds: &[LinkKind]) -> Result<()> {
     if link.source_id == link.target_id {
         return Err(DictumError::SelfLink);
     }
+    if acyclic_kinds.contains(&link.kind) {
+        let edges = get_of_kind(conn, &link.kind)?;
+        crate::db::check_link_cycle(link, acyclic_kinds, &edges)?;
+    }
     conn.execute(
         "INSERT INTO links (source_id, target_id, kind, created_at, reason) VALUES (?1, ?2, ?3, ?4, ?5)",
         params![
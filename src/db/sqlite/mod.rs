@@ -1,19 +1,24 @@
 mod decisions;
+mod expr;
 mod labels;
 mod links;
 mod schema;
+mod transitions;
 
-use std::collections::{HashSet, VecDeque};
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::path::Path;
 
 use rusqlite::Connection;
 
+use crate::db::search::{SearchHit, SearchQuery};
 use crate::db::store::{ListFilter, Neighborhood, Store};
 use crate::error::Result;
-use crate::model::{Decision, Link, LinkKind, Status};
+use crate::model::{Decision, Link, LinkKind, Origin, Status, Transition};
 
 pub struct SqliteStore {
     conn: Connection,
+    acyclic_kinds: Vec<LinkKind>,
 }
 
 impl SqliteStore {
@@ -23,7 +28,15 @@ impl SqliteStore {
         conn.execute_batch("PRAGMA journal_mode=WAL; PRAGMA foreign_keys=ON;")?;
         migrate(&conn)?;
         initialize(&conn)?;
-        Ok(SqliteStore { conn })
+        Ok(SqliteStore {
+            conn,
+            acyclic_kinds: crate::config::default_acyclic_link_kinds(),
+        })
+    }
+
+    pub fn with_acyclic_kinds(mut self, kinds: Vec<LinkKind>) -> Self {
+        self.acyclic_kinds = kinds;
+        self
     }
 
     #[cfg(test)]
@@ -31,7 +44,10 @@ impl SqliteStore {
         let conn = Connection::open_in_memory()?;
         conn.execute_batch("PRAGMA foreign_keys=ON;")?;
         initialize(&conn)?;
-        Ok(SqliteStore { conn })
+        Ok(SqliteStore {
+            conn,
+            acyclic_kinds: crate::config::default_acyclic_link_kinds(),
+        })
     }
 }
 
@@ -39,15 +55,23 @@ pub fn initialize(conn: &Connection) -> Result<()> {
     conn.execute_batch(schema::CREATE_DECISIONS_TABLE)?;
     conn.execute_batch(schema::CREATE_LINKS_TABLE)?;
     conn.execute_batch(schema::CREATE_LABELS_TABLE)?;
+    conn.execute_batch(schema::CREATE_TRANSITIONS_TABLE)?;
+    for sql in schema::CREATE_DECISIONS_FTS {
+        conn.execute_batch(sql)?;
+    }
     Ok(())
 }
 
-fn needs_migration(conn: &Connection) -> Result<bool> {
-    let mut stmt = conn.prepare("PRAGMA table_info(decisions)")?;
-    let columns: Vec<String> = stmt
+fn decision_columns(conn: &Connection) -> Result<Vec<String>> {
+    table_columns(conn, "decisions")
+}
+
+fn table_columns(conn: &Connection, table: &str) -> Result<Vec<String>> {
+    let mut stmt = conn.prepare(&format!("PRAGMA table_info({})", table))?;
+    let columns = stmt
         .query_map([], |row| row.get::<_, String>(1))?
         .collect::<std::result::Result<Vec<_>, _>>()?;
-    Ok(!columns.iter().any(|c| c == "kind"))
+    Ok(columns)
 }
 
 fn migrate(conn: &Connection) -> Result<()> {
@@ -59,17 +83,27 @@ fn migrate(conn: &Connection) -> Result<()> {
     if !table_exists {
         return Ok(());
     }
-    if !needs_migration(conn)? {
-        return Ok(());
+    if !decision_columns(conn)?.iter().any(|c| c == "kind") {
+        for sql in schema::MIGRATE_DECISIONS_V2 {
+            conn.execute_batch(sql)?;
+        }
+        conn.execute_batch("PRAGMA foreign_keys=OFF;")?;
+        for sql in schema::MIGRATE_LINKS_V2 {
+            conn.execute_batch(sql)?;
+        }
+        conn.execute_batch("PRAGMA foreign_keys=ON;")?;
     }
-    for sql in schema::MIGRATE_DECISIONS_V2 {
-        conn.execute_batch(sql)?;
+    if !decision_columns(conn)?.iter().any(|c| c == "origin") {
+        conn.execute_batch("PRAGMA foreign_keys=OFF;")?;
+        for sql in schema::MIGRATE_DECISIONS_V3 {
+            conn.execute_batch(sql)?;
+        }
+        conn.execute_batch("PRAGMA foreign_keys=ON;")?;
     }
-    conn.execute_batch("PRAGMA foreign_keys=OFF;")?;
-    for sql in schema::MIGRATE_LINKS_V2 {
-        conn.execute_batch(sql)?;
+    let transitions = table_columns(conn, "transitions")?;
+    if !transitions.is_empty() && !transitions.iter().any(|c| c == "from_fields") {
+        conn.execute_batch(schema::MIGRATE_TRANSITIONS_V2)?;
     }
-    conn.execute_batch("PRAGMA foreign_keys=ON;")?;
     Ok(())
 }
 
@@ -95,7 +129,15 @@ impl Store for SqliteStore {
         decisions::update_status(&self.conn, id, status, superseded_by)
     }
 
-    fn decision_search(&self, query: &str) -> Result<Vec<Decision>> {
+    fn decision_reject(&mut self, id: &str, origin: &Origin) -> Result<()> {
+        decisions::reject(&self.conn, id, origin)
+    }
+
+    fn decision_replace(&mut self, decision: &Decision) -> Result<()> {
+        decisions::replace(&self.conn, decision)
+    }
+
+    fn decision_search(&self, query: &SearchQuery) -> Result<Vec<SearchHit>> {
         decisions::search(&self.conn, query)
     }
 
@@ -103,8 +145,16 @@ impl Store for SqliteStore {
         labels::add(&self.conn, decision_id, label)
     }
 
+    fn transition_insert(&mut self, transition: &Transition) -> Result<()> {
+        transitions::insert(&self.conn, transition)
+    }
+
+    fn transitions(&self, decision_id: Option<&str>) -> Result<Vec<Transition>> {
+        transitions::get(&self.conn, decision_id)
+    }
+
     fn link_insert(&mut self, link: &Link) -> Result<()> {
-        links::insert(&self.conn, link)
+        links::insert(&self.conn, link, &self.acyclic_kinds)
     }
 
     fn link_delete(&mut self, source_id: &str, kind: &LinkKind, target_id: &str) -> Result<()> {
@@ -120,9 +170,9 @@ impl Store for SqliteStore {
     }
 
     fn neighborhood(&self, id: &str, depth: u32) -> Result<Neighborhood> {
-        let mut visited_ids: HashSet<String> = HashSet::new();
+        let mut hops: HashMap<String, u32> = HashMap::new();
         let mut queue: VecDeque<(String, u32)> = VecDeque::new();
-        visited_ids.insert(id.to_string());
+        hops.insert(id.to_string(), 0);
         queue.push_back((id.to_string(), 0));
 
         let mut all_links: Vec<Link> = Vec::new();
@@ -138,7 +188,8 @@ impl Store for SqliteStore {
                 } else {
                     &link.source_id
                 };
-                if visited_ids.insert(neighbor.clone()) {
+                if let Entry::Vacant(slot) = hops.entry(neighbor.clone()) {
+                    slot.insert(current_depth + 1);
                     queue.push_back((neighbor.clone(), current_depth + 1));
                 }
             }
@@ -151,12 +202,21 @@ impl Store for SqliteStore {
             seen.insert((l.source_id.clone(), l.target_id.clone(), l.kind.to_string()))
         });
 
+        let mut node_ids: Vec<&String> = hops.keys().collect();
+        node_ids.sort_by(|a, b| hops[*a].cmp(&hops[*b]).then_with(|| a.cmp(b)));
+
         let mut result_decisions = Vec::new();
-        for node_id in &visited_ids {
+        for node_id in node_ids {
             result_decisions.push(decisions::get(&self.conn, node_id)?);
         }
 
-        Ok(Neighborhood { decisions: result_decisions, links: all_links })
+        Ok(Neighborhood {
+            root: id.to_string(),
+            depth,
+            decisions: result_decisions,
+            links: all_links,
+            hops,
+        })
     }
 
     fn reachable(&self, id: &str, kinds: &[LinkKind]) -> Result<Vec<String>> {
@@ -184,3 +244,74 @@ impl Store for SqliteStore {
         Ok(visited.into_iter().skip(1).collect())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::DictumError;
+
+    fn make_link(src: &str, tgt: &str, kind: LinkKind) -> Link {
+        Link {
+            source_id: src.to_string(),
+            target_id: tgt.to_string(),
+            kind,
+            created_at: "2025-01-01T00:00:00Z".to_string(),
+            reason: None,
+        }
+    }
+
+    #[test]
+    fn status_changes_record_transitions() {
+        let mut store = SqliteStore::in_memory().unwrap();
+        store.decision_insert(&Decision::test("d-1")).unwrap();
+        store.decision_insert(&Decision::test("d-2")).unwrap();
+        store.decision_update_status("d-1", &Status::Superseded, Some("d-2")).unwrap();
+        store.decision_update_status("d-1", &Status::Deprecated, None).unwrap();
+
+        let transitions = store.transitions(Some("d-1")).unwrap();
+        assert_eq!(transitions.len(), 2);
+        assert_eq!(transitions[0].from_status, Status::Active);
+        assert_eq!(transitions[1].from_status, Status::Superseded);
+        assert_eq!(transitions[1].from_superseded_by.as_deref(), Some("d-2"));
+        assert!(store.transitions(Some("d-2")).unwrap().is_empty());
+        assert_eq!(store.transitions(None).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn link_cycle_rejected_for_acyclic_kind() {
+        let mut store = SqliteStore::in_memory().unwrap();
+        for id in ["d-1", "d-2", "d-3"] {
+            store.decision_insert(&Decision::test(id)).unwrap();
+        }
+        store.link_insert(&make_link("d-1", "d-2", LinkKind::Requires)).unwrap();
+        store.link_insert(&make_link("d-2", "d-3", LinkKind::Requires)).unwrap();
+
+        let result = store.link_insert(&make_link("d-3", "d-1", LinkKind::Requires));
+        assert!(matches!(result, Err(DictumError::LinkCycle { .. })));
+
+        store.link_insert(&make_link("d-3", "d-1", LinkKind::Conflicts)).unwrap();
+    }
+
+    #[test]
+    fn neighborhood_follows_links_both_ways_and_groups_by_hop() {
+        let mut store = SqliteStore::in_memory().unwrap();
+        for id in ["d-1", "d-2", "d-3", "d-4", "d-5"] {
+            store.decision_insert(&Decision::test(id)).unwrap();
+        }
+        store.link_insert(&make_link("d-2", "d-1", LinkKind::Refines)).unwrap();
+        store.link_insert(&make_link("d-1", "d-5", LinkKind::Requires)).unwrap();
+        store.link_insert(&make_link("d-3", "d-2", LinkKind::Refines)).unwrap();
+        store.link_insert(&make_link("d-4", "d-3", LinkKind::Refines)).unwrap();
+
+        let nb = store.neighborhood("d-1", 2).unwrap();
+        let ids: Vec<&str> = nb.decisions.iter().map(|d| d.id.as_str()).collect();
+        assert_eq!(ids, vec!["d-1", "d-2", "d-5", "d-3"]);
+        assert_eq!((nb.root.as_str(), nb.depth), ("d-1", 2));
+        assert_eq!((nb.hop_of("d-5"), nb.hop_of("d-3")), (1, 2));
+        assert_eq!(nb.links.len(), 3);
+
+        let root_only = store.neighborhood("d-1", 0).unwrap();
+        assert_eq!(root_only.decisions.len(), 1);
+        assert!(root_only.links.is_empty());
+    }
+}
@@ -4,7 +4,7 @@ CREATE TABLE IF NOT EXISTS decisions (
     title TEXT NOT NULL,
     body TEXT,
     level TEXT NOT NULL CHECK(level IN ('strategic', 'tactical', 'operational')),
-    status TEXT NOT NULL DEFAULT 'active' CHECK(status IN ('active', 'superseded', 'deprecated', 'draft')),
+    status TEXT NOT NULL DEFAULT 'active' CHECK(status IN ('active', 'superseded', 'deprecated', 'draft', 'rejected')),
     superseded_by TEXT,
     author TEXT NOT NULL,
     created_at TEXT NOT NULL,
@@ -12,7 +12,8 @@ CREATE TABLE IF NOT EXISTS decisions (
     kind TEXT NOT NULL DEFAULT 'choice' CHECK(kind IN ('principle','constraint','assumption','choice','rule','goal')),
     weight TEXT NOT NULL DEFAULT 'should' CHECK(weight IN ('must','should','may')),
     rebuttal TEXT,
-    scope TEXT
+    scope TEXT,
+    origin TEXT
 )";
 
 pub const CREATE_LINKS_TABLE: &str = "
@@ -35,6 +36,18 @@ CREATE TABLE IF NOT EXISTS labels (
     FOREIGN KEY (decision_id) REFERENCES decisions(id)
 )";
 
+pub const CREATE_TRANSITIONS_TABLE: &str = "
+CREATE TABLE IF NOT EXISTS transitions (
+    decision_id TEXT NOT NULL,
+    from_status TEXT NOT NULL,
+    to_status TEXT NOT NULL,
+    from_superseded_by TEXT,
+    at TEXT NOT NULL,
+    from_fields TEXT,
+    FOREIGN KEY (decision_id) REFERENCES decisions(id)
+);
+CREATE INDEX IF NOT EXISTS transitions_decision ON transitions (decision_id, at)";
+
 pub const MIGRATE_DECISIONS_V2: &[&str] = &[
     "ALTER TABLE decisions ADD COLUMN kind TEXT NOT NULL DEFAULT 'choice'",
     "ALTER TABLE decisions ADD COLUMN weight TEXT NOT NULL DEFAULT 'should'",
@@ -42,6 +55,10 @@ pub const MIGRATE_DECISIONS_V2: &[&str] = &[
     "ALTER TABLE decisions ADD COLUMN scope TEXT",
 ];
 
+/// Field edits recorded alongside status changes, as a JSON object of the
+/// values they replaced.
+pub const MIGRATE_TRANSITIONS_V2: &str = "ALTER TABLE transitions ADD COLUMN from_fields TEXT";
+
 pub const MIGRATE_LINKS_V2: &[&str] = &[
     "ALTER TABLE links RENAME TO links_old",
     "CREATE TABLE links (
@@ -57,3 +74,54 @@ pub const MIGRATE_LINKS_V2: &[&str] = &[
     "INSERT INTO links (source_id, target_id, kind, created_at) SELECT source_id, target_id, kind, created_at FROM links_old",
     "DROP TABLE links_old",
 ];
+
+/// Adds `origin` and the `rejected` status. SQLite cannot change a CHECK
+/// constraint in place, so the table is rebuilt; the full-text triggers go
+/// with the old table and are recreated by `initialize`.
+pub const MIGRATE_DECISIONS_V3: &[&str] = &[
+    "CREATE TABLE decisions_new (
+        id TEXT PRIMARY KEY,
+        title TEXT NOT NULL,
+        body TEXT,
+        level TEXT NOT NULL CHECK(level IN ('strategic', 'tactical', 'operational')),
+        status TEXT NOT NULL DEFAULT 'active' CHECK(status IN ('active', 'superseded', 'deprecated', 'draft', 'rejected')),
+        superseded_by TEXT,
+        author TEXT NOT NULL,
+        created_at TEXT NOT NULL,
+        updated_at TEXT NOT NULL,
+        kind TEXT NOT NULL DEFAULT 'choice' CHECK(kind IN ('principle','constraint','assumption','choice','rule','goal')),
+        weight TEXT NOT NULL DEFAULT 'should' CHECK(weight IN ('must','should','may')),
+        rebuttal TEXT,
+        scope TEXT,
+        origin TEXT
+    )",
+    "INSERT INTO decisions_new (id, title, body, level, status, superseded_by, author, created_at, updated_at, kind, weight, rebuttal, scope)
+        SELECT id, title, body, level, status, superseded_by, author, created_at, updated_at, kind, weight, rebuttal, scope FROM decisions",
+    "DROP TABLE decisions",
+    "ALTER TABLE decisions_new RENAME TO decisions",
+];
+
+/// Full-text index over the searchable decision fields, kept in sync by triggers.
+/// `remove_diacritics 0` keeps tokens identical to the ones `db::search` highlights.
+pub const CREATE_DECISIONS_FTS: &[&str] = &[
+    "CREATE VIRTUAL TABLE IF NOT EXISTS decisions_fts USING fts5(
+        id UNINDEXED, title, body, rebuttal, scope,
+        tokenize = 'unicode61 remove_diacritics 0'
+    )",
+    "CREATE TRIGGER IF NOT EXISTS decisions_fts_insert AFTER INSERT ON decisions BEGIN
+        INSERT INTO decisions_fts (id, title, body, rebuttal, scope)
+        VALUES (new.id, new.title, new.body, new.rebuttal, new.scope);
+    END",
+    "CREATE TRIGGER IF NOT EXISTS decisions_fts_update AFTER UPDATE ON decisions BEGIN
+        DELETE FROM decisions_fts WHERE id = old.id;
+        INSERT INTO decisions_fts (id, title, body, rebuttal, scope)
+        VALUES (new.id, new.title, new.body, new.rebuttal, new.scope);
+    END",
+    "CREATE TRIGGER IF NOT EXISTS decisions_fts_delete AFTER DELETE ON decisions BEGIN
+        DELETE FROM decisions_fts WHERE id = old.id;
+    END",
+    // Backfill databases created before the index existed
+    "INSERT INTO decisions_fts (id, title, body, rebuttal, scope)
+        SELECT id, title, body, rebuttal, scope FROM decisions
+        WHERE id NOT IN (SELECT id FROM decisions_fts)",
+];
@@ -0,0 +1,56 @@
+use rusqlite::{params, Connection, Row};
+
+use crate::error::Result;
+use crate::model::{Status, Transition};
+
+fn transition_from_row(row: &Row) -> rusqlite::Result<Transition> {
+    Ok(Transition {
+        decision_id: row.get(0)?,
+        from_status: row
+            .get::<_, String>(1)?
+            .parse::<Status>()
+            .unwrap_or(Status::Active),
+        to_status: row
+            .get::<_, String>(2)?
+            .parse::<Status>()
+            .unwrap_or(Status::Active),
+        from_superseded_by: row.get(3)?,
+        from_fields: row
+            .get::<_, Option<String>>(5)?
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default(),
+        at: row.get(4)?,
+    })
+}
+
+pub fn insert(conn: &Connection, transition: &Transition) -> Result<()> {
+    let from_fields = if transition.from_fields.is_empty() {
+        None
+    } else {
+        Some(serde_json::to_string(&transition.from_fields)?)
+    };
+    conn.execute(
+        "INSERT INTO transitions (decision_id, from_status, to_status, from_superseded_by, at, from_fields)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        params![
+            transition.decision_id,
+            transition.from_status.to_string(),
+            transition.to_status.to_string(),
+            transition.from_superseded_by,
+            transition.at,
+            from_fields,
+        ],
+    )?;
+    Ok(())
+}
+
+pub fn get(conn: &Connection, decision_id: Option<&str>) -> Result<Vec<Transition>> {
+    let mut stmt = conn.prepare(
+        "SELECT decision_id, from_status, to_status, from_superseded_by, at, from_fields FROM transitions
+         WHERE ?1 IS NULL OR decision_id = ?1 ORDER BY at, rowid",
+    )?;
+    let transitions = stmt
+        .query_map(params![decision_id], transition_from_row)?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    Ok(transitions)
+}
@@ -1,20 +1,44 @@
+use std::collections::HashMap;
+
+use super::expr::Expr;
+use super::search::{SearchHit, SearchQuery};
+use super::sort::Sort;
 use crate::error::Result;
-use crate::model::{Decision, Kind, Level, Link, LinkKind, Status, Weight};
+use crate::model::{Decision, Kind, Level, Link, LinkKind, Origin, Status, Transition, Weight};
 
-#[derive(Default)]
+#[derive(Clone, Default)]
 pub struct ListFilter {
     pub level: Option<Level>,
     pub status: Option<Status>,
     pub label: Option<String>,
     pub kind: Option<Kind>,
     pub weight: Option<Weight>,
+    /// Matches decisions scoped here, at any enclosing scope, or unscoped,
+    /// unless `scope_exact` is set.
     pub scope: Option<String>,
+    pub scope_exact: bool,
+    /// Parsed `--where` expression, ANDed with the fields above.
+    pub expr: Option<Expr>,
+    /// Result order; `None` means newest first.
+    pub sort: Option<Sort>,
+    pub limit: Option<usize>,
+    pub offset: usize,
 }
 
-#[allow(dead_code)]
+/// The decisions and links within N hops of a root decision.
+/// `decisions` is ordered by hop distance, then by ID; `hops` maps each ID to its distance.
 pub struct Neighborhood {
+    pub root: String,
+    pub depth: u32,
     pub decisions: Vec<Decision>,
     pub links: Vec<Link>,
+    pub hops: HashMap<String, u32>,
+}
+
+impl Neighborhood {
+    pub fn hop_of(&self, id: &str) -> u32 {
+        self.hops.get(id).copied().unwrap_or(0)
+    }
 }
 
 pub trait Store {
@@ -28,7 +52,14 @@ pub trait Store {
         status: &Status,
         superseded_by: Option<&str>,
     ) -> Result<()>;
-    fn decision_search(&self, query: &str) -> Result<Vec<Decision>>;
+    /// Mark a proposal rejected and store its origin, which carries the reason,
+    /// in the same update.
+    fn decision_reject(&mut self, id: &str, origin: &Origin) -> Result<()>;
+    /// Overwrite an existing decision's fields, timestamps included, with
+    /// `decision`'s. Labels, links and history are left alone.
+    fn decision_replace(&mut self, decision: &Decision) -> Result<()>;
+    /// Relevance-ranked full-text search, best match first.
+    fn decision_search(&self, query: &SearchQuery) -> Result<Vec<SearchHit>>;
     fn decision_get_all(&self) -> Result<Vec<Decision>> {
         self.decision_list(&ListFilter::default())
     }
@@ -36,6 +67,11 @@ pub trait Store {
     // --- Label operations ---
     fn label_add(&mut self, decision_id: &str, label: &str) -> Result<()>;
 
+    // --- History ---
+    fn transition_insert(&mut self, transition: &Transition) -> Result<()>;
+    /// Recorded status changes, oldest first; all decisions when `decision_id` is `None`.
+    fn transitions(&self, decision_id: Option<&str>) -> Result<Vec<Transition>>;
+
     // --- Link operations ---
     fn link_insert(&mut self, link: &Link) -> Result<()>;
     fn link_delete(&mut self, source_id: &str, kind: &LinkKind, target_id: &str) -> Result<()>;
@@ -43,7 +79,6 @@ pub trait Store {
     fn links_of_kind(&self, kind: &LinkKind) -> Result<Vec<(String, String)>>;
 
     // --- Graph traversal (used by Grafeo backend; available to all) ---
-    #[allow(dead_code)]
     fn neighborhood(&self, id: &str, depth: u32) -> Result<Neighborhood>;
     #[allow(dead_code)]
     fn reachable(&self, id: &str, kinds: &[LinkKind]) -> Result<Vec<String>>;
@@ -17,7 +17,7 @@ pub enum DictumError {
     #[error("invalid level: {0} (expected strategic, tactical, or operational)")]
     InvalidLevel(String),
 
-    #[error("invalid status: {0} (expected active, superseded, deprecated, or draft)")]
+    #[error("invalid status: {0} (expected active, superseded, deprecated, draft, or rejected)")]
     InvalidStatus(String),
 
     #[error("invalid link kind: {0}")]
@@ -38,6 +38,9 @@ pub enum DictumError {
     #[error("cannot link a decision to itself")]
     SelfLink,
 
+    #[error("link would create a {kind} cycle: {cycle}")]
+    LinkCycle { kind: String, cycle: String },
+
     #[error(
         "wrong binary: this .dictum/ was initialized with the '{found}' backend \
          but this binary uses '{expected}'. \
@@ -62,8 +65,92 @@ pub enum DictumError {
     #[error("config error: {0}")]
     Config(String),
 
-    #[error("template does not contain the required data placeholder (/*__DICTUM_DATA__*/null)")]
-    InvalidTemplate,
+    #[error("invalid --where expression: {0}")]
+    InvalidExpression(String),
+
+    #[error("invalid operation log: {0}")]
+    InvalidOpLog(String),
+
+    #[error("{0} record(s) left with merge conflicts (see their \"_conflict\" fields)")]
+    MergeConflicts(usize),
+
+    #[error("record {0} has unresolved merge conflicts")]
+    UnresolvedConflict(String),
+
+    #[error("cannot read {0}: expected `store`, a JSONL file, or a git revision of an export")]
+    InvalidDiffSide(String),
+
+    #[error("invalid import mode: {0} (expected upsert, skip, or overwrite)")]
+    InvalidImportMode(String),
+
+    #[error("invalid sort: {0} (expected field[:asc|desc])")]
+    InvalidSort(String),
+
+    #[error("unknown field: {0} (expected one of {known})", known = crate::format::DECISION_FIELDS.join(", "))]
+    InvalidField(String),
+
+    #[error("invalid --as-of: {0} (expected an RFC3339 time, YYYY-MM-DD, or a git revision)")]
+    InvalidAsOf(String),
+
+    #[error("nothing to look up in '{0}': name what the question is about")]
+    EmptyQuestion(String),
+
+    #[error("invalid --since: {0} (expected an RFC3339 time, YYYY-MM-DD, a git revision, or a token from an earlier call)")]
+    InvalidSince(String),
+
+    #[error("cannot modify the store through an --as-of view")]
+    AsOfWrite,
+
+    #[error("view not found: {0}")]
+    ViewNotFound(String),
+
+    #[error("invalid search query: {0}")]
+    InvalidQuery(String),
+
+    #[cfg(feature = "grafeo")]
+    #[error("query modifies the store; pass --write to allow it")]
+    ReadOnlyQuery,
+
+    #[cfg(feature = "grafeo")]
+    #[error("the operation log cannot record this write ({0}); the store was left unchanged")]
+    UnloggableWrite(String),
+
+    #[cfg(feature = "grafeo")]
+    #[error("invalid parameter '{0}' (expected key=value)")]
+    InvalidParam(String),
+
+    #[error("too similar to existing decision {id} (score {score}); amend it instead, or drop --strict")]
+    TooSimilar { id: String, score: String },
+
+    #[error("already proposed as {0}; see `dictum inbox`")]
+    AlreadyProposed(String),
+
+    #[error("a similar proposal was rejected: {id} ({reason})")]
+    PreviouslyRejected { id: String, reason: String },
+
+    #[error("{0} is {1}, not a pending proposal")]
+    NotPending(String, String),
+
+    #[error("candidate set is inconsistent ({0} issue(s))")]
+    Inconsistent(usize),
+
+    #[error("{0} must-weight decision(s) implicated by the diff")]
+    MustRulesImplicated(usize),
+
+    #[error("git diff failed: {0}")]
+    GitDiff(String),
+
+    #[error("invalid argument {0}")]
+    InvalidArgument(String),
+
+    #[error("{0} instruction file(s) out of date; run `dictum sync-instructions`")]
+    InstructionsOutOfDate(usize),
+
+    #[error("unbalanced dictum markers in {0} (expected one <!-- dictum:begin --> followed by <!-- dictum:end -->)")]
+    InvalidMarkers(String),
+
+    #[error("template {0} does not contain the required data placeholder (/*__DICTUM_DATA__*/null)")]
+    InvalidTemplate(String),
 }
 
 pub type Result<T> = std::result::Result<T, DictumError>;
@@ -0,0 +1,105 @@
+use std::collections::HashSet;
+
+use crate::model::{Decision, Link};
+
+/// Render decisions and the links between them as a Mermaid flowchart.
+/// Link endpoints that are not in `decisions` are drawn as bare ID nodes.
+pub fn format_mermaid(decisions: &[Decision], links: &[Link]) -> String {
+    let mut out = String::from("flowchart TD\n");
+
+    let known: HashSet<&str> = decisions.iter().map(|d| d.id.as_str()).collect();
+    for d in decisions {
+        out.push_str(&format!(
+            "  {}[\"{}: {}\"]\n",
+            mermaid_id(&d.id),
+            d.id,
+            mermaid_safe(&d.title)
+        ));
+    }
+
+    let mut extra: HashSet<&str> = HashSet::new();
+    for link in links {
+        for id in [link.source_id.as_str(), link.target_id.as_str()] {
+            if !known.contains(id) && extra.insert(id) {
+                out.push_str(&format!("  {}[\"{}\"]\n", mermaid_id(id), id));
+            }
+        }
+    }
+
+    for link in links {
+        out.push_str(&format!(
+            "  {} -->|{}| {}\n",
+            mermaid_id(&link.source_id),
+            link.kind,
+            mermaid_id(&link.target_id)
+        ));
+    }
+
+    out
+}
+
+/// Render decisions and the links between them as a Graphviz DOT digraph.
+pub fn format_dot(decisions: &[Decision], links: &[Link]) -> String {
+    let mut out = String::from("digraph dictum {\n  node [shape=box];\n");
+
+    for d in decisions {
+        out.push_str(&format!(
+            "  \"{}\" [label=\"{}\\n{}\"];\n",
+            d.id,
+            d.id,
+            dot_escape(&d.title)
+        ));
+    }
+
+    for link in links {
+        out.push_str(&format!(
+            "  \"{}\" -> \"{}\" [label=\"{}\"];\n",
+            link.source_id, link.target_id, link.kind
+        ));
+    }
+
+    out.push_str("}\n");
+    out
+}
+
+/// Mermaid node IDs allow only letters, digits and `_`, so `_` is doubled and
+/// anything else becomes `_<hex>_`, keeping e.g. `d-1` and `d_1` apart.
+fn mermaid_id(id: &str) -> String {
+    let mut out = String::with_capacity(id.len());
+    for c in id.chars() {
+        match c {
+            '_' => out.push_str("__"),
+            c if c.is_ascii_alphanumeric() => out.push(c),
+            c => out.push_str(&format!("_{:x}_", c as u32)),
+        }
+    }
+    out
+}
+
+fn mermaid_safe(s: &str) -> String {
+    s.chars()
+        .map(|c| match c {
+            '"' => '\'',
+            '<' | '>' | '{' | '}' | '|' | '#' | '&' | ';' | '[' | ']' => ' ',
+            _ => c,
+        })
+        .collect()
+}
+
+fn dot_escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mermaid_ids_do_not_collide() {
+        assert_eq!(mermaid_id("d-1"), "d_2d_1");
+        assert_eq!(mermaid_id("d_1"), "d__1");
+        let ids = ["d-1", "d_1", "d.1", "d1", "d_2d_1"];
+        let escaped: HashSet<String> = ids.iter().map(|id| mermaid_id(id)).collect();
+        assert_eq!(escaped.len(), ids.len());
+    }
+}
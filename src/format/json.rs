@@ -1,9 +1,10 @@
 use serde_json::Value;
 
+use crate::db::{Neighborhood, SearchHit};
 use crate::error::Result;
-use crate::model::{Decision, Link};
+use crate::model::{Decision, Link, Transition};
 
-fn decision_to_value(decision: &Decision, links: Option<&[Link]>) -> Value {
+pub fn decision_to_value(decision: &Decision, links: Option<&[Link]>) -> Value {
     let mut v = serde_json::to_value(decision).unwrap_or(Value::Null);
     if let Some(links) = links {
         if let Value::Object(ref mut map) = v {
@@ -42,8 +43,106 @@ pub fn format_decision_list_jsonl(decisions: &[Decision]) -> Result<String> {
     Ok(out)
 }
 
+/// The values of `fields` (serialized key names) of a decision, in the given order.
+pub fn field_values(decision: &Decision, fields: &[&str]) -> Vec<Value> {
+    let full = decision_to_value(decision, None);
+    fields
+        .iter()
+        .map(|f| full.get(*f).cloned().unwrap_or(Value::Null))
+        .collect()
+}
+
+fn project(decision: &Decision, fields: &[&str]) -> Value {
+    let values = field_values(decision, fields);
+    Value::Object(
+        fields
+            .iter()
+            .map(|f| f.to_string())
+            .zip(values)
+            .collect(),
+    )
+}
+
+pub fn format_decision_fields(decisions: &[Decision], fields: &[&str]) -> Result<String> {
+    let values: Vec<Value> = decisions
+        .iter()
+        .map(|d| project(d, fields))
+        .collect();
+    Ok(serde_json::to_string_pretty(&values)?)
+}
+
+pub fn format_decision_fields_jsonl(decisions: &[Decision], fields: &[&str]) -> Result<String> {
+    let mut out = String::new();
+    for d in decisions {
+        out.push_str(&serde_json::to_string(&project(d, fields))?);
+        out.push('\n');
+    }
+    Ok(out)
+}
+
+pub fn search_hit_to_value(hit: &SearchHit) -> Value {
+    let mut v = decision_to_value(&hit.decision, None);
+    if let Value::Object(ref mut map) = v {
+        map.insert("score".to_string(), Value::from(hit.score));
+        map.insert("matched_field".to_string(), Value::from(hit.field.name()));
+        map.insert("snippet".to_string(), Value::from(hit.snippet.as_str()));
+    }
+    v
+}
+
+pub fn format_search_hits(hits: &[SearchHit]) -> Result<String> {
+    let values: Vec<Value> = hits.iter().map(search_hit_to_value).collect();
+    Ok(serde_json::to_string_pretty(&values)?)
+}
+
+pub fn format_search_hits_jsonl(hits: &[SearchHit]) -> Result<String> {
+    let mut out = String::new();
+    for hit in hits {
+        out.push_str(&serde_json::to_string(&search_hit_to_value(hit))?);
+        out.push('\n');
+    }
+    Ok(out)
+}
+
 /// Format for export: each line is a decision with its links
-pub fn format_export_line(decision: &Decision, links: &[Link]) -> Result<String> {
-    let v = decision_to_value(decision, Some(links));
+pub fn format_export_line(
+    decision: &Decision,
+    links: &[Link],
+    transitions: &[Transition],
+) -> Result<String> {
+    let mut v = decision_to_value(decision, Some(links));
+    if !transitions.is_empty() {
+        if let Value::Object(ref mut map) = v {
+            map.insert("transitions".to_string(), serde_json::to_value(transitions)?);
+        }
+    }
     Ok(serde_json::to_string(&v)?)
 }
+
+pub fn neighborhood_to_value(neighborhood: &Neighborhood) -> Value {
+    let decisions: Vec<Value> = neighborhood
+        .decisions
+        .iter()
+        .map(|d| {
+            let mut v = decision_to_value(d, None);
+            if let Value::Object(ref mut map) = v {
+                map.insert("hop".to_string(), Value::from(neighborhood.hop_of(&d.id)));
+            }
+            v
+        })
+        .collect();
+    serde_json::json!({
+        "root": neighborhood.root,
+        "depth": neighborhood.depth,
+        "decisions": decisions,
+        "links": serde_json::to_value(&neighborhood.links).unwrap_or(Value::Array(vec![])),
+    })
+}
+
+pub fn format_neighborhood(neighborhood: &Neighborhood) -> Result<String> {
+    Ok(serde_json::to_string_pretty(&neighborhood_to_value(neighborhood))?)
+}
+
+pub fn format_neighborhood_jsonl(neighborhood: &Neighborhood) -> Result<String> {
+    Ok(serde_json::to_string(&neighborhood_to_value(neighborhood))?)
+}
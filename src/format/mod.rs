@@ -1,8 +1,11 @@
+pub mod graph;
 pub mod json;
+pub mod template;
 pub mod text;
 pub mod tree;
 
-use crate::error::Result;
+use crate::db::{Neighborhood, SearchHit};
+use crate::error::{DictumError, Result};
 use crate::model::{Decision, Link};
 
 #[derive(Debug, Clone, PartialEq)]
@@ -11,6 +14,8 @@ pub enum OutputFormat {
     Json,
     Jsonl,
     Compact,
+    Mermaid,
+    Dot,
 }
 
 impl OutputFormat {
@@ -20,6 +25,8 @@ impl OutputFormat {
             Some("jsonl") => OutputFormat::Jsonl,
             Some("text") => OutputFormat::Text,
             Some("compact") => OutputFormat::Compact,
+            Some("mermaid") => OutputFormat::Mermaid,
+            Some("dot") => OutputFormat::Dot,
             _ => {
                 if is_tty {
                     OutputFormat::Text
@@ -36,6 +43,8 @@ pub fn format_decision(decision: &Decision, links: &[Link], format: &OutputForma
         OutputFormat::Text => Ok(text::format_decision(decision, links)),
         OutputFormat::Json | OutputFormat::Compact => json::format_decision(decision, links),
         OutputFormat::Jsonl => json::format_decision_jsonl(decision),
+        OutputFormat::Mermaid => Ok(graph::format_mermaid(std::slice::from_ref(decision), links)),
+        OutputFormat::Dot => Ok(graph::format_dot(std::slice::from_ref(decision), links)),
     }
 }
 
@@ -44,5 +53,86 @@ pub fn format_decision_list(decisions: &[Decision], format: &OutputFormat) -> Re
         OutputFormat::Text => Ok(text::format_decision_list(decisions)),
         OutputFormat::Json | OutputFormat::Compact => json::format_decision_list(decisions),
         OutputFormat::Jsonl => json::format_decision_list_jsonl(decisions),
+        OutputFormat::Mermaid => Ok(graph::format_mermaid(decisions, &[])),
+        OutputFormat::Dot => Ok(graph::format_dot(decisions, &[])),
+    }
+}
+
+/// Decision fields selectable with `--fields`, by their serialized names.
+pub const DECISION_FIELDS: &[&str] = &[
+    "id",
+    "title",
+    "body",
+    "level",
+    "status",
+    "kind",
+    "weight",
+    "scope",
+    "rebuttal",
+    "labels",
+    "author",
+    "superseded_by",
+    "created_at",
+    "updated_at",
+];
+
+/// Parse a comma-separated `--fields` list; `created`, `updated` and `label`
+/// are accepted for `created_at`, `updated_at` and `labels`.
+pub fn parse_fields(spec: &str) -> Result<Vec<&'static str>> {
+    spec.split(',')
+        .map(str::trim)
+        .filter(|f| !f.is_empty())
+        .map(|f| {
+            let name = match f {
+                "created" => "created_at",
+                "updated" => "updated_at",
+                "label" => "labels",
+                other => other,
+            };
+            DECISION_FIELDS
+                .iter()
+                .copied()
+                .find(|known| *known == name)
+                .ok_or_else(|| DictumError::InvalidField(f.to_string()))
+        })
+        .collect()
+}
+
+/// Like `format_decision_list`, restricted to `fields`. Graph formats ignore the selection.
+pub fn format_decision_fields(
+    decisions: &[Decision],
+    fields: &[&str],
+    format: &OutputFormat,
+) -> Result<String> {
+    match format {
+        OutputFormat::Text => Ok(text::format_decision_fields(decisions, fields)),
+        OutputFormat::Json | OutputFormat::Compact => json::format_decision_fields(decisions, fields),
+        OutputFormat::Jsonl => json::format_decision_fields_jsonl(decisions, fields),
+        OutputFormat::Mermaid | OutputFormat::Dot => format_decision_list(decisions, format),
+    }
+}
+
+pub fn format_search_hits(hits: &[SearchHit], format: &OutputFormat) -> Result<String> {
+    match format {
+        OutputFormat::Text => Ok(text::format_search_hits(hits)),
+        OutputFormat::Json | OutputFormat::Compact => json::format_search_hits(hits),
+        OutputFormat::Jsonl => json::format_search_hits_jsonl(hits),
+        OutputFormat::Mermaid | OutputFormat::Dot => {
+            let decisions: Vec<Decision> = hits.iter().map(|h| h.decision.clone()).collect();
+            format_decision_list(&decisions, format)
+        }
+    }
+}
+
+pub fn format_neighborhood(neighborhood: &Neighborhood, format: &OutputFormat) -> Result<String> {
+    match format {
+        OutputFormat::Text => Ok(text::format_neighborhood(neighborhood)),
+        OutputFormat::Json | OutputFormat::Compact => json::format_neighborhood(neighborhood),
+        OutputFormat::Jsonl => json::format_neighborhood_jsonl(neighborhood),
+        OutputFormat::Mermaid => Ok(graph::format_mermaid(
+            &neighborhood.decisions,
+            &neighborhood.links,
+        )),
+        OutputFormat::Dot => Ok(graph::format_dot(&neighborhood.decisions, &neighborhood.links)),
     }
 }
@@ -0,0 +1,36 @@
+//! User templates, as `report`, `context` and `sync-instructions` take them:
+//! a file holding `/*__DICTUM_DATA__*/null` once, which is replaced with what
+//! the command would otherwise print. The placeholder is a valid JavaScript
+//! expression, so an HTML template still opens on its own.
+
+use crate::error::{DictumError, Result};
+
+pub const PLACEHOLDER: &str = "/*__DICTUM_DATA__*/null";
+
+/// Put `content` in place of the placeholder in `template`, read from `name`.
+pub fn fill(template: &str, name: &str, content: &str) -> Result<String> {
+    if !template.contains(PLACEHOLDER) {
+        return Err(DictumError::InvalidTemplate(name.to_string()));
+    }
+    Ok(template.replacen(PLACEHOLDER, content, 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fills_the_first_placeholder_only() {
+        let template = format!("<pre>{}</pre><!-- {} -->", PLACEHOLDER, PLACEHOLDER);
+        assert_eq!(
+            fill(&template, "t.html", "[1]").unwrap(),
+            format!("<pre>[1]</pre><!-- {} -->", PLACEHOLDER)
+        );
+    }
+
+    #[test]
+    fn missing_placeholder_names_the_template() {
+        let err = fill("{{decisions}}", "t.tmpl", "[]").unwrap_err();
+        assert!(err.to_string().contains("t.tmpl"));
+    }
+}
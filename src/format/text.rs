@@ -1,3 +1,6 @@
+use serde_json::Value;
+
+use crate::db::{Neighborhood, SearchHit};
 use crate::model::{Decision, Link};
 
 pub fn format_decision(decision: &Decision, links: &[Link]) -> String {
@@ -32,6 +35,15 @@ pub fn format_decision(decision: &Decision, links: &[Link]) -> String {
         out.push_str(&format!("  Labels: {}\n", decision.labels.join(", ")));
     }
 
+    if let Some(ref origin) = decision.origin {
+        if !origin.to_string().is_empty() {
+            out.push_str(&format!("  Proposed: {}\n", origin));
+        }
+        if let Some(ref rejection) = origin.rejection {
+            out.push_str(&format!("  Rejected: {}\n", rejection));
+        }
+    }
+
     if let Some(ref body) = decision.body {
         out.push_str(&format!("\n  {}\n", body));
     }
@@ -76,3 +88,115 @@ pub fn format_decision_list(decisions: &[Decision]) -> String {
     }
     out
 }
+
+/// One `|`-separated line per decision with only the selected fields.
+pub fn format_decision_fields(decisions: &[Decision], fields: &[&str]) -> String {
+    if decisions.is_empty() {
+        return "No decisions found.\n".to_string();
+    }
+
+    let mut out = String::new();
+    for d in decisions {
+        let cells: Vec<String> = super::json::field_values(d, fields)
+            .into_iter()
+            .map(|v| match v {
+                Value::Null => "-".to_string(),
+                Value::String(s) => s,
+                Value::Array(items) => items
+                    .iter()
+                    .map(|i| i.as_str().map(str::to_string).unwrap_or_else(|| i.to_string()))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                other => other.to_string(),
+            })
+            .collect();
+        out.push_str(&cells.join(" | "));
+        out.push('\n');
+    }
+    out
+}
+
+pub fn format_search_hits(hits: &[SearchHit]) -> String {
+    if hits.is_empty() {
+        return "No decisions found.\n".to_string();
+    }
+
+    let mut out = String::new();
+    for hit in hits {
+        let d = &hit.decision;
+        out.push_str(&format!(
+            "{} | {:6.2} | {:10} | {:8} | {}\n",
+            d.id, hit.score, d.status, hit.field, d.title
+        ));
+        out.push_str(&format!("    {}\n", hit.snippet));
+    }
+    out
+}
+
+/// Plain aligned table with a header row and a row count.
+#[cfg(feature = "grafeo")]
+pub fn format_table(columns: &[String], rows: &[Vec<String>]) -> String {
+    let mut widths: Vec<usize> = columns.iter().map(|c| c.chars().count()).collect();
+    for row in rows {
+        for (w, cell) in widths.iter_mut().zip(row) {
+            *w = (*w).max(cell.chars().count());
+        }
+    }
+
+    let line = |cells: &[String]| -> String {
+        let padded: Vec<String> = cells
+            .iter()
+            .zip(&widths)
+            .map(|(c, w)| format!("{:w$}", c, w = *w))
+            .collect();
+        format!("{}\n", padded.join(" | ").trim_end())
+    };
+
+    let mut out = line(columns);
+    let rule: Vec<String> = widths.iter().map(|w| "-".repeat(*w)).collect();
+    out.push_str(&format!("{}\n", rule.join("-+-")));
+    for row in rows {
+        out.push_str(&line(row));
+    }
+    out.push_str(&format!(
+        "({} row{})\n",
+        rows.len(),
+        if rows.len() == 1 { "" } else { "s" }
+    ));
+    out
+}
+
+pub fn format_neighborhood(neighborhood: &Neighborhood) -> String {
+    let mut out = String::new();
+
+    for (i, d) in neighborhood.decisions.iter().enumerate() {
+        let hop = neighborhood.hop_of(&d.id);
+        if i == 0 || neighborhood.hop_of(&neighborhood.decisions[i - 1].id) != hop {
+            if hop == 0 {
+                out.push_str("Root:\n");
+            } else {
+                out.push_str(&format!("\nHop {}:\n", hop));
+            }
+        }
+        out.push_str(&format!(
+            "  [{}] ({}/{}, {}) {}\n",
+            d.id, d.kind, d.weight, d.status, d.title
+        ));
+    }
+
+    if !neighborhood.links.is_empty() {
+        out.push_str("\nLinks:\n");
+        for link in &neighborhood.links {
+            out.push_str(&format!(
+                "  {} {} {}",
+                link.source_id, link.kind, link.target_id
+            ));
+            if let Some(ref reason) = link.reason {
+                out.push_str(&format!(" ({})", reason));
+            }
+            out.push('\n');
+        }
+    }
+
+    out
+}
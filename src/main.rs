@@ -1,3 +1,4 @@
+mod budget;
 mod cli;
 mod config;
 mod db;
@@ -5,6 +6,9 @@ mod error;
 mod format;
 mod id;
 mod model;
+mod reasoning;
+mod relevance;
+mod similarity;
 #[cfg(feature = "tui")]
 mod tui;
 
@@ -22,7 +26,11 @@ struct Cli {
 #[derive(Subcommand)]
 enum Commands {
     /// Initialize .dictum/ in current directory
-    Init,
+    Init {
+        /// Also register the dictum merge driver with git (safe to rerun)
+        #[arg(long)]
+        git: bool,
+    },
 
     /// Manage decisions
     Decision {
@@ -38,18 +46,215 @@ enum Commands {
 
     /// Dump active decisions as compact context for LLM agents
     Context {
-        /// Output format: text, json, compact
+        /// Output format: text, json, compact, markdown, xml
         #[arg(long)]
         format: Option<String>,
+        /// Template file whose /*__DICTUM_DATA__*/null is replaced with the context (JSON unless --format)
+        #[arg(long)]
+        template: Option<String>,
         /// Filter by kind
         #[arg(long)]
         kind: Option<String>,
         /// Filter by weight
         #[arg(long)]
         weight: Option<String>,
-        /// Filter by scope
+        /// Filter by scope; includes enclosing scopes and unscoped decisions
+        #[arg(long)]
+        scope: Option<String>,
+        /// Match --scope exactly, without inherited scopes
+        #[arg(long)]
+        exact: bool,
+        /// Filter expression, e.g. "kind in (rule, constraint) and not scope:legacy*"
+        #[arg(long = "where", value_name = "EXPR")]
+        where_expr: Option<String>,
+        /// Apply a saved view; explicit flags override its values
+        #[arg(long)]
+        view: Option<String>,
+        /// Materialize decisions entailed by active must-decisions
+        #[arg(long)]
+        entailments: bool,
+        /// Use the decisions at a past time: RFC3339, YYYY-MM-DD, or a git tag/commit
+        #[arg(long, value_name = "DATE|REV")]
+        as_of: Option<String>,
+        /// Keep only the highest-priority decisions that fit in about N tokens
+        #[arg(long, value_name = "N")]
+        max_tokens: Option<usize>,
+        /// Only decisions relevant to this task, ranked, with their governing parents
+        #[arg(long)]
+        task: Option<String>,
+        /// Only changes since a time, git revision, or the token from an earlier call
+        #[arg(long, value_name = "DATE|REV|TOKEN")]
+        since: Option<String>,
+    },
+
+
+    /// Manage saved views (named list presets)
+    View {
+        #[command(subcommand)]
+        command: ViewCommands,
+    },
+
+    /// Browse the scope hierarchy
+    Scope {
+        #[command(subcommand)]
+        command: ScopeCommands,
+    },
+
+    /// Find decisions similar to a decision or a piece of text
+    Similar {
+        /// Decision ID, or free text to compare against
+        query: String,
+        /// Number of results
+        #[arg(long, default_value_t = 5)]
+        top: usize,
+        /// Include deprecated, superseded and draft decisions
+        #[arg(long)]
+        all: bool,
+        /// Output format: text, json, jsonl
+        #[arg(long)]
+        format: Option<String>,
+    },
+
+    /// Find the active decisions that answer a question, or say that none does
+    Ask {
+        /// The question, e.g. "should we cache sessions in Redis?"
+        question: String,
+        /// Only decisions that apply at this scope
         #[arg(long)]
         scope: Option<String>,
+        /// Maximum number of decisions
+        #[arg(long, default_value_t = 5)]
+        top: usize,
+        /// Relevance (0-1) a decision needs to count as governing
+        #[arg(long, default_value_t = cli::ask::MIN_SCORE)]
+        min_score: f64,
+        /// Use the decisions at a past time: RFC3339, YYYY-MM-DD, or a git tag/commit
+        #[arg(long, value_name = "DATE|REV")]
+        as_of: Option<String>,
+        /// Output format: text, json
+        #[arg(long)]
+        format: Option<String>,
+    },
+
+    /// Check whether a set of decisions can be adopted together
+    CheckConsistency {
+        /// Candidate decision IDs
+        ids: Vec<String>,
+        /// Draft decisions in export JSONL format ("-" for stdin)
+        #[arg(long)]
+        draft: Option<String>,
+        /// Output format: text, json
+        #[arg(long)]
+        format: Option<String>,
+    },
+
+    /// Report active decisions implicated by a diff (default: `git diff`)
+    CheckDiff {
+        /// Unified diff to read instead of running git ("-" for stdin)
+        file: Option<String>,
+        /// Diff the staged changes (`git diff --cached`)
+        #[arg(long)]
+        staged: bool,
+        /// Exit non-zero when a must-weight decision is implicated
+        #[arg(long)]
+        strict: bool,
+        /// Output format: text, json
+        #[arg(long)]
+        format: Option<String>,
+    },
+
+    /// Run a raw GQL query against the decision graph (read-only by default)
+    #[cfg(feature = "grafeo")]
+    Gql {
+        /// GQL query, e.g. "MATCH (d:Decision) RETURN d.id, d.title"
+        query: String,
+        /// Query parameter as key=value (value parsed as JSON when possible); repeatable
+        #[arg(long = "param", value_name = "KEY=VALUE")]
+        params: Vec<String>,
+        /// Allow statements that modify the store
+        #[arg(long)]
+        write: bool,
+        /// Output format: table, json, jsonl
+        #[arg(long)]
+        format: Option<String>,
+    },
+
+    /// Propose a decision for review; it stays a draft until accepted
+    Propose {
+        #[command(flatten)]
+        fields: DecisionFields,
+        /// Name of the agent proposing it
+        #[arg(long)]
+        agent: Option<String>,
+        /// Agent session or conversation ID
+        #[arg(long)]
+        session: Option<String>,
+        /// File or diff that prompted the proposal
+        #[arg(long)]
+        source: Option<String>,
+        /// Output format: text, json, jsonl
+        #[arg(long)]
+        format: Option<String>,
+    },
+
+    /// List proposals waiting for review
+    Inbox {
+        /// List rejected proposals instead
+        #[arg(long)]
+        rejected: bool,
+        /// Output format: text, json, jsonl
+        #[arg(long)]
+        format: Option<String>,
+    },
+
+    /// Accept a proposal, optionally editing it first
+    Accept {
+        /// Proposal ID
+        id: String,
+        /// Edit the decision statement
+        #[arg(long)]
+        title: Option<String>,
+        /// Edit the rationale
+        #[arg(long)]
+        body: Option<String>,
+        /// Edit the proposition kind
+        #[arg(long)]
+        kind: Option<String>,
+        /// Edit the obligation weight
+        #[arg(long)]
+        weight: Option<String>,
+        /// Edit the rebuttal condition
+        #[arg(long)]
+        rebuttal: Option<String>,
+        /// Edit the scope
+        #[arg(long)]
+        scope: Option<String>,
+        /// Output format: text, json, jsonl
+        #[arg(long)]
+        format: Option<String>,
+    },
+
+    /// Reject a proposal; it is kept so it is not proposed again
+    Reject {
+        /// Proposal ID
+        id: String,
+        /// Why it was rejected, shown to agents that propose it again
+        #[arg(long)]
+        reason: Option<String>,
+        /// Output format: text, json, jsonl
+        #[arg(long)]
+        format: Option<String>,
+    },
+
+    /// Serve decisions to agents over the Model Context Protocol (stdio)
+    Mcp,
+
+    /// Render decisions into agent instruction files (AGENTS.md, CLAUDE.md, ...)
+    /// configured under [[instructions]] in .dictum/config.toml
+    SyncInstructions {
+        /// Write nothing; fail if any file is out of date
+        #[arg(long)]
+        check: bool,
     },
 
     /// Interactive terminal UI for browsing decisions
@@ -74,6 +279,9 @@ enum Commands {
         /// Custom HTML template file
         #[arg(long)]
         template: Option<String>,
+        /// Report the state at a past time: RFC3339, YYYY-MM-DD, or a git tag/commit
+        #[arg(long, value_name = "DATE|REV")]
+        as_of: Option<String>,
     },
 
     /// Import from JSONL
@@ -81,9 +289,47 @@ enum Commands {
         /// Input file (default: stdin)
         #[arg(short)]
         i: Option<String>,
+        /// For decisions already stored: upsert (newer wins, status never regresses), skip, or overwrite
+        #[arg(long, default_value = "upsert")]
+        mode: String,
         /// Preview only
         #[arg(long)]
         dry_run: bool,
+        /// Output format for the report: text, json
+        #[arg(long)]
+        format: Option<String>,
+    },
+
+    /// Recreate the database by replaying .dictum/ops.jsonl
+    Rebuild {
+        /// Output format: text, json
+        #[arg(long)]
+        format: Option<String>,
+    },
+
+    /// Compare two snapshots: `store`, a JSONL export, or a git revision of one
+    Diff {
+        /// Older side: store, FILE, REV, or REV:PATH
+        from: String,
+        /// Newer side (default: store)
+        #[arg(default_value = "store")]
+        to: String,
+        /// Export path inside a bare REV (default: `export` in config.toml)
+        #[arg(long)]
+        file: Option<String>,
+        /// Output format: text, json, markdown
+        #[arg(long)]
+        format: Option<String>,
+    },
+
+    /// Three-way merge of dictum JSONL files, for git (see `init --git`)
+    MergeDriver {
+        /// Common ancestor (%O)
+        base: String,
+        /// Our version, overwritten with the result (%A)
+        ours: String,
+        /// Their version (%B)
+        theirs: String,
     },
 }
 
@@ -91,47 +337,29 @@ enum Commands {
 enum DecisionCommands {
     /// Add a decision
     Add {
-        /// Decision statement
-        title: String,
-        /// Level: strategic, tactical, or operational
-        #[arg(long, default_value = "tactical")]
-        level: String,
-        /// Parent decision ID (creates a "refines" link)
-        #[arg(long)]
-        parent: Option<String>,
-        /// Label(s) to tag the decision
-        #[arg(long)]
-        label: Vec<String>,
-        /// Additional context or rationale
-        #[arg(long)]
-        body: Option<String>,
-        /// Author name
-        #[arg(long)]
-        author: Option<String>,
+        #[command(flatten)]
+        fields: DecisionFields,
         /// Output format: text, json, jsonl
         #[arg(long)]
         format: Option<String>,
-        /// Proposition kind: principle, constraint, assumption, choice, rule, goal
-        #[arg(long, default_value = "choice")]
-        kind: String,
-        /// Obligation weight: must, should, may
-        #[arg(long, default_value = "should")]
-        weight: String,
-        /// Condition under which this decision can be overridden
+        /// Refuse to add a near-duplicate of an active decision instead of warning
         #[arg(long)]
-        rebuttal: Option<String>,
-        /// Scope this decision applies to
-        #[arg(long)]
-        scope: Option<String>,
+        strict: bool,
     },
 
     /// Show a decision and its links
     Show {
         /// Decision ID
         id: String,
-        /// Output format: text, json, jsonl
+        /// Show the N-hop neighborhood instead of direct links
+        #[arg(long)]
+        depth: Option<u32>,
+        /// Output format: text, json, jsonl, mermaid, dot
         #[arg(long)]
         format: Option<String>,
+        /// Show the state at a past time: RFC3339, YYYY-MM-DD, or a git tag/commit
+        #[arg(long, value_name = "DATE|REV")]
+        as_of: Option<String>,
     },
 
     /// List decisions
@@ -157,13 +385,41 @@ enum DecisionCommands {
         /// Filter by weight
         #[arg(long)]
         weight: Option<String>,
-        /// Filter by scope
+        /// Filter by scope; includes enclosing scopes and unscoped decisions
         #[arg(long)]
         scope: Option<String>,
+        /// Match --scope exactly, without inherited scopes
+        #[arg(long)]
+        exact: bool,
+        /// Filter expression, e.g. "kind in (rule, constraint) and not scope:legacy*"
+        #[arg(long = "where", value_name = "EXPR")]
+        where_expr: Option<String>,
+        /// Sort order: created, updated, level, weight, kind, title, status or id, with :asc or :desc
+        #[arg(long, value_name = "FIELD[:asc|desc]")]
+        sort: Option<String>,
+        /// Return at most N decisions
+        #[arg(long, value_name = "N")]
+        limit: Option<usize>,
+        /// Skip the first N decisions
+        #[arg(long, value_name = "N", default_value_t = 0)]
+        offset: usize,
+        /// Only output these fields, e.g. id,title,weight
+        #[arg(long, value_name = "FIELDS")]
+        fields: Option<String>,
+        /// Apply a saved view; explicit flags override its values
+        #[arg(long)]
+        view: Option<String>,
+        /// Show the state at a past time: RFC3339, YYYY-MM-DD, or a git tag/commit
+        #[arg(long, value_name = "DATE|REV")]
+        as_of: Option<String>,
     },
 
     /// Visual tree of decisions (refines hierarchy)
-    Tree,
+    Tree {
+        /// Show the state at a past time: RFC3339, YYYY-MM-DD, or a git tag/commit
+        #[arg(long, value_name = "DATE|REV")]
+        as_of: Option<String>,
+    },
 
     /// Supersede a decision with a new one
     Amend {
@@ -206,8 +462,11 @@ enum DecisionCommands {
 
     /// Search decisions
     Query {
-        /// Search text
+        /// Search text: words, "phrases", prefix*, optionally field-scoped (title:cache)
         question: String,
+        /// Only return decisions with this status
+        #[arg(long)]
+        status: Option<String>,
         /// Output format: text, json, jsonl
         #[arg(long)]
         format: Option<String>,
@@ -240,69 +499,164 @@ enum LinkCommands {
     },
 }
 
+#[derive(Subcommand)]
+enum ViewCommands {
+    /// List saved views
+    List {
+        /// Output format: text, json, jsonl
+        #[arg(long)]
+        format: Option<String>,
+    },
+
+    /// Save (or replace) a view
+    Save {
+        /// View name
+        name: String,
+        #[command(flatten)]
+        view: Box<ViewArgs>,
+    },
+
+    /// Delete a saved view
+    Delete {
+        /// View name
+        name: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum ScopeCommands {
+    /// Show the scope tree with decision counts
+    List {
+        /// Count deprecated, superseded and draft decisions too
+        #[arg(long)]
+        all: bool,
+        /// Output format: text, json
+        #[arg(long)]
+        format: Option<String>,
+    },
+}
+
+#[derive(clap::Args)]
+struct DecisionFields {
+    /// Decision statement
+    title: String,
+    /// Level: strategic, tactical, or operational
+    #[arg(long, default_value = "tactical")]
+    level: String,
+    /// Parent decision ID (creates a "refines" link)
+    #[arg(long)]
+    parent: Option<String>,
+    /// Label(s) to tag the decision
+    #[arg(long)]
+    label: Vec<String>,
+    /// Additional context or rationale
+    #[arg(long)]
+    body: Option<String>,
+    /// Author name
+    #[arg(long)]
+    author: Option<String>,
+    /// Proposition kind: principle, constraint, assumption, choice, rule, goal
+    #[arg(long, default_value = "choice")]
+    kind: String,
+    /// Obligation weight: must, should, may
+    #[arg(long, default_value = "should")]
+    weight: String,
+    /// Condition under which this decision can be overridden
+    #[arg(long)]
+    rebuttal: Option<String>,
+    /// Scope this decision applies to
+    #[arg(long)]
+    scope: Option<String>,
+}
+
+impl DecisionFields {
+    fn into_add_args(self, format: Option<String>, strict: bool, origin: Option<model::Origin>) -> cli::add::AddArgs {
+        let (level, kind, weight) = match (self.level.parse(), self.kind.parse(), self.weight.parse()) {
+            (Ok(l), Ok(k), Ok(w)) => (l, k, w),
+            (Err(e), _, _) | (_, Err(e), _) | (_, _, Err(e)) => {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+        };
+        cli::add::AddArgs {
+            title: self.title,
+            level,
+            parent: self.parent,
+            label: self.label,
+            body: self.body,
+            author: self.author,
+            format,
+            kind,
+            weight,
+            rebuttal: self.rebuttal,
+            scope: self.scope,
+            strict,
+            origin,
+        }
+    }
+}
+
+#[derive(clap::Args)]
+struct ViewArgs {
+    /// Show as hierarchy (refines links)
+    #[arg(long)]
+    tree: bool,
+    /// Filter by level
+    #[arg(long)]
+    level: Option<String>,
+    /// Filter by status
+    #[arg(long)]
+    status: Option<String>,
+    /// Filter by label
+    #[arg(long)]
+    label: Option<String>,
+    /// Filter by kind
+    #[arg(long)]
+    kind: Option<String>,
+    /// Filter by weight
+    #[arg(long)]
+    weight: Option<String>,
+    /// Filter by scope
+    #[arg(long)]
+    scope: Option<String>,
+    /// Match --scope exactly, without inherited scopes
+    #[arg(long)]
+    exact: bool,
+    /// Filter expression
+    #[arg(long = "where", value_name = "EXPR")]
+    where_expr: Option<String>,
+    /// Sort order: field[:asc|desc]
+    #[arg(long)]
+    sort: Option<String>,
+    /// Only output these fields, e.g. id,title,weight
+    #[arg(long)]
+    fields: Option<String>,
+    /// Output format used by the view
+    #[arg(long)]
+    format: Option<String>,
+}
+
 fn main() {
     let cli = Cli::parse();
     let cwd = std::env::current_dir().expect("cannot determine current directory");
     let is_tty = std::io::stdout().is_terminal();
 
     let result = match cli.command {
-        Commands::Init => cli::init::run(&cwd),
+        Commands::Init { git } => cli::init::run_with_git(&cwd, git),
 
         Commands::Decision { command } => match command {
             DecisionCommands::Add {
-                title,
-                level,
-                parent,
-                label,
-                body,
-                author,
+                fields,
                 format,
-                kind,
-                weight,
-                rebuttal,
-                scope,
-            } => {
-                let level = match level.parse() {
-                    Ok(l) => l,
-                    Err(e) => {
-                        eprintln!("Error: {}", e);
-                        std::process::exit(1);
-                    }
-                };
-                let kind = match kind.parse() {
-                    Ok(k) => k,
-                    Err(e) => {
-                        eprintln!("Error: {}", e);
-                        std::process::exit(1);
-                    }
-                };
-                let weight = match weight.parse() {
-                    Ok(w) => w,
-                    Err(e) => {
-                        eprintln!("Error: {}", e);
-                        std::process::exit(1);
-                    }
-                };
-                cli::add::run(
-                    &cwd,
-                    cli::add::AddArgs {
-                        title,
-                        level,
-                        parent,
-                        label,
-                        body,
-                        author,
-                        format,
-                        kind,
-                        weight,
-                        rebuttal,
-                        scope,
-                    },
-                    is_tty,
-                )
-            }
+                strict,
+            } => cli::add::run(&cwd, fields.into_add_args(format, strict, None), is_tty),
 
-            DecisionCommands::Show { id, format } => cli::show::run(&cwd, &id, format, is_tty),
+            DecisionCommands::Show {
+                id,
+                depth,
+                format,
+                as_of,
+            } => cli::show::run(&cwd, &id, depth, format, as_of, is_tty),
 
             DecisionCommands::List {
                 tree,
@@ -313,6 +667,14 @@ fn main() {
                 kind,
                 weight,
                 scope,
+                exact,
+                where_expr,
+                sort,
+                limit,
+                offset,
+                fields,
+                view,
+                as_of,
             } => cli::list::run(
                 &cwd,
                 cli::list::ListArgs {
@@ -324,11 +686,19 @@ fn main() {
                     kind,
                     weight,
                     scope,
+                    exact,
+                    where_expr,
+                    sort,
+                    limit,
+                    offset,
+                    fields,
+                    view,
+                    as_of,
                 },
                 is_tty,
             ),
 
-            DecisionCommands::Tree => cli::list::run_tree(&cwd),
+            DecisionCommands::Tree { as_of } => cli::list::run_tree(&cwd, as_of),
 
             DecisionCommands::Amend {
                 id,
@@ -374,9 +744,11 @@ fn main() {
                 cli::amend::run_deprecate(&cwd, &id, reason, format, is_tty)
             }
 
-            DecisionCommands::Query { question, format } => {
-                cli::query::run(&cwd, &question, format, is_tty)
-            }
+            DecisionCommands::Query {
+                question,
+                status,
+                format,
+            } => cli::query::run(&cwd, &question, status, format, is_tty),
         },
 
         Commands::Link { command } => match command {
@@ -399,6 +771,15 @@ fn main() {
             kind,
             weight,
             scope,
+            exact,
+            where_expr,
+            view,
+            entailments,
+            as_of,
+            max_tokens,
+            task,
+            since,
+            template,
         } => {
             let kind = kind
                 .map(|k| k.parse())
@@ -421,17 +802,223 @@ fn main() {
                     kind,
                     weight,
                     scope,
+                    exact,
+                    where_expr,
+                    view,
+                    entailments,
+                    as_of,
+                    max_tokens,
+                    task,
+                    since,
+                    template,
                 },
                 is_tty,
             )
         }
 
+        Commands::View { command } => match command {
+            ViewCommands::List { format } => cli::view::run_list(&cwd, format, is_tty),
+            ViewCommands::Save { name, view } => {
+                let ViewArgs {
+                    tree,
+                    level,
+                    status,
+                    label,
+                    kind,
+                    weight,
+                    scope,
+                    exact,
+                    where_expr,
+                    sort,
+                    fields,
+                    format,
+                } = *view;
+                cli::view::run_save(
+                    &cwd,
+                    &name,
+                    config::View {
+                        level,
+                        status,
+                        label,
+                        kind,
+                        weight,
+                        scope,
+                        exact,
+                        where_expr,
+                        sort,
+                        fields,
+                        format,
+                        tree,
+                    },
+                )
+            }
+            ViewCommands::Delete { name } => cli::view::run_delete(&cwd, &name),
+        },
+
+        Commands::Scope { command } => match command {
+            ScopeCommands::List { all, format } => cli::scope::run_list(&cwd, all, format, is_tty),
+        },
+
+        Commands::Similar {
+            query,
+            top,
+            all,
+            format,
+        } => cli::similar::run(&cwd, &query, top, all, format, is_tty),
+
+        Commands::Ask {
+            question,
+            scope,
+            top,
+            min_score,
+            as_of,
+            format,
+        } => cli::ask::run(
+            &cwd,
+            cli::ask::AskArgs {
+                question,
+                scope,
+                top,
+                min_score,
+                as_of,
+                format,
+            },
+            is_tty,
+        ),
+
+        Commands::CheckConsistency { ids, draft, format } => {
+            cli::check::run_consistency(&cwd, &ids, draft, format, is_tty)
+        }
+
+        Commands::CheckDiff {
+            file,
+            staged,
+            strict,
+            format,
+        } => cli::check::run_diff(&cwd, file, staged, strict, format, is_tty),
+
+        #[cfg(feature = "grafeo")]
+        Commands::Gql {
+            query,
+            params,
+            write,
+            format,
+        } => cli::gql::run(
+            &cwd,
+            cli::gql::GqlArgs {
+                query,
+                params,
+                write,
+                format,
+            },
+            is_tty,
+        ),
+
+        Commands::Propose {
+            fields,
+            agent,
+            session,
+            source,
+            format,
+        } => cli::proposal::run_propose(
+            &cwd,
+            fields.into_add_args(
+                format,
+                false,
+                Some(model::Origin {
+                    agent,
+                    session,
+                    source,
+                    rejection: None,
+                }),
+            ),
+            is_tty,
+        ),
+
+        Commands::Inbox { rejected, format } => cli::proposal::run_inbox(&cwd, rejected, format, is_tty),
+
+        Commands::Accept {
+            id,
+            title,
+            body,
+            kind,
+            weight,
+            rebuttal,
+            scope,
+            format,
+        } => {
+            let kind = kind
+                .map(|k| k.parse())
+                .transpose()
+                .unwrap_or_else(|e| {
+                    eprintln!("Error: {}", e);
+                    std::process::exit(1);
+                });
+            let weight = weight
+                .map(|w| w.parse())
+                .transpose()
+                .unwrap_or_else(|e| {
+                    eprintln!("Error: {}", e);
+                    std::process::exit(1);
+                });
+            cli::proposal::run_accept(
+                &cwd,
+                cli::amend::AmendArgs {
+                    id,
+                    title,
+                    body,
+                    format,
+                    kind,
+                    weight,
+                    rebuttal,
+                    scope,
+                },
+                is_tty,
+            )
+        }
+
+        Commands::Reject { id, reason, format } => cli::proposal::run_reject(&cwd, &id, reason, format, is_tty),
+
+        Commands::Mcp => cli::mcp::run(&cwd),
+
+        Commands::SyncInstructions { check } => cli::instructions::run(&cwd, check),
+
         #[cfg(feature = "tui")]
         Commands::Tui => tui::run(&cwd),
 
         Commands::Export { o } => cli::io::run_export(&cwd, o),
-        Commands::Report { all, o, template } => cli::report::run(&cwd, all, o, template),
-        Commands::Import { i, dry_run } => cli::io::run_import(&cwd, i, dry_run),
+        Commands::Report {
+            all,
+            o,
+            template,
+            as_of,
+        } => cli::report::run(&cwd, all, o, template, as_of),
+
+        Commands::Import {
+            i,
+            mode,
+            dry_run,
+            format,
+        } => {
+            let mode = match mode.parse() {
+                Ok(m) => m,
+                Err(e) => {
+                    eprintln!("Error: {}", e);
+                    std::process::exit(1);
+                }
+            };
+            cli::io::run_import(&cwd, i, mode, dry_run, format, is_tty)
+        }
+        Commands::Rebuild { format } => cli::io::run_rebuild(&cwd, format, is_tty),
+        Commands::Diff {
+            from,
+            to,
+            file,
+            format,
+        } => cli::diff::run(&cwd, &from, &to, file, format, is_tty),
+        Commands::MergeDriver { base, ours, theirs } => {
+            cli::merge::run(base.as_ref(), ours.as_ref(), theirs.as_ref())
+        },
     };
 
     if let Err(e) = result {
@@ -41,6 +41,8 @@ pub enum Status {
     Superseded,
     Deprecated,
     Draft,
+    /// A proposal turned down in review, kept so it is not proposed again
+    Rejected,
 }
 
 impl fmt::Display for Status {
@@ -50,6 +52,7 @@ impl fmt::Display for Status {
             Status::Superseded => write!(f, "superseded"),
             Status::Deprecated => write!(f, "deprecated"),
             Status::Draft => write!(f, "draft"),
+            Status::Rejected => write!(f, "rejected"),
         }
     }
 }
@@ -62,6 +65,7 @@ impl FromStr for Status {
             "superseded" => Ok(Status::Superseded),
             "deprecated" => Ok(Status::Deprecated),
             "draft" => Ok(Status::Draft),
+            "rejected" => Ok(Status::Rejected),
             _ => Err(DictumError::InvalidStatus(s.to_string())),
         }
     }
@@ -136,7 +140,7 @@ impl FromStr for Weight {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Decision {
     pub id: String,
     pub title: String,
@@ -159,6 +163,46 @@ pub struct Decision {
     pub rebuttal: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub scope: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub origin: Option<Origin>,
+}
+
+/// Where a proposed decision came from: the agent and session that proposed
+/// it and the file or diff that prompted it. Kept once the proposal is
+/// accepted or rejected.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct Origin {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub agent: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session: Option<String>,
+    /// File path or diff the proposal was drawn from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    /// Why the proposal was rejected, when it was
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rejection: Option<String>,
+}
+
+impl Origin {
+    pub fn is_empty(&self) -> bool {
+        *self == Origin::default()
+    }
+}
+
+/// `by coding-agent, session 7f3a, from src/cache.rs`; the rejection is left out.
+impl fmt::Display for Origin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let parts: Vec<String> = [
+            self.agent.as_ref().map(|a| format!("by {}", a)),
+            self.session.as_ref().map(|s| format!("session {}", s)),
+            self.source.as_ref().map(|s| format!("from {}", s)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        write!(f, "{}", parts.join(", "))
+    }
 }
 
 fn default_kind() -> Kind {
@@ -169,6 +213,129 @@ fn default_weight() -> Weight {
     Weight::Should
 }
 
+/// Test fixtures: `Decision::test("d-1")` is an active, tactical "should"
+/// choice titled "Decision d-1", and the `with_*` methods change one field.
+#[cfg(test)]
+impl Decision {
+    pub fn test(id: &str) -> Self {
+        Decision {
+            id: id.to_string(),
+            title: format!("Decision {}", id),
+            body: None,
+            level: Level::Tactical,
+            status: Status::Active,
+            superseded_by: None,
+            author: "test".to_string(),
+            created_at: "2025-01-01T00:00:00Z".to_string(),
+            updated_at: "2025-01-01T00:00:00Z".to_string(),
+            labels: Vec::new(),
+            kind: Kind::Choice,
+            weight: Weight::Should,
+            rebuttal: None,
+            scope: None,
+            origin: None,
+        }
+    }
+
+    pub fn with_title(mut self, title: &str) -> Self {
+        self.title = title.to_string();
+        self
+    }
+
+    pub fn with_body(mut self, body: &str) -> Self {
+        self.body = Some(body.to_string());
+        self
+    }
+
+    pub fn with_level(mut self, level: Level) -> Self {
+        self.level = level;
+        self
+    }
+
+    pub fn with_status(mut self, status: Status) -> Self {
+        self.status = status;
+        self
+    }
+
+    pub fn with_kind(mut self, kind: Kind) -> Self {
+        self.kind = kind;
+        self
+    }
+
+    pub fn with_weight(mut self, weight: Weight) -> Self {
+        self.weight = weight;
+        self
+    }
+
+    pub fn with_scope(mut self, scope: &str) -> Self {
+        self.scope = Some(scope.to_string());
+        self
+    }
+
+    pub fn with_labels(mut self, labels: &[&str]) -> Self {
+        self.labels = labels.iter().map(|l| l.to_string()).collect();
+        self
+    }
+
+    pub fn with_author(mut self, author: &str) -> Self {
+        self.author = author.to_string();
+        self
+    }
+
+    pub fn with_rebuttal(mut self, rebuttal: &str) -> Self {
+        self.rebuttal = Some(rebuttal.to_string());
+        self
+    }
+
+    /// Sets `updated_at` too, as a freshly added decision has.
+    pub fn with_created(mut self, at: &str) -> Self {
+        self.created_at = at.to_string();
+        self.updated_at = at.to_string();
+        self
+    }
+}
+
+/// A project in a fresh temporary directory, removed again when dropped.
+/// `TestProject::new()` runs `init` in it; `TestProject::empty()` does not.
+#[cfg(test)]
+pub struct TestProject {
+    pub root: std::path::PathBuf,
+}
+
+#[cfg(test)]
+impl TestProject {
+    pub fn new() -> Self {
+        let project = Self::empty();
+        crate::cli::init::run(&project.root).unwrap();
+        project
+    }
+
+    pub fn empty() -> Self {
+        static NEXT: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+        let n = NEXT.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        let root = std::env::temp_dir().join(format!("dictum-test-{}-{}", std::process::id(), n));
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(&root).unwrap();
+        TestProject { root }
+    }
+
+    pub fn dictum_dir(&self) -> std::path::PathBuf {
+        self.root.join(".dictum")
+    }
+
+    /// The database without the op log, as `db::open_cache` gives it.
+    pub fn open_cache(&self) -> Box<dyn crate::db::Store> {
+        crate::db::open_cache(&self.dictum_dir()).unwrap()
+    }
+}
+
+#[cfg(test)]
+impl Drop for TestProject {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.root);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -46,7 +46,7 @@ impl FromStr for LinkKind {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Link {
     pub source_id: String,
     pub target_id: String,
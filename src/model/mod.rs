@@ -1,5 +1,10 @@
 pub mod decision;
 pub mod link;
+pub mod scope;
+pub mod transition;
 
-pub use decision::{Decision, Kind, Level, Status, Weight};
+pub use decision::{Decision, Kind, Level, Origin, Status, Weight};
+#[cfg(test)]
+pub use decision::TestProject;
 pub use link::{Link, LinkKind};
+pub use transition::Transition;
@@ -0,0 +1,137 @@
+//! Scopes are slash-separated paths such as `billing/invoices`. A decision
+//! applies to its own scope and everything below it; a decision without a
+//! scope applies everywhere.
+
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+/// Trim whitespace and redundant slashes; `None` if nothing is left.
+pub fn normalize(scope: &str) -> Option<String> {
+    let segments: Vec<&str> = scope
+        .split('/')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .collect();
+    if segments.is_empty() {
+        None
+    } else {
+        Some(segments.join("/"))
+    }
+}
+
+/// The scope and every scope above it, outermost first:
+/// `billing/invoices` gives `["billing", "billing/invoices"]`.
+pub fn ancestors(scope: &str) -> Vec<String> {
+    let Some(scope) = normalize(scope) else {
+        return Vec::new();
+    };
+    let mut out = Vec::new();
+    for (i, c) in scope.char_indices() {
+        if c == '/' {
+            out.push(scope[..i].to_string());
+        }
+    }
+    out.push(scope);
+    out
+}
+
+#[derive(Debug, Serialize)]
+pub struct ScopeNode {
+    pub name: String,
+    pub path: String,
+    /// Decisions scoped exactly here
+    pub direct: usize,
+    /// Decisions scoped here or anywhere below
+    pub total: usize,
+    pub children: Vec<ScopeNode>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ScopeTree {
+    /// Decisions without a scope, which apply everywhere
+    pub global: usize,
+    pub scopes: Vec<ScopeNode>,
+}
+
+#[derive(Default)]
+struct Builder {
+    direct: usize,
+    children: BTreeMap<String, Builder>,
+}
+
+impl Builder {
+    fn finish(self, name: String, path: String) -> ScopeNode {
+        let children: Vec<ScopeNode> = self
+            .children
+            .into_iter()
+            .map(|(child, b)| {
+                let child_path = format!("{}/{}", path, child);
+                b.finish(child, child_path)
+            })
+            .collect();
+        ScopeNode {
+            name,
+            path,
+            direct: self.direct,
+            total: self.direct + children.iter().map(|c| c.total).sum::<usize>(),
+            children,
+        }
+    }
+}
+
+/// Count decisions per scope, nested by path and sorted by name.
+pub fn tree<'a>(scopes: impl IntoIterator<Item = Option<&'a str>>) -> ScopeTree {
+    let mut global = 0;
+    let mut root = Builder::default();
+    for scope in scopes {
+        let Some(scope) = scope.and_then(normalize) else {
+            global += 1;
+            continue;
+        };
+        let mut node = &mut root;
+        for segment in scope.split('/') {
+            node = node.children.entry(segment.to_string()).or_default();
+        }
+        node.direct += 1;
+    }
+    ScopeTree {
+        global,
+        scopes: root
+            .children
+            .into_iter()
+            .map(|(name, b)| b.finish(name.clone(), name))
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn paths_normalize_and_expand() {
+        assert_eq!(normalize(" /billing//invoices/ ").as_deref(), Some("billing/invoices"));
+        assert_eq!(normalize("/"), None);
+        assert_eq!(ancestors("billing/invoices/pdf"), ["billing", "billing/invoices", "billing/invoices/pdf"]);
+        assert!(ancestors("").is_empty());
+    }
+
+    #[test]
+    fn tree_counts_direct_and_total() {
+        let t = tree([
+            Some("billing"),
+            Some("billing/invoices"),
+            Some("billing/invoices"),
+            Some("auth"),
+            None,
+        ]);
+        assert_eq!(t.global, 1);
+        let names: Vec<&str> = t.scopes.iter().map(|n| n.name.as_str()).collect();
+        assert_eq!(names, ["auth", "billing"]);
+        let billing = &t.scopes[1];
+        assert_eq!((billing.direct, billing.total), (1, 3));
+        assert_eq!(billing.children[0].path, "billing/invoices");
+        assert_eq!(billing.children[0].direct, 2);
+    }
+}
@@ -0,0 +1,76 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::{Decision, Status};
+
+/// Fields whose changes are recorded in `from_fields`. Status and
+/// `superseded_by` have their own columns; labels only ever accumulate.
+const UNTRACKED: &[&str] = &["id", "status", "superseded_by", "labels", "updated_at"];
+
+/// A recorded change to a decision, kept so past states can be reconstructed
+/// (`--as-of`). A field edit keeps the status, so `from_status` and
+/// `to_status` are equal and `from_fields` holds what the edit replaced.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Transition {
+    pub decision_id: String,
+    pub from_status: Status,
+    pub to_status: Status,
+    /// `superseded_by` before the change
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from_superseded_by: Option<String>,
+    /// Previous values of the other fields the change touched, by their
+    /// serialized name; `null` for a field that was unset
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub from_fields: BTreeMap<String, Value>,
+    pub at: String,
+}
+
+impl Transition {
+    /// The transition that turns `old` into `new`, or `None` when nothing
+    /// recorded changed.
+    pub fn between(old: &Decision, new: &Decision, at: String) -> Option<Transition> {
+        let fields = |d: &Decision| match serde_json::to_value(d) {
+            Ok(Value::Object(map)) => map,
+            _ => unreachable!("decisions serialize to objects"),
+        };
+        let (before, after) = (fields(old), fields(new));
+        let from_fields: BTreeMap<String, Value> = before
+            .keys()
+            .chain(after.keys())
+            .filter(|name| !UNTRACKED.contains(&name.as_str()))
+            .filter(|name| before.get(*name) != after.get(*name))
+            .map(|name| (name.clone(), before.get(name).cloned().unwrap_or(Value::Null)))
+            .collect();
+        if from_fields.is_empty() && old.status == new.status && old.superseded_by == new.superseded_by {
+            return None;
+        }
+        Some(Transition {
+            decision_id: old.id.clone(),
+            from_status: old.status.clone(),
+            to_status: new.status.clone(),
+            from_superseded_by: old.superseded_by.clone(),
+            from_fields,
+            at,
+        })
+    }
+
+    /// Put back the fields this transition changed, undoing it on `d`.
+    pub fn revert_fields(&self, d: Decision) -> Decision {
+        if self.from_fields.is_empty() {
+            return d;
+        }
+        let Ok(Value::Object(mut map)) = serde_json::to_value(&d) else {
+            return d;
+        };
+        for (name, value) in &self.from_fields {
+            if value.is_null() {
+                map.remove(name);
+            } else {
+                map.insert(name.clone(), value.clone());
+            }
+        }
+        serde_json::from_value(Value::Object(map)).unwrap_or(d)
+    }
+}
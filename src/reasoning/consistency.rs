@@ -0,0 +1,331 @@
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+
+use serde::Serialize;
+
+use crate::db::Store;
+use crate::error::{DictumError, Result};
+use crate::model::{Decision, Link, LinkKind, Status, Weight};
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum IssueKind {
+    /// A decision in the `requires` closure is deprecated or superseded.
+    Inactive,
+    /// A decision in the `requires` closure does not exist.
+    Unknown,
+    /// A decision in the closure excludes or conflicts with an active decision outside it.
+    ConflictsWithActive,
+    /// Two must-decisions in the closure exclude or conflict with each other.
+    MustContradiction,
+    /// Two decisions in the closure conflict, but at least one of them is negotiable.
+    InternalConflict,
+}
+
+impl IssueKind {
+    /// Whether this issue makes the candidate set inconsistent (as opposed to a warning).
+    pub fn is_fatal(&self) -> bool {
+        !matches!(self, IssueKind::InternalConflict)
+    }
+}
+
+/// One reason the candidate set cannot be adopted, with the smallest set of
+/// decisions and steps that explain it.
+#[derive(Debug, Serialize)]
+pub struct Issue {
+    pub kind: IssueKind,
+    pub ids: Vec<String>,
+    pub explanation: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ConsistencyReport {
+    pub candidates: Vec<String>,
+    pub closure: Vec<String>,
+    pub issues: Vec<Issue>,
+}
+
+impl ConsistencyReport {
+    pub fn is_consistent(&self) -> bool {
+        !self.issues.iter().any(|i| i.kind.is_fatal())
+    }
+}
+
+/// Check candidate decisions (stored or draft) against the store.
+/// `draft_links` are links carried by draft decisions that are not stored yet.
+pub fn check(
+    store: &dyn Store,
+    candidates: &[Decision],
+    draft_links: &[Link],
+) -> Result<ConsistencyReport> {
+    let mut requires = store.links_of_kind(&LinkKind::Requires)?;
+    let mut opposed: Vec<(String, String, LinkKind)> = Vec::new();
+    for kind in [LinkKind::Conflicts, LinkKind::Excludes] {
+        for (src, tgt) in store.links_of_kind(&kind)? {
+            opposed.push((src, tgt, kind.clone()));
+        }
+    }
+    for link in draft_links {
+        let (src, tgt) = (link.source_id.clone(), link.target_id.clone());
+        match link.kind {
+            LinkKind::Requires => requires.push((src, tgt)),
+            LinkKind::Conflicts | LinkKind::Excludes => opposed.push((src, tgt, link.kind.clone())),
+            _ => {}
+        }
+    }
+
+    let mut known: HashMap<String, Decision> =
+        candidates.iter().map(|d| (d.id.clone(), d.clone())).collect();
+
+    // BFS over `requires`, remembering how each member was first reached
+    let mut parent: HashMap<String, String> = HashMap::new();
+    let mut closure: Vec<String> = Vec::new();
+    let mut in_closure: HashSet<String> = HashSet::new();
+    let mut queue: VecDeque<String> = VecDeque::new();
+    for d in candidates {
+        if in_closure.insert(d.id.clone()) {
+            queue.push_back(d.id.clone());
+        }
+    }
+    while let Some(current) = queue.pop_front() {
+        closure.push(current.clone());
+        for (src, tgt) in &requires {
+            if src == &current && in_closure.insert(tgt.clone()) {
+                parent.insert(tgt.clone(), current.clone());
+                queue.push_back(tgt.clone());
+            }
+        }
+    }
+
+    let mut unknown = Vec::new();
+    for id in &closure {
+        if !known.contains_key(id) {
+            match store.decision_get(id) {
+                Ok(d) => {
+                    known.insert(id.clone(), d);
+                }
+                Err(DictumError::DecisionNotFound(_)) => unknown.push(id.clone()),
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    let path_to = |id: &str| -> Vec<String> {
+        let mut path = vec![id.to_string()];
+        let mut current = id;
+        while let Some(prev) = parent.get(current) {
+            path.push(prev.clone());
+            current = prev;
+        }
+        path.reverse();
+        path
+    };
+    let requires_steps = |path: &[String]| -> Vec<String> {
+        path.windows(2)
+            .map(|w| format!("{} requires {}", w[0], w[1]))
+            .collect()
+    };
+
+    let mut issues = Vec::new();
+
+    for id in &unknown {
+        let path = path_to(id);
+        let mut explanation = requires_steps(&path);
+        explanation.push(format!("{} does not exist", id));
+        issues.push(Issue {
+            kind: IssueKind::Unknown,
+            ids: path,
+            explanation,
+        });
+    }
+
+    for id in &closure {
+        let Some(d) = known.get(id) else {
+            continue;
+        };
+        if matches!(d.status, Status::Deprecated | Status::Superseded) {
+            let path = path_to(id);
+            let mut explanation = requires_steps(&path);
+            explanation.push(match d.superseded_by {
+                Some(ref by) => format!("{} is superseded by {}", id, by),
+                None => format!("{} is {}", id, d.status),
+            });
+            issues.push(Issue {
+                kind: IssueKind::Inactive,
+                ids: path,
+                explanation,
+            });
+        }
+    }
+
+    // Unknown IDs are reported above; conflicts need the decision itself
+    let member = |id: &String| in_closure.contains(id) && known.contains_key(id);
+    let mut seen_pairs: HashSet<BTreeSet<String>> = HashSet::new();
+    for (a, b, kind) in &opposed {
+        let pair: BTreeSet<String> = [a.clone(), b.clone()].into_iter().collect();
+        if !seen_pairs.insert(pair) {
+            continue;
+        }
+        let link_step = format!("{} {} {}", a, kind, b);
+        match (member(a), member(b)) {
+            (true, true) => {
+                let both_must = known[a].weight == Weight::Must && known[b].weight == Weight::Must;
+                let (path_a, path_b) = (path_to(a), path_to(b));
+                let mut explanation = requires_steps(&path_a);
+                explanation.extend(requires_steps(&path_b));
+                explanation.push(link_step);
+                if both_must {
+                    explanation.push(format!("{} and {} are both must", a, b));
+                }
+                let ids: BTreeSet<String> = path_a.into_iter().chain(path_b).collect();
+                issues.push(Issue {
+                    kind: if both_must {
+                        IssueKind::MustContradiction
+                    } else {
+                        IssueKind::InternalConflict
+                    },
+                    ids: ids.into_iter().collect(),
+                    explanation,
+                });
+            }
+            (true, false) | (false, true) => {
+                let (inside, outside) = if member(a) { (a, b) } else { (b, a) };
+                let other = match store.decision_get(outside) {
+                    Ok(d) => d,
+                    Err(DictumError::DecisionNotFound(_)) => continue,
+                    Err(e) => return Err(e),
+                };
+                if other.status != Status::Active {
+                    continue;
+                }
+                let mut ids = path_to(inside);
+                let mut explanation = requires_steps(&ids);
+                explanation.push(link_step);
+                explanation.push(format!("{} is active", outside));
+                ids.push(outside.clone());
+                issues.push(Issue {
+                    kind: IssueKind::ConflictsWithActive,
+                    ids,
+                    explanation,
+                });
+            }
+            (false, false) => {}
+        }
+    }
+
+    Ok(ConsistencyReport {
+        candidates: candidates.iter().map(|d| d.id.clone()).collect(),
+        closure,
+        issues,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::TestProject;
+
+    fn decision(id: &str, weight: Weight) -> Decision {
+        Decision::test(id).with_weight(weight)
+    }
+
+    fn link(source: &str, kind: LinkKind, target: &str) -> Link {
+        Link {
+            source_id: source.to_string(),
+            target_id: target.to_string(),
+            kind,
+            created_at: "2025-01-01T00:00:00Z".to_string(),
+            reason: None,
+        }
+    }
+
+    /// d-1 requires d-2, which d-3 supersedes; d-4 and d-5 are musts that
+    /// conflict; d-6 excludes the active d-7.
+    fn with_store(test: impl FnOnce(&dyn Store)) {
+        let project = TestProject::new();
+        let mut store = project.open_cache();
+        for (id, weight) in [
+            ("d-1", Weight::Should),
+            ("d-2", Weight::Should),
+            ("d-3", Weight::Should),
+            ("d-4", Weight::Must),
+            ("d-5", Weight::Must),
+            ("d-6", Weight::Should),
+            ("d-7", Weight::Should),
+        ] {
+            store.decision_insert(&decision(id, weight)).unwrap();
+        }
+        store.decision_update_status("d-2", &Status::Superseded, Some("d-3")).unwrap();
+        store.link_insert(&link("d-1", LinkKind::Requires, "d-2")).unwrap();
+        store.link_insert(&link("d-3", LinkKind::Supersedes, "d-2")).unwrap();
+        store.link_insert(&link("d-4", LinkKind::Conflicts, "d-5")).unwrap();
+        store.link_insert(&link("d-6", LinkKind::Excludes, "d-7")).unwrap();
+        test(&*store);
+    }
+
+    fn get(store: &dyn Store, ids: &[&str]) -> Vec<Decision> {
+        ids.iter().map(|id| store.decision_get(id).unwrap()).collect()
+    }
+
+    #[test]
+    fn requiring_a_superseded_decision_is_inconsistent() {
+        with_store(|store| {
+            let report = check(store, &get(store, &["d-1"]), &[]).unwrap();
+            assert_eq!(report.closure, ["d-1", "d-2"]);
+            assert!(!report.is_consistent());
+            assert_eq!(report.issues.len(), 1);
+            let issue = &report.issues[0];
+            assert_eq!(issue.kind, IssueKind::Inactive);
+            assert_eq!(issue.ids, ["d-1", "d-2"]);
+            assert_eq!(issue.explanation, ["d-1 requires d-2", "d-2 is superseded by d-3"]);
+        });
+    }
+
+    #[test]
+    fn the_successor_itself_is_consistent() {
+        with_store(|store| {
+            assert!(check(store, &get(store, &["d-3"]), &[]).unwrap().is_consistent());
+        });
+    }
+
+    #[test]
+    fn conflicting_musts_are_inconsistent() {
+        with_store(|store| {
+            let report = check(store, &get(store, &["d-4", "d-5"]), &[]).unwrap();
+            let kinds: Vec<&IssueKind> = report.issues.iter().map(|i| &i.kind).collect();
+            assert_eq!(kinds, [&IssueKind::MustContradiction]);
+            assert_eq!(report.issues[0].explanation.last().unwrap(), "d-4 and d-5 are both must");
+        });
+    }
+
+    #[test]
+    fn excluding_an_active_decision_is_inconsistent() {
+        with_store(|store| {
+            let report = check(store, &get(store, &["d-6"]), &[]).unwrap();
+            assert_eq!(report.issues[0].kind, IssueKind::ConflictsWithActive);
+            assert_eq!(report.issues[0].ids, ["d-6", "d-7"]);
+        });
+    }
+
+    #[test]
+    fn a_should_conflicting_inside_the_set_is_only_a_warning() {
+        with_store(|store| {
+            let draft = decision("d-8", Weight::Should);
+            let links = [link("d-8", LinkKind::Conflicts, "d-3")];
+            let report = check(store, &[draft, store.decision_get("d-3").unwrap()], &links).unwrap();
+            assert_eq!(report.issues[0].kind, IssueKind::InternalConflict);
+            assert!(report.is_consistent());
+        });
+    }
+
+    #[test]
+    fn a_draft_requiring_an_unknown_decision_is_a_finding() {
+        with_store(|store| {
+            let draft = decision("d-9", Weight::Should);
+            let links = [link("d-9", LinkKind::Requires, "d-missing")];
+            let report = check(store, &[draft], &links).unwrap();
+            assert!(!report.is_consistent());
+            assert_eq!(report.issues[0].kind, IssueKind::Unknown);
+            assert_eq!(report.issues[0].explanation, ["d-9 requires d-missing", "d-missing does not exist"]);
+        });
+    }
+}
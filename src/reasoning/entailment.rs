@@ -0,0 +1,163 @@
+use std::collections::HashSet;
+
+use crate::db::Store;
+use crate::error::Result;
+use crate::model::{Decision, LinkKind, Status, Weight};
+
+/// A decision that is implied by an active must-decision through `entails` links.
+pub struct Entailment {
+    pub decision: Decision,
+    /// Derivation from the must-decision down to (and including) `decision`.
+    pub chain: Vec<String>,
+}
+
+#[derive(Default)]
+pub struct EntailmentReport {
+    /// Active entailed decisions; binding regardless of their own weight.
+    pub binding: Vec<Entailment>,
+    /// Entailed decisions that are no longer in force.
+    pub inactive: Vec<Entailment>,
+    /// `entails` cycles, each as a closed path (`[a, b, a]`).
+    pub cycles: Vec<Vec<String>>,
+}
+
+impl EntailmentReport {
+    pub fn binding_chain(&self, id: &str) -> Option<&[String]> {
+        self.binding
+            .iter()
+            .find(|e| e.decision.id == id)
+            .map(|e| e.chain.as_slice())
+    }
+}
+
+/// Materialize the `entails` closure of every active must-decision in `roots`.
+/// Each entailed decision is reported once, with its shortest derivation.
+pub fn closure(store: &dyn Store, roots: &[Decision]) -> Result<EntailmentReport> {
+    let edges = store.links_of_kind(&LinkKind::Entails)?;
+    let mut report = EntailmentReport {
+        cycles: super::find_cycles(&edges),
+        ..Default::default()
+    };
+
+    let mut seen: HashSet<String> = HashSet::new();
+    let mut musts: Vec<&Decision> = roots
+        .iter()
+        .filter(|d| d.status == Status::Active && d.weight == Weight::Must)
+        .collect();
+    musts.sort_by(|a, b| a.id.cmp(&b.id));
+
+    for root in musts {
+        for id in store.reachable(&root.id, &[LinkKind::Entails])? {
+            if id == root.id || !seen.insert(id.clone()) {
+                continue;
+            }
+            let chain = super::shortest_path(&edges, &root.id, &id).unwrap_or_default();
+            let decision = store.decision_get(&id)?;
+            let entry = Entailment { decision, chain };
+            match entry.decision.status {
+                Status::Active => report.binding.push(entry),
+                Status::Deprecated | Status::Superseded => report.inactive.push(entry),
+                Status::Draft | Status::Rejected => {}
+            }
+        }
+    }
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{Link, TestProject};
+
+    /// The must d-1 entails d-2, which entails d-3, and entails d-3 and the
+    /// deprecated d-4 directly; the must d-9 entails d-2 too. The should d-5
+    /// entails d-6, and d-7 and d-8 entail each other.
+    fn with_store(test: impl FnOnce(&dyn Store)) {
+        let project = TestProject::new();
+        let mut store = project.open_cache();
+        for id in ["d-1", "d-9"] {
+            store.decision_insert(&Decision::test(id).with_weight(Weight::Must)).unwrap();
+        }
+        for id in ["d-2", "d-3", "d-5", "d-6", "d-7", "d-8"] {
+            store.decision_insert(&Decision::test(id)).unwrap();
+        }
+        store.decision_insert(&Decision::test("d-4").with_status(Status::Deprecated)).unwrap();
+        for (source, target) in [
+            ("d-1", "d-2"),
+            ("d-2", "d-3"),
+            ("d-1", "d-3"),
+            ("d-1", "d-4"),
+            ("d-9", "d-2"),
+            ("d-5", "d-6"),
+            ("d-7", "d-8"),
+            ("d-8", "d-7"),
+        ] {
+            store
+                .link_insert(&Link {
+                    source_id: source.to_string(),
+                    target_id: target.to_string(),
+                    kind: LinkKind::Entails,
+                    created_at: "2025-01-01T00:00:00Z".to_string(),
+                    reason: None,
+                })
+                .unwrap();
+        }
+        test(&*store);
+    }
+
+    fn all(store: &dyn Store) -> Vec<Decision> {
+        store.decision_get_all().unwrap()
+    }
+
+    #[test]
+    fn what_a_must_entails_is_binding_with_its_shortest_chain() {
+        with_store(|store| {
+            let report = closure(store, &all(store)).unwrap();
+            let binding: Vec<&str> = report.binding.iter().map(|e| e.decision.id.as_str()).collect();
+            assert_eq!(binding.len(), 2);
+            assert!(binding.contains(&"d-2") && binding.contains(&"d-3"));
+            assert_eq!(report.binding_chain("d-3").unwrap(), ["d-1", "d-3"]);
+        });
+    }
+
+    #[test]
+    fn each_entailed_decision_is_reported_once_from_the_first_must() {
+        with_store(|store| {
+            let report = closure(store, &all(store)).unwrap();
+            assert_eq!(report.binding.iter().filter(|e| e.decision.id == "d-2").count(), 1);
+            assert_eq!(report.binding_chain("d-2").unwrap(), ["d-1", "d-2"]);
+        });
+    }
+
+    #[test]
+    fn only_active_musts_entail() {
+        with_store(|store| {
+            let report = closure(store, &[store.decision_get("d-5").unwrap()]).unwrap();
+            assert!(report.binding.is_empty() && report.inactive.is_empty());
+            assert!(report.binding_chain("d-6").is_none());
+        });
+    }
+
+    #[test]
+    fn inactive_entailments_are_reported_apart() {
+        with_store(|store| {
+            let report = closure(store, &[store.decision_get("d-1").unwrap()]).unwrap();
+            let inactive: Vec<(&str, &[String])> =
+                report.inactive.iter().map(|e| (e.decision.id.as_str(), e.chain.as_slice())).collect();
+            assert_eq!(inactive, [("d-4", &["d-1".to_string(), "d-4".to_string()][..])]);
+            assert!(report.binding_chain("d-4").is_none());
+        });
+    }
+
+    #[test]
+    fn entails_cycles_are_reported_as_closed_paths() {
+        with_store(|store| {
+            let report = closure(store, &[]).unwrap();
+            assert_eq!(report.cycles.len(), 1);
+            let cycle = &report.cycles[0];
+            assert_eq!((cycle.len(), cycle.first()), (3, cycle.last()));
+            assert!(cycle.contains(&"d-7".to_string()) && cycle.contains(&"d-8".to_string()));
+        });
+    }
+}
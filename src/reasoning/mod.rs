@@ -0,0 +1,104 @@
+pub mod consistency;
+pub mod entailment;
+pub mod patch;
+
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// Shortest path `from -> ... -> to` over directed edges, both ends included.
+pub fn shortest_path(edges: &[(String, String)], from: &str, to: &str) -> Option<Vec<String>> {
+    let mut parent: HashMap<&str, &str> = HashMap::new();
+    let mut visited: HashSet<&str> = HashSet::new();
+    let mut queue: VecDeque<&str> = VecDeque::new();
+    visited.insert(from);
+    queue.push_back(from);
+
+    while let Some(current) = queue.pop_front() {
+        if current == to && current != from {
+            break;
+        }
+        for (src, tgt) in edges {
+            if src == current && visited.insert(tgt.as_str()) {
+                parent.insert(tgt.as_str(), current);
+                queue.push_back(tgt.as_str());
+            }
+        }
+    }
+
+    if from == to {
+        return Some(vec![from.to_string()]);
+    }
+    parent.get(to)?;
+
+    let mut path = vec![to.to_string()];
+    let mut current = to;
+    while let Some(prev) = parent.get(current) {
+        path.push(prev.to_string());
+        current = prev;
+    }
+    path.reverse();
+    Some(path)
+}
+
+/// Every distinct cycle in a directed graph, each reported once as a closed
+/// path starting at its smallest ID (e.g. `[a, b, c, a]`).
+pub fn find_cycles(edges: &[(String, String)]) -> Vec<Vec<String>> {
+    let mut seen: HashSet<Vec<String>> = HashSet::new();
+    let mut cycles = Vec::new();
+
+    for (src, tgt) in edges {
+        let Some(mut path) = shortest_path(edges, tgt, src) else {
+            continue;
+        };
+        // path is tgt -> ... -> src; closing edge src -> tgt makes the cycle
+        path.insert(0, src.clone());
+        path.pop();
+        let start = path
+            .iter()
+            .enumerate()
+            .min_by(|a, b| a.1.cmp(b.1))
+            .map(|(i, _)| i)
+            .unwrap_or(0);
+        path.rotate_left(start);
+        if seen.insert(path.clone()) {
+            let first = path[0].clone();
+            path.push(first);
+            cycles.push(path);
+        }
+    }
+
+    cycles.sort();
+    cycles
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edges(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(a, b)| (a.to_string(), b.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn shortest_path_follows_direction() {
+        let e = edges(&[("a", "b"), ("b", "c"), ("a", "c")]);
+        assert_eq!(shortest_path(&e, "a", "c").unwrap(), vec!["a", "c"]);
+        assert_eq!(shortest_path(&e, "b", "c").unwrap(), vec!["b", "c"]);
+        assert!(shortest_path(&e, "c", "a").is_none());
+    }
+
+    #[test]
+    fn find_cycles_reports_each_cycle_once() {
+        let e = edges(&[("b", "c"), ("c", "a"), ("a", "b"), ("c", "d")]);
+        let cycles = find_cycles(&e);
+        assert_eq!(cycles, vec![vec!["a", "b", "c", "a"]]);
+    }
+
+    #[test]
+    fn find_cycles_empty_for_dag() {
+        let e = edges(&[("a", "b"), ("b", "c"), ("a", "c")]);
+        assert!(find_cycles(&e).is_empty());
+    }
+}
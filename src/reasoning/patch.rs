@@ -0,0 +1,330 @@
+//! Which active decisions a change touches, for `check-diff`. A unified diff
+//! gives the touched paths and the added lines. A decision matches when its
+//! scope names directories on a touched path, when one of its labels is a
+//! path segment, or through a code anchor: a `backticked` span in its title,
+//! body or rebuttal. Anchors that look like paths (`src/api/**`, `*.sql`,
+//! `Cargo.toml`) are matched against touched paths; anything else
+//! (`unwrap()`, `OrderService`) against the added lines.
+
+use serde::Serialize;
+
+use crate::model::{Decision, Weight};
+
+#[derive(Debug, Default, Serialize)]
+pub struct FileChange {
+    pub path: String,
+    #[serde(skip)]
+    pub added: Vec<String>,
+    pub added_lines: usize,
+}
+
+/// The files in a unified diff (`git diff` output), with their added lines.
+/// Deleted files are listed under their old path. Hunk line counts are
+/// followed, so a body line that reads like a `---`/`+++` header is content.
+pub fn parse(diff: &str) -> Vec<FileChange> {
+    let mut files: Vec<FileChange> = Vec::new();
+    let mut old_path: Option<String> = None;
+    // Lines still expected in the current hunk: (old side, new side)
+    let mut remaining = (0usize, 0usize);
+    for line in diff.lines() {
+        if remaining != (0, 0) {
+            match line.chars().next() {
+                Some('+') => {
+                    remaining.1 = remaining.1.saturating_sub(1);
+                    if let Some(file) = files.last_mut() {
+                        file.added.push(line[1..].to_string());
+                        file.added_lines += 1;
+                    }
+                    continue;
+                }
+                Some('-') => {
+                    remaining.0 = remaining.0.saturating_sub(1);
+                    continue;
+                }
+                Some(' ') | None => {
+                    remaining.0 = remaining.0.saturating_sub(1);
+                    remaining.1 = remaining.1.saturating_sub(1);
+                    continue;
+                }
+                Some('\\') => continue,
+                // A hunk shorter than its header says
+                Some(_) => remaining = (0, 0),
+            }
+        }
+        if let Some(counts) = hunk_counts(line) {
+            remaining = counts;
+        } else if let Some(path) = line.strip_prefix("--- ") {
+            old_path = diff_path(path, "a/");
+        } else if let Some(path) = line.strip_prefix("+++ ") {
+            let path = diff_path(path, "b/").or(old_path.take());
+            files.push(FileChange {
+                path: path.unwrap_or_default(),
+                ..Default::default()
+            });
+        }
+    }
+    files.retain(|f| !f.path.is_empty());
+    files
+}
+
+/// The old and new line counts of a `@@ -a,b +c,d @@` hunk header; an
+/// omitted count is 1.
+fn hunk_counts(line: &str) -> Option<(usize, usize)> {
+    let ranges = line.strip_prefix("@@ -")?.split(" @@").next()?;
+    let (old, new) = ranges.split_once(" +")?;
+    let count = |range: &str| match range.split_once(',') {
+        Some((_, n)) => n.parse().ok(),
+        None => range.parse::<usize>().ok().map(|_| 1),
+    };
+    Some((count(old)?, count(new)?))
+}
+
+fn diff_path(raw: &str, prefix: &str) -> Option<String> {
+    let raw = raw.split('\t').next().unwrap_or(raw).trim();
+    if raw == "/dev/null" {
+        return None;
+    }
+    Some(raw.strip_prefix(prefix).unwrap_or(raw).to_string())
+}
+
+#[derive(Debug, Serialize)]
+pub struct Reason {
+    /// `scope`, `label` or `anchor`
+    pub via: &'static str,
+    pub detail: String,
+    pub paths: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Hit {
+    #[serde(flatten)]
+    pub decision: Decision,
+    pub reasons: Vec<Reason>,
+}
+
+/// Decisions implicated by the change, must-weight first.
+pub fn check(files: &[FileChange], decisions: &[Decision]) -> Vec<Hit> {
+    let mut hits: Vec<Hit> = decisions
+        .iter()
+        .filter_map(|d| {
+            let reasons = reasons(d, files);
+            (!reasons.is_empty()).then(|| Hit {
+                decision: d.clone(),
+                reasons,
+            })
+        })
+        .collect();
+    hits.sort_by_key(|h| match h.decision.weight {
+        Weight::Must => 0,
+        Weight::Should => 1,
+        Weight::May => 2,
+    });
+    hits
+}
+
+fn reasons(d: &Decision, files: &[FileChange]) -> Vec<Reason> {
+    let mut out: Vec<Reason> = Vec::new();
+    let mut add = |via: &'static str, detail: &str, path: &str| {
+        match out.iter_mut().find(|r| r.via == via && r.detail == detail) {
+            Some(r) if !r.paths.iter().any(|p| p == path) => r.paths.push(path.to_string()),
+            Some(_) => {}
+            None => out.push(Reason {
+                via,
+                detail: detail.to_string(),
+                paths: vec![path.to_string()],
+            }),
+        }
+    };
+
+    let anchors = anchors(d);
+    for file in files {
+        let segments = path_segments(&file.path);
+        if let Some(ref scope) = d.scope {
+            let scope: Vec<&str> = scope.split('/').collect();
+            if segments.windows(scope.len()).any(|w| w == scope.as_slice()) {
+                add("scope", &scope.join("/"), &file.path);
+            }
+        }
+        for label in &d.labels {
+            if segments.iter().any(|s| s.eq_ignore_ascii_case(label)) {
+                add("label", label, &file.path);
+            }
+        }
+        for anchor in &anchors {
+            let hit = if is_path_like(anchor) {
+                path_matches(anchor, &file.path)
+            } else {
+                file.added.iter().any(|line| line.contains(anchor.as_str()))
+            };
+            if hit {
+                add("anchor", anchor, &file.path);
+            }
+        }
+    }
+    out
+}
+
+/// Directory names plus the file name without its extension.
+fn path_segments(path: &str) -> Vec<&str> {
+    let mut segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+    if let Some(last) = segments.last_mut() {
+        *last = last.split('.').next().unwrap_or(last);
+    }
+    segments
+}
+
+/// Backticked spans of at least three characters.
+fn anchors(d: &Decision) -> Vec<String> {
+    let mut text = d.title.clone();
+    for part in [&d.body, &d.rebuttal].into_iter().flatten() {
+        text.push('\n');
+        text.push_str(part);
+    }
+    let mut out: Vec<String> = Vec::new();
+    for (i, span) in text.split('`').enumerate() {
+        let span = span.trim();
+        if i % 2 == 1 && span.chars().count() >= 3 && !span.contains('\n') && !out.iter().any(|a| a == span) {
+            out.push(span.to_string());
+        }
+    }
+    out
+}
+
+fn is_path_like(anchor: &str) -> bool {
+    if anchor.contains(' ') || anchor.contains('(') {
+        return false;
+    }
+    anchor.contains('/')
+        || anchor.contains('*')
+        || anchor
+            .rsplit_once('.')
+            .is_some_and(|(stem, ext)| !stem.is_empty() && (1..=5).contains(&ext.len()) && ext.chars().all(|c| c.is_ascii_alphanumeric()))
+}
+
+/// Glob patterns match the whole path (`*` within a segment, `**` across
+/// segments); plain paths match themselves, anything below them, or a
+/// trailing run of segments (`Cargo.toml` matches `crates/x/Cargo.toml`).
+fn path_matches(anchor: &str, path: &str) -> bool {
+    let anchor = anchor.trim_start_matches("./").trim_end_matches('/');
+    if anchor.contains('*') || anchor.contains('?') {
+        return glob(anchor.as_bytes(), path.as_bytes())
+            || (!anchor.contains('/') && glob(anchor.as_bytes(), path.rsplit('/').next().unwrap_or(path).as_bytes()));
+    }
+    path == anchor || path.starts_with(&format!("{}/", anchor)) || path.ends_with(&format!("/{}", anchor))
+}
+
+fn glob(pattern: &[u8], text: &[u8]) -> bool {
+    match pattern {
+        [] => text.is_empty(),
+        [b'*', b'*', rest @ ..] => {
+            let rest = rest.strip_prefix(b"/").unwrap_or(rest);
+            (0..=text.len()).any(|i| glob(rest, &text[i..]))
+        }
+        [b'*', rest @ ..] => (0..=text.len())
+            .take_while(|&i| i == 0 || text[i - 1] != b'/')
+            .any(|i| glob(rest, &text[i..])),
+        [b'?', rest @ ..] => !text.is_empty() && text[0] != b'/' && glob(rest, &text[1..]),
+        [c, rest @ ..] => text.first() == Some(c) && glob(rest, &text[1..]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decision(id: &str, title: &str, weight: Weight) -> Decision {
+        Decision::test(id).with_title(title).with_weight(weight)
+    }
+
+    const DIFF: &str = "\
+diff --git a/src/logging/mod.rs b/src/logging/mod.rs
+--- a/src/logging/mod.rs
++++ b/src/logging/mod.rs
+@@ -1,2 +1,3 @@
+ use std::fmt;
++let user = lookup(id).unwrap();
+-old line
+diff --git a/migrations/001.sql b/migrations/001.sql
+--- /dev/null
++++ b/migrations/001.sql
+@@ -0,0 +1 @@
++CREATE TABLE t (id INT);
+diff --git a/docs/old.md b/docs/old.md
+--- a/docs/old.md
++++ /dev/null
+@@ -1 +0,0 @@
+-gone
+";
+
+    #[test]
+    fn parses_paths_and_added_lines() {
+        let files = parse(DIFF);
+        let paths: Vec<&str> = files.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(paths, ["src/logging/mod.rs", "migrations/001.sql", "docs/old.md"]);
+        assert_eq!(files[0].added, ["let user = lookup(id).unwrap();"]);
+        assert_eq!(files[2].added_lines, 0);
+    }
+
+    #[test]
+    fn header_like_lines_inside_a_hunk_are_content() {
+        let diff = "\
+diff --git a/notes.md b/notes.md
+--- a/notes.md
++++ b/notes.md
+@@ -1,2 +1,2 @@
+ # Notes
+---- a/section break
+++++ b/new heading
+\\ No newline at end of file
+diff --git a/README.md b/README.md
+--- a/README.md
++++ b/README.md
+@@ -5 +5 @@
+-old
++new
+";
+        let files = parse(diff);
+        let paths: Vec<&str> = files.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(paths, ["notes.md", "README.md"]);
+        assert_eq!(files[0].added, ["+++ b/new heading"]);
+        assert_eq!(files[1].added, ["new"]);
+    }
+
+    #[test]
+    fn hunk_counts_default_to_one() {
+        assert_eq!(hunk_counts("@@ -5 +5,0 @@ fn main()"), Some((1, 0)));
+    }
+
+    fn hits() -> Vec<Hit> {
+        let decisions = vec![
+            decision("d-1", "Prefer small PRs", Weight::May),
+            decision("d-2", "Never call `unwrap()` in request paths", Weight::Should),
+            decision("d-3", "No PII in logs", Weight::Must).with_scope("logging"),
+            decision("d-4", "Migrations in `migrations/*.sql` are append-only", Weight::Must).with_labels(&["docs"]),
+        ];
+        check(&parse(DIFF), &decisions)
+    }
+
+    #[test]
+    fn ranks_hits_by_weight() {
+        let ids: Vec<String> = hits().into_iter().map(|h| h.decision.id).collect();
+        assert_eq!(ids, ["d-3", "d-4", "d-2"]);
+    }
+
+    #[test]
+    fn matches_scope_labels_and_anchors() {
+        let hits = hits();
+        assert_eq!(hits[0].reasons[0].via, "scope");
+        assert_eq!(hits[0].reasons[0].paths, ["src/logging/mod.rs"]);
+        let via: Vec<(&str, &str)> = hits[1].reasons.iter().map(|r| (r.via, r.detail.as_str())).collect();
+        assert_eq!(via, [("anchor", "migrations/*.sql"), ("label", "docs")]);
+        assert_eq!(hits[2].reasons[0].detail, "unwrap()");
+    }
+
+    #[test]
+    fn path_globs_cross_directories_only_with_double_stars() {
+        assert!(path_matches("src/**/*.rs", "src/a/b/c.rs"));
+        assert!(path_matches("Cargo.toml", "crates/x/Cargo.toml"));
+        assert!(!path_matches("src/*.rs", "src/a/b.rs"));
+    }
+}
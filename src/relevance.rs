@@ -0,0 +1,227 @@
+//! Task relevance for `context --task`: how well a decision's title, labels,
+//! scope and body match a free-text description of the work about to be done.
+//! Terms are weighted by rarity across the candidate decisions and by field
+//! (a title hit counts more than a body hit), and words are crudely stemmed so
+//! "caching" finds "cache". Words that describe doing work rather than what it
+//! is about ("add", "implement") are ignored.
+
+use std::collections::{HashMap, HashSet};
+
+use serde::Serialize;
+
+use crate::model::{Decision, Link, LinkKind};
+use crate::similarity::term_counts;
+
+const TITLE_BOOST: f64 = 3.0;
+const LABEL_BOOST: f64 = 2.0;
+const SCOPE_BOOST: f64 = 2.0;
+const BODY_BOOST: f64 = 1.0;
+
+/// Compared after stemming, so "adding" and "implemented" are dropped too
+const TASK_WORDS: &[&str] = &[
+    "add", "change", "create", "fix", "implement", "improve", "make", "new", "refactor",
+    "support", "update", "write",
+];
+
+/// Why a decision is in a task-focused context.
+#[derive(Debug, Clone, Serialize)]
+pub struct Relevance {
+    /// 0 (no overlap) to 1 (every task term in the title)
+    pub score: f64,
+    pub reason: String,
+    /// How many task terms the decision matched
+    #[serde(skip)]
+    pub matched: usize,
+}
+
+fn stem(word: &str) -> String {
+    let mut w = word;
+    for suffix in ["ing", "ed", "es", "s"] {
+        if let Some(s) = w.strip_suffix(suffix) {
+            if s.chars().count() >= 3 {
+                w = s;
+                break;
+            }
+        }
+    }
+    w.strip_suffix('e').filter(|s| s.chars().count() >= 3).unwrap_or(w).to_string()
+}
+
+/// The words of `text` that say what it is about: `term_counts` without the
+/// task words, in no particular order.
+pub fn terms(text: &str) -> Vec<String> {
+    term_counts(text)
+        .into_keys()
+        .filter(|word| !TASK_WORDS.iter().any(|t| stem(t) == stem(word)))
+        .collect()
+}
+
+fn stems(text: &str) -> HashSet<String> {
+    term_counts(text).keys().map(|t| stem(t)).collect()
+}
+
+struct Fields {
+    title: HashSet<String>,
+    labels: HashSet<String>,
+    scope: HashSet<String>,
+    body: HashSet<String>,
+}
+
+impl Fields {
+    fn of(d: &Decision) -> Self {
+        let mut body = vec![d.body.as_deref().unwrap_or_default()];
+        body.extend(d.rebuttal.as_deref());
+        Fields {
+            title: stems(&d.title),
+            labels: stems(&d.labels.join(" ")),
+            scope: stems(d.scope.as_deref().unwrap_or_default()),
+            body: stems(&body.join("\n")),
+        }
+    }
+
+    /// The strongest field a term appears in, with its boost.
+    fn best(&self, term: &str) -> Option<(&'static str, f64)> {
+        [
+            ("title", TITLE_BOOST, &self.title),
+            ("labels", LABEL_BOOST, &self.labels),
+            ("scope", SCOPE_BOOST, &self.scope),
+            ("body", BODY_BOOST, &self.body),
+        ]
+        .into_iter()
+        .find(|(_, _, terms)| terms.contains(term))
+        .map(|(field, boost, _)| (field, boost))
+    }
+}
+
+/// Score every decision against `task`. Decisions sharing no term with the
+/// task are left out.
+pub fn score(task: &str, decisions: &[Decision]) -> HashMap<String, Relevance> {
+    score_against("task", task, decisions)
+}
+
+/// `score` for any free text; `subject` names it in the reasons, e.g.
+/// "matches question (title: cache)".
+pub fn score_against(subject: &str, task: &str, decisions: &[Decision]) -> HashMap<String, Relevance> {
+    let fields: Vec<Fields> = decisions.iter().map(Fields::of).collect();
+    // Stemmed task terms, each with the word it came from for the explanation
+    let mut task_terms: Vec<(String, String)> = terms(task)
+        .into_iter()
+        .map(|word| (stem(&word), word))
+        .collect();
+    let lower = task.to_lowercase();
+    task_terms.sort_by_key(|(_, word)| lower.find(word.as_str()));
+    let mut seen = HashSet::new();
+    task_terms.retain(|(term, _)| seen.insert(term.clone()));
+
+    let n = decisions.len() as f64;
+    let idf: HashMap<&str, f64> = task_terms
+        .iter()
+        .map(|(t, _)| {
+            let df = fields.iter().filter(|f| f.best(t).is_some()).count() as f64;
+            (t.as_str(), ((n + 1.0) / (df + 1.0)).ln() + 1.0)
+        })
+        .collect();
+    let max: f64 = idf.values().map(|w| w * TITLE_BOOST).sum();
+
+    let mut scores = HashMap::new();
+    for (d, f) in decisions.iter().zip(&fields) {
+        let mut total = 0.0;
+        let mut by_field: Vec<(&str, Vec<&str>)> = Vec::new();
+        for (term, word) in &task_terms {
+            let Some((field, boost)) = f.best(term) else {
+                continue;
+            };
+            total += idf[term.as_str()] * boost;
+            match by_field.iter_mut().find(|(name, _)| *name == field) {
+                Some((_, words)) => words.push(word),
+                None => by_field.push((field, vec![word])),
+            }
+        }
+        if total == 0.0 {
+            continue;
+        }
+        let matched: Vec<String> = by_field
+            .iter()
+            .map(|(field, terms)| format!("{}: {}", field, terms.join(", ")))
+            .collect();
+        scores.insert(
+            d.id.clone(),
+            Relevance {
+                score: (total / max * 100.0).round() / 100.0,
+                reason: format!("matches {} ({})", subject, matched.join("; ")),
+                matched: by_field.iter().map(|(_, words)| words.len()).sum(),
+            },
+        );
+    }
+    scores
+}
+
+/// The decisions that govern `id` one hop away: what it refines or requires,
+/// and what entails it. Returns `(governing id, reason)` pairs.
+pub fn governing(id: &str, links: &[Link]) -> Vec<(String, String)> {
+    links
+        .iter()
+        .filter_map(|l| match l.kind {
+            LinkKind::Refines | LinkKind::Requires if l.source_id == id => Some((
+                l.target_id.clone(),
+                format!("{} by {}", if l.kind == LinkKind::Refines { "refined" } else { "required" }, id),
+            )),
+            LinkKind::Entails if l.target_id == id => {
+                Some((l.source_id.clone(), format!("entails {}", id)))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decision(id: &str, title: &str) -> Decision {
+        Decision::test(id).with_title(title)
+    }
+
+    #[test]
+    fn ignores_words_about_doing_the_work() {
+        let decisions = vec![
+            decision("d-1", "Add a changelog entry for every release"),
+            decision("d-2", "Implement retries in the client").with_labels(&["client"]),
+        ];
+        assert_eq!(terms("Add and implement new things"), ["things"]);
+        assert!(score("adding, implementing and updating", &decisions).is_empty());
+
+        let scores = score("add retries to the client", &decisions);
+        assert!(!scores.contains_key("d-1"));
+        assert_eq!(scores["d-2"].reason, "matches task (title: retries, client)");
+    }
+
+    #[test]
+    fn ranks_by_field_and_explains_matches() {
+        let decisions = vec![
+            decision("d-1", "Cache hot reads in Redis"),
+            decision("d-2", "Version every endpoint").with_scope("api/orders").with_labels(&["api"]),
+            decision("d-3", "Bill monthly").with_scope("billing"),
+        ];
+        let scores = score("add Redis caching to the order API", &decisions);
+
+        assert!(!scores.contains_key("d-3"));
+        assert!(scores["d-1"].score > 0.0 && scores["d-1"].score <= 1.0);
+        assert_eq!(scores["d-1"].reason, "matches task (title: redis, caching)");
+        assert_eq!(scores["d-2"].reason, "matches task (scope: order; labels: api)");
+    }
+
+    #[test]
+    fn governing_follows_links_upward_only() {
+        let link = Link {
+            source_id: "d-2".to_string(),
+            target_id: "d-9".to_string(),
+            kind: LinkKind::Refines,
+            created_at: String::new(),
+            reason: None,
+        };
+        let links = [link];
+        assert_eq!(governing("d-2", &links), [("d-9".to_string(), "refined by d-2".to_string())]);
+        assert!(governing("d-9", &links).is_empty());
+    }
+}
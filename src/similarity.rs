@@ -0,0 +1,234 @@
+//! Offline near-duplicate detection: TF-IDF vectors over title, body and rebuttal,
+//! compared by cosine similarity. A pair scores the higher of its full-text and
+//! title-only similarity, so a long body on one side does not hide a duplicate
+//! title. The index is cached in `.dictum/similarity.json` and rebuilt whenever
+//! the set of decisions changes.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::error::Result;
+use crate::model::Decision;
+
+const INDEX_FILE: &str = "similarity.json";
+
+const STOP_WORDS: &[&str] = &[
+    "a", "an", "and", "are", "as", "at", "be", "by", "for", "from", "in", "into", "is", "it",
+    "its", "of", "on", "or", "that", "the", "this", "to", "we", "with",
+];
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SimilarityIndex {
+    /// Hash of every decision's id and `updated_at`; a mismatch means the cache is stale.
+    fingerprint: String,
+    doc_count: usize,
+    doc_freq: HashMap<String, usize>,
+    docs: HashMap<String, DocTerms>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct DocTerms {
+    title: HashMap<String, usize>,
+    full: HashMap<String, usize>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Similar {
+    pub id: String,
+    pub score: f64,
+}
+
+impl SimilarityIndex {
+    pub fn build(decisions: &[Decision]) -> Self {
+        let mut doc_freq: HashMap<String, usize> = HashMap::new();
+        let mut docs = HashMap::new();
+        for d in decisions {
+            let terms = doc_terms(d);
+            for term in terms.full.keys() {
+                *doc_freq.entry(term.clone()).or_default() += 1;
+            }
+            docs.insert(d.id.clone(), terms);
+        }
+        SimilarityIndex {
+            fingerprint: fingerprint(decisions),
+            doc_count: decisions.len(),
+            doc_freq,
+            docs,
+        }
+    }
+
+    /// Load the cached index, rebuilding (and re-saving) it if `decisions` changed.
+    /// A cache that cannot be written is not an error; it is rebuilt next time.
+    pub fn load_or_build(dictum_dir: &Path, decisions: &[Decision]) -> Result<Self> {
+        let path = dictum_dir.join(INDEX_FILE);
+        let expected = fingerprint(decisions);
+        if let Ok(content) = std::fs::read_to_string(&path) {
+            if let Ok(index) = serde_json::from_str::<SimilarityIndex>(&content) {
+                if index.fingerprint == expected {
+                    return Ok(index);
+                }
+            }
+        }
+        let index = Self::build(decisions);
+        let _ = std::fs::write(&path, serde_json::to_string(&index)?);
+        Ok(index)
+    }
+
+    /// Decisions most similar to the stored decision `id` (excluding itself).
+    pub fn similar_to_id(&self, id: &str, candidates: &[&str], k: usize) -> Vec<Similar> {
+        let Some(terms) = self.docs.get(id) else {
+            return Vec::new();
+        };
+        let others: Vec<&str> = candidates.iter().copied().filter(|c| *c != id).collect();
+        self.rank(terms, &others, k)
+    }
+
+    /// Decisions most similar to free text.
+    pub fn similar_to_text(&self, text: &str, candidates: &[&str], k: usize) -> Vec<Similar> {
+        let counts = term_counts(text);
+        let terms = DocTerms {
+            title: counts.clone(),
+            full: counts,
+        };
+        self.rank(&terms, candidates, k)
+    }
+
+    /// Decisions most similar to a decision that is not stored yet.
+    pub fn similar_to_decision(&self, decision: &Decision, candidates: &[&str], k: usize) -> Vec<Similar> {
+        self.rank(&doc_terms(decision), candidates, k)
+    }
+
+    fn rank(&self, query: &DocTerms, candidates: &[&str], k: usize) -> Vec<Similar> {
+        let (query_title, query_full) = (self.weigh(&query.title), self.weigh(&query.full));
+        let mut results: Vec<Similar> = candidates
+            .iter()
+            .filter_map(|id| {
+                let doc = self.docs.get(*id)?;
+                let score = cosine(&query_full, &self.weigh(&doc.full))
+                    .max(cosine(&query_title, &self.weigh(&doc.title)));
+                (score > 0.0).then(|| Similar {
+                    id: id.to_string(),
+                    score,
+                })
+            })
+            .collect();
+        results.sort_by(|a, b| b.score.total_cmp(&a.score).then_with(|| a.id.cmp(&b.id)));
+        results.truncate(k);
+        results
+    }
+
+    /// TF-IDF weights: `(1 + ln tf) * (ln((N + 1) / (df + 1)) + 1)`.
+    fn weigh(&self, tf: &HashMap<String, usize>) -> HashMap<String, f64> {
+        tf.iter()
+            .map(|(term, &count)| {
+                let df = self.doc_freq.get(term).copied().unwrap_or(0);
+                let idf = ((self.doc_count as f64 + 1.0) / (df as f64 + 1.0)).ln() + 1.0;
+                (term.clone(), (1.0 + (count as f64).ln()) * idf)
+            })
+            .collect()
+    }
+}
+
+fn cosine(a: &HashMap<String, f64>, b: &HashMap<String, f64>) -> f64 {
+    let dot: f64 = a
+        .iter()
+        .filter_map(|(term, wa)| b.get(term).map(|wb| wa * wb))
+        .sum();
+    let norm = |v: &HashMap<String, f64>| v.values().map(|w| w * w).sum::<f64>().sqrt();
+    let denom = norm(a) * norm(b);
+    if denom == 0.0 {
+        0.0
+    } else {
+        dot / denom
+    }
+}
+
+fn doc_terms(d: &Decision) -> DocTerms {
+    let mut text = vec![d.title.as_str()];
+    text.extend(d.body.as_deref());
+    text.extend(d.rebuttal.as_deref());
+    DocTerms {
+        title: term_counts(&d.title),
+        full: term_counts(&text.join("\n")),
+    }
+}
+
+pub fn term_counts(text: &str) -> HashMap<String, usize> {
+    let mut counts = HashMap::new();
+    for word in text.split(|c: char| !c.is_alphanumeric()) {
+        let word = word.to_lowercase();
+        if word.chars().count() < 2 || STOP_WORDS.contains(&word.as_str()) {
+            continue;
+        }
+        *counts.entry(word).or_default() += 1;
+    }
+    counts
+}
+
+fn fingerprint(decisions: &[Decision]) -> String {
+    let mut keys: Vec<(&str, &str)> = decisions
+        .iter()
+        .map(|d| (d.id.as_str(), d.updated_at.as_str()))
+        .collect();
+    keys.sort();
+    let mut hasher = Sha256::new();
+    for (id, updated_at) in keys {
+        hasher.update(id.as_bytes());
+        hasher.update(b"\0");
+        hasher.update(updated_at.as_bytes());
+        hasher.update(b"\n");
+    }
+    format!("{:x}", hasher.finalize())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const IDS: [&str; 3] = ["d-1", "d-2", "d-3"];
+
+    fn index() -> SimilarityIndex {
+        SimilarityIndex::build(&[
+            Decision::test("d-1")
+                .with_title("Use Postgres for persistent storage")
+                .with_body("Relational data fits well."),
+            Decision::test("d-2").with_title("Cache sessions in Redis"),
+            Decision::test("d-3").with_title("Log in JSON format"),
+        ])
+    }
+
+    #[test]
+    fn text_matches_rank_first() {
+        let hits = index().similar_to_text("use postgres for storage", &IDS, 3);
+        assert_eq!(hits[0].id, "d-1");
+        assert!(hits[0].score > 0.5);
+        assert!(hits.iter().all(|h| h.id != "d-3"));
+    }
+
+    #[test]
+    fn a_decision_is_not_similar_to_itself() {
+        let hits = index().similar_to_id("d-1", &IDS, 3);
+        assert!(hits.iter().all(|h| h.id != "d-1"));
+    }
+
+    #[test]
+    fn near_duplicates_score_high() {
+        let dup = Decision::test("d-9").with_title("Use Postgres for persistent storage");
+        let hits = index().similar_to_decision(&dup, &IDS, 1);
+        assert_eq!(hits[0].id, "d-1");
+        assert!(hits[0].score > 0.8);
+    }
+
+    #[test]
+    fn fingerprint_tracks_updates() {
+        let mut decisions = vec![Decision::test("d-1"), Decision::test("d-2")];
+        let before = fingerprint(&decisions);
+        decisions.reverse();
+        assert_eq!(fingerprint(&decisions), before);
+        decisions[0].updated_at = "2025-02-01T00:00:00Z".to_string();
+        assert_ne!(fingerprint(&decisions), before);
+    }
+}
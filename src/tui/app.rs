@@ -1,8 +1,12 @@
 use std::collections::HashSet;
 use std::path::Path;
 
+use crate::cli::amend::AmendArgs;
+use crate::cli::proposal;
+use crate::config::Config;
 use crate::db;
-use crate::db::{ListFilter, Store};
+use crate::db::expr::Expr;
+use crate::db::{ListFilter, Neighborhood, SearchQuery, Sort, Store};
 use crate::error::Result;
 use crate::format::tree::build_tree;
 use crate::model::decision::{Kind, Level, Status, Weight};
@@ -14,6 +18,8 @@ pub enum View {
     Detail,
     Tree,
     Search,
+    /// Pending proposals awaiting accept or reject
+    Inbox,
 }
 
 #[derive(Debug, Clone)]
@@ -31,6 +37,12 @@ pub struct FilterState {
     pub status: Option<Status>,
     pub level: Option<Level>,
     pub scope: Option<String>,
+    pub scope_exact: bool,
+    pub label: Option<String>,
+    pub expr: Option<Expr>,
+    pub sort: Option<Sort>,
+    /// Name of the saved view these filters came from, until one is changed by hand
+    pub view: Option<String>,
 }
 
 impl FilterState {
@@ -40,20 +52,45 @@ impl FilterState {
             && self.status.is_none()
             && self.level.is_none()
             && self.scope.is_none()
+            && self.label.is_none()
+            && self.expr.is_none()
+            && self.sort.is_none()
+    }
+
+    /// Filters from a saved view. Views are validated when saved, so a value
+    /// that no longer parses (e.g. a hand-edited config) is simply dropped.
+    pub fn from_view(name: &str, view: &crate::config::View) -> Self {
+        FilterState {
+            kind: view.kind.as_deref().and_then(|k| k.parse().ok()),
+            weight: view.weight.as_deref().and_then(|w| w.parse().ok()),
+            status: view.status.as_deref().and_then(|s| s.parse().ok()),
+            level: view.level.as_deref().and_then(|l| l.parse().ok()),
+            scope: view.scope.clone(),
+            scope_exact: view.exact,
+            label: view.label.clone(),
+            expr: view.where_expr.as_deref().and_then(|e| db::expr::parse(e).ok()),
+            sort: view.sort.as_deref().and_then(|s| s.parse().ok()),
+            view: Some(name.to_string()),
+        }
     }
 
     pub fn to_list_filter(&self) -> ListFilter {
         ListFilter {
             level: self.level.clone(),
             status: self.status.clone(),
-            label: None,
+            label: self.label.clone(),
             kind: self.kind.clone(),
             weight: self.weight.clone(),
             scope: self.scope.clone(),
+            scope_exact: self.scope_exact,
+            expr: self.expr.clone(),
+            sort: self.sort,
+            ..Default::default()
         }
     }
 
     pub fn cycle_field(&mut self, field: usize) {
+        self.view = None;
         match field {
             0 => {
                 self.kind = match &self.kind {
@@ -80,7 +117,8 @@ impl FilterState {
                     Some(Status::Active) => Some(Status::Superseded),
                     Some(Status::Superseded) => Some(Status::Deprecated),
                     Some(Status::Deprecated) => Some(Status::Draft),
-                    Some(Status::Draft) => None,
+                    Some(Status::Draft) => Some(Status::Rejected),
+                    Some(Status::Rejected) => None,
                 };
             }
             3 => {
@@ -98,18 +136,25 @@ impl FilterState {
 
 pub struct App {
     pub store: Box<dyn Store>,
+    pub config: Config,
     pub view: View,
     pub decisions: Vec<Decision>,
     pub selected_index: usize,
     pub detail_scroll: u16,
     pub filter: FilterState,
     pub filter_panel_open: bool,
+    /// Saved views from config.toml, in name order
+    pub views: Vec<(String, crate::config::View)>,
     pub tree_nodes: Vec<TreeNode>,
     pub expanded_nodes: HashSet<String>,
     pub search_query: String,
     pub selected_decision: Option<Decision>,
     pub selected_links: Vec<Link>,
     pub refines_links: Vec<(String, String)>,
+    pub neighborhood_depth: u32,
+    pub neighborhood: Option<Neighborhood>,
+    /// Reason being typed while rejecting the selected proposal
+    pub reject_reason: Option<String>,
 }
 
 impl App {
@@ -117,24 +162,31 @@ impl App {
         let dictum_dir = cwd.join(".dictum");
         crate::cli::ensure_init(&dictum_dir)?;
         let store = db::open(&dictum_dir)?;
+        let config = Config::load(&dictum_dir)?;
+        let views = config.views.clone().into_iter().collect();
 
         let decisions = store.decision_get_all()?;
         let refines_links = store.links_of_kind(&crate::model::LinkKind::Refines)?;
 
         let mut app = App {
             store,
+            config,
             view: View::List,
             decisions,
             selected_index: 0,
             detail_scroll: 0,
             filter: FilterState::default(),
             filter_panel_open: false,
+            views,
             tree_nodes: Vec::new(),
             expanded_nodes: HashSet::new(),
             search_query: String::new(),
             selected_decision: None,
             selected_links: Vec::new(),
             refines_links,
+            neighborhood_depth: 0,
+            neighborhood: None,
+            reject_reason: None,
         };
 
         app.refresh_tree();
@@ -158,6 +210,80 @@ impl App {
         Ok(())
     }
 
+    /// Step through saved views as filter presets: none, then each view in
+    /// name order, then back to none.
+    pub fn cycle_view(&mut self) -> Result<()> {
+        if self.views.is_empty() {
+            return Ok(());
+        }
+        let next = match self.filter.view {
+            None => Some(0),
+            Some(ref name) => self
+                .views
+                .iter()
+                .position(|(n, _)| n == name)
+                .map(|i| i + 1)
+                .filter(|&i| i < self.views.len()),
+        };
+        match next {
+            Some(i) => {
+                let (name, view) = &self.views[i];
+                self.filter = FilterState::from_view(name, view);
+                if view.tree {
+                    self.view = View::Tree;
+                    self.selected_index = 0;
+                }
+            }
+            None => self.filter = FilterState::default(),
+        }
+        self.refresh_list()
+    }
+
+    /// Show pending proposals, oldest first.
+    pub fn open_inbox(&mut self) -> Result<()> {
+        self.decisions = proposal::pending(&*self.store, false)?;
+        self.view = View::Inbox;
+        self.selected_index = 0;
+        self.load_selected_decision();
+        Ok(())
+    }
+
+    pub fn accept_selected(&mut self) -> Result<()> {
+        let Some(d) = self.decisions.get(self.selected_index) else {
+            return Ok(());
+        };
+        let edits = AmendArgs {
+            id: d.id.clone(),
+            title: None,
+            body: None,
+            format: None,
+            kind: None,
+            weight: None,
+            rebuttal: None,
+            scope: None,
+        };
+        proposal::accept(&mut *self.store, &self.config, &edits)?;
+        self.reload_inbox()
+    }
+
+    pub fn reject_selected(&mut self, reason: Option<String>) -> Result<()> {
+        let Some(d) = self.decisions.get(self.selected_index) else {
+            return Ok(());
+        };
+        let id = d.id.clone();
+        proposal::reject(&mut *self.store, &id, reason)?;
+        self.reload_inbox()
+    }
+
+    fn reload_inbox(&mut self) -> Result<()> {
+        self.decisions = proposal::pending(&*self.store, false)?;
+        if self.selected_index >= self.decisions.len() {
+            self.selected_index = self.decisions.len().saturating_sub(1);
+        }
+        self.load_selected_decision();
+        Ok(())
+    }
+
     pub fn load_selected_decision(&mut self) {
         let selected_id = match self.view {
             View::Tree => {
@@ -177,11 +303,30 @@ impl App {
                     self.store.links_for_decision(&id).unwrap_or_default();
                 self.selected_decision = Some(d);
                 self.detail_scroll = 0;
+                self.neighborhood_depth = 0;
+                self.neighborhood = None;
                 return;
             }
         }
         self.selected_decision = None;
         self.selected_links = Vec::new();
+        self.neighborhood_depth = 0;
+        self.neighborhood = None;
+    }
+
+    /// Grow or shrink the neighborhood shown in the detail view by `delta` hops.
+    pub fn change_neighborhood_depth(&mut self, delta: i32) -> Result<()> {
+        let Some(ref d) = self.selected_decision else {
+            return Ok(());
+        };
+        let depth = (self.neighborhood_depth as i32 + delta).max(0) as u32;
+        self.neighborhood = if depth == 0 {
+            None
+        } else {
+            Some(self.store.neighborhood(&d.id, depth)?)
+        };
+        self.neighborhood_depth = depth;
+        Ok(())
     }
 
     pub fn refresh_tree(&mut self) {
@@ -219,7 +364,16 @@ impl App {
         if self.search_query.is_empty() {
             self.decisions = self.store.decision_get_all()?;
         } else {
-            self.decisions = self.store.decision_search(&self.search_query)?;
+            // A malformed query (e.g. an unclosed quote) just finds nothing
+            self.decisions = match SearchQuery::parse(&self.search_query) {
+                Ok(query) => self
+                    .store
+                    .decision_search(&query)?
+                    .into_iter()
+                    .map(|hit| hit.decision)
+                    .collect(),
+                Err(_) => Vec::new(),
+            };
         }
         self.selected_index = 0;
         self.load_selected_decision();
@@ -20,6 +20,10 @@ pub fn handle_events(app: &mut App) -> Result<bool> {
             _ => {}
         }
 
+        if app.reject_reason.is_some() {
+            return handle_reason_keys(app, key);
+        }
+
         if app.filter_panel_open {
             return handle_filter_keys(app, key);
         }
@@ -42,12 +46,17 @@ fn handle_normal_keys(app: &mut App, key: KeyEvent) -> Result<bool> {
         KeyCode::Char('G') => app.move_selection(app.item_count() as i32),
 
         KeyCode::Enter => {
-            if !app.decisions.is_empty() || !app.tree_nodes.is_empty() {
+            // The inbox already shows the selected proposal alongside the list
+            if app.view != View::Inbox && (!app.decisions.is_empty() || !app.tree_nodes.is_empty()) {
                 app.view = View::Detail;
                 app.detail_scroll = 0;
             }
         }
 
+        KeyCode::Esc if app.view == View::Inbox => {
+            app.view = View::List;
+            app.refresh_list()?;
+        }
         KeyCode::Esc => {
             if app.view == View::Detail {
                 // Go back to whichever list view was active before
@@ -81,16 +90,40 @@ fn handle_normal_keys(app: &mut App, key: KeyEvent) -> Result<bool> {
             app.search_query.clear();
         }
 
-        KeyCode::Char('f') => {
+        KeyCode::Char('f') if app.view != View::Inbox => {
             app.filter_panel_open = !app.filter_panel_open;
         }
 
+        // Review pending proposals
+        KeyCode::Char('i') => {
+            if app.view == View::Inbox {
+                app.view = View::List;
+                app.refresh_list()?;
+            } else if app.view != View::Detail {
+                app.open_inbox()?;
+            }
+        }
+        KeyCode::Char('a') if app.view == View::Inbox => {
+            app.accept_selected()?;
+        }
+        KeyCode::Char('r') if app.view == View::Inbox && !app.decisions.is_empty() => {
+            app.reject_reason = Some(String::new());
+        }
+
         KeyCode::Char(' ') => {
             if app.view == View::Tree {
                 app.toggle_tree_node();
             }
         }
 
+        // Expand / collapse the neighborhood in the detail view
+        KeyCode::Char('n') if app.view == View::Detail => {
+            app.change_neighborhood_depth(1)?;
+        }
+        KeyCode::Char('N') if app.view == View::Detail => {
+            app.change_neighborhood_depth(-1)?;
+        }
+
         // Scroll detail view
         KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => {
             if app.view == View::Detail {
@@ -134,6 +167,11 @@ fn handle_filter_keys(app: &mut App, key: KeyEvent) -> Result<bool> {
             app.refresh_list()?;
         }
 
+        // Next saved view
+        KeyCode::Char('v') => {
+            app.cycle_view()?;
+        }
+
         // Clear all filters
         KeyCode::Char('0') => {
             app.filter = super::app::FilterState::default();
@@ -145,6 +183,34 @@ fn handle_filter_keys(app: &mut App, key: KeyEvent) -> Result<bool> {
     Ok(false)
 }
 
+fn handle_reason_keys(app: &mut App, key: KeyEvent) -> Result<bool> {
+    match key.code {
+        KeyCode::Esc => {
+            app.reject_reason = None;
+        }
+
+        KeyCode::Enter => {
+            let reason = app.reject_reason.take().filter(|r| !r.trim().is_empty());
+            app.reject_selected(reason)?;
+        }
+
+        KeyCode::Backspace => {
+            if let Some(ref mut reason) = app.reject_reason {
+                reason.pop();
+            }
+        }
+
+        KeyCode::Char(c) => {
+            if let Some(ref mut reason) = app.reject_reason {
+                reason.push(c);
+            }
+        }
+
+        _ => {}
+    }
+    Ok(false)
+}
+
 fn handle_search_keys(app: &mut App, key: KeyEvent) -> Result<bool> {
     match key.code {
         KeyCode::Esc => {
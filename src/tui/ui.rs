@@ -23,6 +23,7 @@ pub fn draw(f: &mut Frame, app: &App) {
         View::Tree => draw_tree_view(f, app, chunks[1]),
         View::Detail => draw_detail_view(f, app, chunks[1]),
         View::Search => draw_search_view(f, app, chunks[1]),
+        View::Inbox => draw_inbox_view(f, app, chunks[1]),
     }
 
     draw_keybind_bar(f, app, chunks[2]);
@@ -36,6 +37,13 @@ fn draw_filter_bar(f: &mut Frame, app: &App, area: Rect) {
     if filter.is_empty() && !app.filter_panel_open {
         spans.push(Span::styled("(none)", Style::default().fg(Color::DarkGray)));
     } else {
+        if let Some(ref v) = filter.view {
+            spans.push(Span::styled(
+                format!(" view:{} ", v),
+                Style::default().bg(Color::White).fg(Color::Black),
+            ));
+            spans.push(Span::raw(" "));
+        }
         if let Some(ref k) = filter.kind {
             spans.push(Span::styled(
                 format!(" kind:{} ", k),
@@ -69,12 +77,26 @@ fn draw_filter_bar(f: &mut Frame, app: &App, area: Rect) {
                 format!(" scope:{} ", sc),
                 Style::default().bg(Color::Yellow).fg(Color::Black),
             ));
+            spans.push(Span::raw(" "));
+        }
+        if let Some(ref l) = filter.label {
+            spans.push(Span::styled(
+                format!(" label:{} ", l),
+                Style::default().bg(Color::Red).fg(Color::White),
+            ));
+            spans.push(Span::raw(" "));
+        }
+        if filter.expr.is_some() {
+            spans.push(Span::styled(
+                " where ",
+                Style::default().bg(Color::DarkGray).fg(Color::White),
+            ));
         }
     }
 
     if app.filter_panel_open {
         spans.push(Span::styled(
-            " | 1:kind 2:weight 3:status 4:level 0:clear ",
+            " | 1:kind 2:weight 3:status 4:level v:view 0:clear ",
             Style::default()
                 .fg(Color::Yellow)
                 .add_modifier(Modifier::BOLD),
@@ -122,6 +144,9 @@ fn draw_list_view(f: &mut Frame, app: &App, area: Rect) {
                     crate::model::decision::Status::Superseded => {
                         Style::default().fg(Color::DarkGray)
                     }
+                    crate::model::decision::Status::Rejected => {
+                        Style::default().fg(Color::DarkGray)
+                    }
                     _ => Style::default(),
                 }
             };
@@ -227,7 +252,11 @@ fn draw_detail_panel(f: &mut Frame, app: &App, area: Rect) {
 
 fn draw_detail_view(f: &mut Frame, app: &App, area: Rect) {
     let content = if let Some(ref d) = app.selected_decision {
-        build_detail_lines(d, &app.selected_links)
+        let mut lines = build_detail_lines(d, &app.selected_links);
+        if let Some(ref neighborhood) = app.neighborhood {
+            lines.extend(build_neighborhood_lines(neighborhood));
+        }
+        lines
     } else {
         vec![Line::styled(
             "No decision selected",
@@ -270,6 +299,16 @@ fn build_detail_lines(d: &crate::model::Decision, links: &[crate::model::Link])
         Line::from(vec![label("Updated"), Span::raw(d.updated_at.clone())]),
     ];
 
+    if let Some(ref origin) = d.origin {
+        let proposed = origin.to_string();
+        if !proposed.is_empty() {
+            lines.push(Line::from(vec![label("Proposed"), Span::raw(proposed)]));
+        }
+        if let Some(ref rejection) = origin.rejection {
+            lines.push(Line::from(vec![label("Rejected"), Span::raw(rejection.clone())]));
+        }
+    }
+
     if let Some(ref scope) = d.scope {
         lines.push(Line::from(vec![label("Scope"), Span::raw(scope.clone())]));
     }
@@ -339,6 +378,131 @@ fn build_detail_lines(d: &crate::model::Decision, links: &[crate::model::Link])
     lines
 }
 
+fn build_neighborhood_lines(neighborhood: &crate::db::Neighborhood) -> Vec<Line<'static>> {
+    let heading = Style::default()
+        .fg(Color::Yellow)
+        .add_modifier(Modifier::BOLD);
+
+    let mut lines = vec![
+        Line::raw(""),
+        Line::styled(format!("Neighborhood (depth {}):", neighborhood.depth), heading),
+    ];
+
+    let mut current_hop = 0;
+    for d in &neighborhood.decisions {
+        let hop = neighborhood.hop_of(&d.id);
+        if hop == 0 {
+            continue;
+        }
+        if hop != current_hop {
+            current_hop = hop;
+            lines.push(Line::styled(
+                format!("  Hop {}", hop),
+                Style::default().fg(Color::Cyan),
+            ));
+        }
+        let style = match d.status {
+            crate::model::decision::Status::Active => Style::default(),
+            _ => Style::default().fg(Color::DarkGray),
+        };
+        lines.push(Line::styled(
+            format!("    {} ({}/{}) {}", d.id, d.kind, d.weight, d.title),
+            style,
+        ));
+    }
+
+    lines
+}
+
+fn draw_inbox_view(f: &mut Frame, app: &App, area: Rect) {
+    let chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(area);
+
+    // Left: pending proposals, oldest first
+    let header = Row::new(vec!["ID", "Kind", "Wt", "Proposed by", "Title"])
+        .style(
+            Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+        )
+        .bottom_margin(1);
+
+    let rows: Vec<Row> = app
+        .decisions
+        .iter()
+        .enumerate()
+        .map(|(i, d)| {
+            let style = if i == app.selected_index {
+                Style::default()
+                    .bg(Color::DarkGray)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+            };
+            let agent = d
+                .origin
+                .as_ref()
+                .and_then(|o| o.agent.clone())
+                .unwrap_or_else(|| d.author.clone());
+            Row::new(vec![
+                Cell::from(d.id.chars().take(8).collect::<String>()),
+                Cell::from(d.kind.to_string()),
+                Cell::from(d.weight.to_string()),
+                Cell::from(agent),
+                Cell::from(d.title.clone()),
+            ])
+            .style(style)
+        })
+        .collect();
+
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(10),
+            Constraint::Length(12),
+            Constraint::Length(8),
+            Constraint::Length(14),
+            Constraint::Min(20),
+        ],
+    )
+    .header(header)
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(format!(" Inbox ({}) ", app.decisions.len())),
+    );
+
+    f.render_widget(table, chunks[0]);
+
+    // Right: detail panel
+    draw_detail_panel(f, app, chunks[1]);
+
+    if let Some(ref reason) = app.reject_reason {
+        let reason_area = Rect {
+            x: area.x + 2,
+            y: area.y + 1,
+            width: area.width.saturating_sub(4).min(60),
+            height: 3,
+        };
+
+        f.render_widget(Clear, reason_area);
+
+        let input = Paragraph::new(Line::from(vec![
+            Span::raw(reason.clone()),
+            Span::styled("_", Style::default().add_modifier(Modifier::SLOW_BLINK)),
+        ]))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(" Reject: reason (optional) ")
+                .border_style(Style::default().fg(Color::Red)),
+        );
+        f.render_widget(input, reason_area);
+    }
+}
+
 fn draw_search_view(f: &mut Frame, app: &App, area: Rect) {
     // Draw the list in the background
     draw_list_view(f, app, area);
@@ -376,6 +540,7 @@ fn draw_keybind_bar(f: &mut Frame, app: &App, area: Rect) {
             ("Tab", "tree"),
             ("/", "search"),
             ("f", "filter"),
+            ("i", "inbox"),
         ],
         View::Tree => vec![
             ("q", "quit"),
@@ -390,11 +555,23 @@ fn draw_keybind_bar(f: &mut Frame, app: &App, area: Rect) {
             ("q", "quit"),
             ("Esc", "back"),
             ("C-d/C-u", "scroll"),
+            ("n/N", "neighborhood"),
         ],
         View::Search => vec![
             ("Enter", "search"),
             ("Esc", "cancel"),
         ],
+        View::Inbox if app.reject_reason.is_some() => vec![
+            ("Enter", "reject"),
+            ("Esc", "cancel"),
+        ],
+        View::Inbox => vec![
+            ("q", "quit"),
+            ("j/k", "navigate"),
+            ("a", "accept"),
+            ("r", "reject"),
+            ("Esc/i", "back"),
+        ],
     };
 
     let spans: Vec<Span> = hints